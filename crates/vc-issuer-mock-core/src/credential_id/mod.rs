@@ -0,0 +1,64 @@
+//! Identifiers of issued credentials.
+
+use uuid::Uuid;
+
+/// Mints `id`s for credentials issued without one.
+///
+/// A minted `id` is `{prefix}{UUID v4}`.
+///
+/// # Example
+///
+/// ```
+/// use vc_issuer_mock_core::credential_id::CredentialIdMinter;
+///
+/// // urn:uuid:<UUID v4>
+/// let minter = CredentialIdMinter::default();
+///
+/// // https://issuer.example/credentials/<UUID v4>
+/// let minter = CredentialIdMinter::new("https://issuer.example/credentials/");
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CredentialIdMinter {
+    prefix: String,
+}
+
+impl CredentialIdMinter {
+    /// Create a minter with the given prefix (e.g. `urn:uuid:` or `https://issuer.example/credentials/`).
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+        }
+    }
+
+    /// Mint a new credential `id`.
+    pub fn mint(&self) -> String {
+        format!("{}{}", self.prefix, Uuid::new_v4())
+    }
+}
+
+impl Default for CredentialIdMinter {
+    fn default() -> Self {
+        Self::new("urn:uuid:")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mint_success_urn_uuid() {
+        let minter = CredentialIdMinter::default();
+        let id = minter.mint();
+        assert!(id.starts_with("urn:uuid:"));
+        assert!(Uuid::parse_str(id.strip_prefix("urn:uuid:").unwrap()).is_ok());
+        assert_ne!(id, minter.mint());
+    }
+
+    #[test]
+    fn test_mint_success_url_prefix() {
+        let minter = CredentialIdMinter::new("https://issuer.example/credentials/");
+        let id = minter.mint();
+        assert!(id.starts_with("https://issuer.example/credentials/"));
+    }
+}
@@ -0,0 +1,60 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard},
+};
+
+use serde_json::Value;
+
+use super::CredentialStore;
+
+/// A [`CredentialStore`] which loses everything on restart.
+#[derive(Debug, Default)]
+pub struct InMemoryCredentialStore {
+    credentials: Mutex<HashMap<String, Value>>,
+}
+
+impl InMemoryCredentialStore {
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, Value>> {
+        self.credentials.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl CredentialStore for InMemoryCredentialStore {
+    fn put(&self, id: &str, credential: Value) -> anyhow::Result<()> {
+        self.lock().insert(id.to_string(), credential);
+        Ok(())
+    }
+
+    fn get(&self, id: &str) -> anyhow::Result<Option<Value>> {
+        Ok(self.lock().get(id).cloned())
+    }
+
+    fn delete(&self, id: &str) -> anyhow::Result<bool> {
+        Ok(self.lock().remove(id).is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_in_memory_credential_store_success() -> anyhow::Result<()> {
+        let store = InMemoryCredentialStore::default();
+        assert_eq!(store.get("urn:uuid:1")?, None);
+
+        store.put("urn:uuid:1", json!({ "id": "urn:uuid:1" }))?;
+        assert_eq!(
+            store.get("urn:uuid:1")?,
+            Some(json!({ "id": "urn:uuid:1" }))
+        );
+
+        assert!(store.delete("urn:uuid:1")?);
+        assert!(!store.delete("urn:uuid:1")?);
+        assert_eq!(store.get("urn:uuid:1")?, None);
+
+        Ok(())
+    }
+}
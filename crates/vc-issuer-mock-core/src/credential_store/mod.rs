@@ -0,0 +1,47 @@
+//! Storage of issued credentials.
+//!
+//! `POST /credentials/issue` records each issued credential under its identifier
+//! (`credentialId` option or the `id` of the credential), which is then served by
+//! `GET /credentials/{id}` and removed by `DELETE /credentials/{id}`.
+//! Credentials without any identifier are not recorded. Use [`CredentialIdMinter`](crate::credential_id::CredentialIdMinter)
+//! to give every credential an `id`.
+//!
+//! Credentials issued by an [`IssuerProfile`](crate::issuer_profile::IssuerProfile) are recorded apart from the others
+//! by [`ScopedCredentialStore`], and served only by `/issuers/{name}/credentials/{id}`.
+
+mod in_memory;
+pub use in_memory::InMemoryCredentialStore;
+
+mod scoped;
+pub use scoped::ScopedCredentialStore;
+
+use std::{fmt, sync::Arc};
+
+use serde_json::Value;
+
+/// A storage of issued credentials.
+///
+/// Credentials are stored in the JSON representation returned from `POST /credentials/issue`.
+pub trait CredentialStore: fmt::Debug + Send + Sync {
+    /// Store `credential` under `id`, replacing the existing one.
+    fn put(&self, id: &str, credential: Value) -> anyhow::Result<()>;
+
+    /// Get the credential stored under `id`.
+    fn get(&self, id: &str) -> anyhow::Result<Option<Value>>;
+
+    /// Delete the credential stored under `id`. Returns `false` if there is no such credential.
+    fn delete(&self, id: &str) -> anyhow::Result<bool>;
+}
+
+/// A [`CredentialStore`] shared among the endpoints.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use vc_issuer_mock_core::credential_store::{DynCredentialStore, InMemoryCredentialStore};
+///
+/// let store: DynCredentialStore = Arc::new(InMemoryCredentialStore::default());
+/// ```
+pub type DynCredentialStore = Arc<dyn CredentialStore>;
@@ -0,0 +1,120 @@
+use serde_json::Value;
+use tracing::warn;
+
+use super::{CredentialStore, DynCredentialStore};
+
+const ISSUERS_PREFIX: &str = "issuers/";
+
+/// A view of a [`CredentialStore`] which keeps credentials of an issuer profile apart from the others.
+///
+/// Credentials of the profile `{name}` are stored under `issuers/{name}/{id}`.
+/// The global view ([`ScopedCredentialStore::global`]) stores credentials under their `id` as is,
+/// and never reads or writes ids under `issuers/`, so that no profile's credential is reachable without `/issuers/{name}`.
+#[derive(Clone, Debug)]
+pub struct ScopedCredentialStore {
+    inner: DynCredentialStore,
+    prefix: Option<String>,
+}
+
+impl ScopedCredentialStore {
+    /// The view for the endpoints without `/issuers/{name}`.
+    pub fn global(inner: DynCredentialStore) -> Self {
+        Self {
+            inner,
+            prefix: None,
+        }
+    }
+
+    /// The view for the endpoints under `/issuers/{profile_name}`.
+    pub fn for_profile(inner: DynCredentialStore, profile_name: &str) -> Self {
+        Self {
+            inner,
+            prefix: Some(format!("{}{}/", ISSUERS_PREFIX, profile_name)),
+        }
+    }
+
+    fn key(&self, id: &str) -> Option<String> {
+        match &self.prefix {
+            Some(prefix) => Some(format!("{}{}", prefix, id)),
+            None if id.starts_with(ISSUERS_PREFIX) => None,
+            None => Some(id.to_string()),
+        }
+    }
+}
+
+impl CredentialStore for ScopedCredentialStore {
+    fn put(&self, id: &str, credential: Value) -> anyhow::Result<()> {
+        match self.key(id) {
+            Some(key) => self.inner.put(&key, credential),
+            None => {
+                warn!(
+                    "Issued credential `{}` is not stored since `{}` is reserved for issuer profiles",
+                    id, ISSUERS_PREFIX
+                );
+                Ok(())
+            }
+        }
+    }
+
+    fn get(&self, id: &str) -> anyhow::Result<Option<Value>> {
+        match self.key(id) {
+            Some(key) => self.inner.get(&key),
+            None => Ok(None),
+        }
+    }
+
+    fn delete(&self, id: &str) -> anyhow::Result<bool> {
+        match self.key(id) {
+            Some(key) => self.inner.delete(&key),
+            None => Ok(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    use crate::credential_store::InMemoryCredentialStore;
+
+    use super::*;
+
+    #[test]
+    fn test_scoped_credential_store_success() -> anyhow::Result<()> {
+        let inner: DynCredentialStore = Arc::new(InMemoryCredentialStore::default());
+        let global = ScopedCredentialStore::global(inner.clone());
+        let university = ScopedCredentialStore::for_profile(inner.clone(), "university");
+        let bank = ScopedCredentialStore::for_profile(inner.clone(), "bank");
+
+        university.put("urn:uuid:1", json!({ "id": "urn:uuid:1" }))?;
+        assert_eq!(
+            university.get("urn:uuid:1")?,
+            Some(json!({ "id": "urn:uuid:1" }))
+        );
+        assert_eq!(
+            inner.get("issuers/university/urn:uuid:1")?,
+            Some(json!({ "id": "urn:uuid:1" }))
+        );
+
+        // other profiles and the global view do not see it
+        assert_eq!(bank.get("urn:uuid:1")?, None);
+        assert!(!bank.delete("urn:uuid:1")?);
+        assert_eq!(global.get("urn:uuid:1")?, None);
+        assert_eq!(global.get("issuers/university/urn:uuid:1")?, None);
+        assert!(!global.delete("issuers/university/urn:uuid:1")?);
+
+        // the global view cannot write into a profile
+        global.put("issuers/university/urn:uuid:1", json!({ "id": "forged" }))?;
+        assert_eq!(
+            university.get("urn:uuid:1")?,
+            Some(json!({ "id": "urn:uuid:1" }))
+        );
+
+        assert!(university.delete("urn:uuid:1")?);
+        assert_eq!(university.get("urn:uuid:1")?, None);
+
+        Ok(())
+    }
+}
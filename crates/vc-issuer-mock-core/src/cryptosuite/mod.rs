@@ -0,0 +1,257 @@
+//! Cryptographic suites that clients can explicitly request via the `type` and `cryptosuite` issue options.
+//!
+//! When no suite is requested, [`AnySuite::pick`] chooses one from the issuer key (see
+//! [`crate::verification_method::VerificationMethod::try_to_suite`]).
+
+use std::fmt;
+
+use anyhow::anyhow;
+use ssi::{jwk::Params, prelude::AnySuite, JWK};
+
+use crate::{
+    endpoints::vc_api::res::vc_api_error::custom_problem_types::CustomProblemType,
+    vcdm_v2::problem_details::ProblemDetails,
+};
+
+/// The only proof `type` that carries a `cryptosuite`.
+///
+/// <https://www.w3.org/TR/vc-data-integrity/#dataintegrityproof>
+pub(crate) const DATA_INTEGRITY_PROOF: &str = "DataIntegrityProof";
+
+/// A [Data Integrity cryptosuite](https://www.w3.org/TR/vc-data-integrity/#cryptographic-suites)
+/// requested by a client.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum RequestedCryptosuite {
+    /// <https://www.w3.org/TR/vc-di-eddsa/#eddsa-rdfc-2022>
+    EddsaRdfc2022,
+    /// <https://www.w3.org/TR/vc-di-eddsa/#eddsa-jcs-2022>
+    EddsaJcs2022,
+    /// <https://www.w3.org/TR/vc-di-ecdsa/#ecdsa-rdfc-2019>
+    EcdsaRdfc2019,
+    /// <https://www.w3.org/TR/vc-di-ecdsa/#ecdsa-jcs-2019>
+    EcdsaJcs2019,
+    /// <https://www.w3.org/TR/vc-di-ecdsa/#ecdsa-sd-2023>
+    EcdsaSd2023,
+    /// <https://www.w3.org/TR/vc-di-bbs/#bbs-2023>
+    Bbs2023,
+}
+
+impl RequestedCryptosuite {
+    /// Parse the `type` and `cryptosuite` issue options.
+    ///
+    /// Returns `Ok(None)` if neither of them is specified.
+    /// `type` can be omitted when `cryptosuite` is specified, and it must be `DataIntegrityProof` otherwise.
+    pub(crate) fn from_options(
+        r#type: Option<&str>,
+        cryptosuite: Option<&str>,
+    ) -> Result<Option<Self>, ProblemDetails> {
+        match (r#type, cryptosuite) {
+            (None, None) => Ok(None),
+            (Some(DATA_INTEGRITY_PROOF) | None, Some(cryptosuite)) => {
+                Self::from_name(cryptosuite).map(Some)
+            }
+            (Some(DATA_INTEGRITY_PROOF), None) => Err(invalid_cryptosuite_error(
+                "`cryptosuite` option is required when `type` is `DataIntegrityProof`".to_string(),
+            )),
+            (Some(r#type), _) => Err(invalid_cryptosuite_error(format!(
+                "unsupported proof type: {}",
+                r#type
+            ))),
+        }
+    }
+
+    pub(crate) fn from_name(name: &str) -> Result<Self, ProblemDetails> {
+        match name {
+            "eddsa-rdfc-2022" => Ok(Self::EddsaRdfc2022),
+            "eddsa-jcs-2022" => Ok(Self::EddsaJcs2022),
+            "ecdsa-rdfc-2019" => Ok(Self::EcdsaRdfc2019),
+            "ecdsa-jcs-2019" => Ok(Self::EcdsaJcs2019),
+            "ecdsa-sd-2023" => Ok(Self::EcdsaSd2023),
+            "bbs-2023" => Ok(Self::Bbs2023),
+            _ => Err(invalid_cryptosuite_error(format!(
+                "unsupported cryptosuite: {}",
+                name
+            ))),
+        }
+    }
+
+    /// The `cryptosuite` name.
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Self::EddsaRdfc2022 => "eddsa-rdfc-2022",
+            Self::EddsaJcs2022 => "eddsa-jcs-2022",
+            Self::EcdsaRdfc2019 => "ecdsa-rdfc-2019",
+            Self::EcdsaJcs2019 => "ecdsa-jcs-2019",
+            Self::EcdsaSd2023 => "ecdsa-sd-2023",
+            Self::Bbs2023 => "bbs-2023",
+        }
+    }
+
+    /// Whether the given (public) key can be used with this cryptosuite.
+    pub(crate) fn supports_key(&self, jwk: &JWK) -> bool {
+        match (self, &jwk.params) {
+            (Self::EddsaRdfc2022 | Self::EddsaJcs2022, Params::OKP(okp)) => okp.curve == "Ed25519",
+            (Self::EcdsaRdfc2019 | Self::EcdsaJcs2019, Params::EC(ec)) => {
+                matches!(ec.curve.as_deref(), Some("P-256" | "P-384"))
+            }
+            // Only P-256 is supported by the `ssi` implementation (P-384 is not).
+            (Self::EcdsaSd2023, Params::EC(ec)) => ec.curve.as_deref() == Some("P-256"),
+            (Self::Bbs2023, Params::EC(ec)) => ec.curve.as_deref() == Some("BLS12381G2"),
+            _ => false,
+        }
+    }
+
+    /// Whether the cryptosuite creates a base proof from which holders derive selective disclosures.
+    pub(crate) fn is_selective_disclosure(&self) -> bool {
+        matches!(self, Self::EcdsaSd2023 | Self::Bbs2023)
+    }
+
+    /// Choose the cryptosuite when `mandatoryPointers` is given, so that the pointers take effect.
+    ///
+    /// Without `requested`, a selective disclosure cryptosuite is picked from the key
+    /// (`ecdsa-sd-2023` for P-256, `bbs-2023` for BLS12-381 G2). P-384 keys have no such suite.
+    /// `Ok(None)` is returned only if no key usable with Data Integrity suites is given, so that the default suite
+    /// of the verification method is used.
+    /// Otherwise, `requested` must be a selective disclosure cryptosuite.
+    ///
+    /// # Errors
+    ///
+    /// `InvalidCryptosuiteError` if `requested` is not a selective disclosure cryptosuite, or the key supports none.
+    pub(crate) fn for_mandatory_pointers(
+        requested: Option<Self>,
+        jwk: Option<&JWK>,
+    ) -> Result<Option<Self>, ProblemDetails> {
+        match requested {
+            Some(requested) if requested.is_selective_disclosure() => Ok(Some(requested)),
+            Some(requested) => Err(invalid_cryptosuite_error(format!(
+                "`mandatoryPointers` option cannot be used with the cryptosuite `{}`",
+                requested
+            ))),
+            None => jwk
+                .map(|jwk| {
+                    [Self::EcdsaSd2023, Self::Bbs2023]
+                        .into_iter()
+                        .find(|suite| suite.supports_key(jwk))
+                        .ok_or_else(|| {
+                            invalid_cryptosuite_error(
+                                "`mandatoryPointers` option requires `ecdsa-sd-2023` (P-256) or `bbs-2023` (BLS12-381 G2), but the issuer key supports neither"
+                                    .to_string(),
+                            )
+                        })
+                })
+                .transpose(),
+        }
+    }
+
+    pub(crate) fn to_any_suite(self) -> AnySuite {
+        match self {
+            Self::EddsaRdfc2022 => AnySuite::EdDsaRdfc2022,
+            Self::EddsaJcs2022 => AnySuite::EdDsaJcs2022,
+            Self::EcdsaRdfc2019 => AnySuite::EcdsaRdfc2019,
+            Self::EcdsaJcs2019 => AnySuite::EcdsaJcs2019,
+            Self::EcdsaSd2023 => AnySuite::EcdsaSd2023,
+            Self::Bbs2023 => AnySuite::Bbs2023,
+        }
+    }
+}
+
+impl fmt::Display for RequestedCryptosuite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+pub(crate) fn invalid_cryptosuite_error(detail: String) -> ProblemDetails {
+    ProblemDetails::new(
+        CustomProblemType::InvalidCryptosuiteError,
+        "invalid cryptosuite error".to_string(),
+        detail.clone(),
+        anyhow!(detail),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vcdm_v2::problem_details::ProblemType as _;
+
+    use super::*;
+
+    #[test]
+    fn test_from_options_none() {
+        assert_eq!(
+            RequestedCryptosuite::from_options(None, None).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_from_options_cryptosuite_only() {
+        assert_eq!(
+            RequestedCryptosuite::from_options(None, Some("eddsa-rdfc-2022")).unwrap(),
+            Some(RequestedCryptosuite::EddsaRdfc2022)
+        );
+        assert_eq!(
+            RequestedCryptosuite::from_options(Some("DataIntegrityProof"), Some("bbs-2023"))
+                .unwrap(),
+            Some(RequestedCryptosuite::Bbs2023)
+        );
+    }
+
+    #[test]
+    fn test_for_mandatory_pointers() {
+        let p256 = JWK::generate_p256();
+        let p384 = JWK::generate_p384();
+        let ed25519 = JWK::generate_ed25519().unwrap();
+
+        assert_eq!(
+            RequestedCryptosuite::for_mandatory_pointers(None, Some(&p256)).unwrap(),
+            Some(RequestedCryptosuite::EcdsaSd2023)
+        );
+        assert_eq!(
+            RequestedCryptosuite::for_mandatory_pointers(
+                Some(RequestedCryptosuite::EcdsaSd2023),
+                Some(&p256)
+            )
+            .unwrap(),
+            Some(RequestedCryptosuite::EcdsaSd2023)
+        );
+        // no Data Integrity key, the default suite is used
+        assert_eq!(
+            RequestedCryptosuite::for_mandatory_pointers(None, None).unwrap(),
+            None
+        );
+        // no selective disclosure suite for the key
+        for jwk in [&ed25519, &p384] {
+            let e = RequestedCryptosuite::for_mandatory_pointers(None, Some(jwk)).unwrap_err();
+            assert_eq!(
+                e.code().unwrap(),
+                CustomProblemType::InvalidCryptosuiteError.code()
+            );
+        }
+
+        let e = RequestedCryptosuite::for_mandatory_pointers(
+            Some(RequestedCryptosuite::EcdsaRdfc2019),
+            Some(&p256),
+        )
+        .unwrap_err();
+        assert_eq!(
+            e.code().unwrap(),
+            CustomProblemType::InvalidCryptosuiteError.code()
+        );
+    }
+
+    #[test]
+    fn test_from_options_error() {
+        for (r#type, cryptosuite) in [
+            (Some("DataIntegrityProof"), None),
+            (Some("Ed25519Signature2018"), None),
+            (None, Some("unknown-2099")),
+        ] {
+            let e = RequestedCryptosuite::from_options(r#type, cryptosuite).unwrap_err();
+            assert_eq!(
+                e.code().unwrap(),
+                CustomProblemType::InvalidCryptosuiteError.code()
+            );
+        }
+    }
+}
@@ -0,0 +1,288 @@
+//! [did:web](https://w3c-ccg.github.io/did-method-web/) DIDs of the mock issuer.
+//!
+//! DID documents are built from [`IssuerKeys`] and hosted by the mock itself:
+//!
+//! - `did:web:{host}` at `/.well-known/did.json`
+//! - `did:web:{host}:dids:{name}` at `/dids/{name}/did.json` (path-based DIDs, all sharing the issuer keys)
+//!
+//! Verification methods are `Multikey`s, except for RSA keys which are `JsonWebKey2020`s.
+//! Their fragments are the [JWK Thumbprints](https://datatracker.ietf.org/doc/html/rfc7638) of the keys.
+//!
+//! The hosted DIDs are resolved locally, without network, by the verification method resolver.
+//!
+//! Path-based DIDs named after [`IssuerProfiles`] list the keys of the profiles instead of the issuer keys.
+
+use anyhow::{anyhow, bail};
+use serde_json::{json, Value};
+use ssi::{jwk::Params, JWK};
+
+use crate::{issuer_keys::VerificationKey, issuer_profile::IssuerProfiles, IssuerKeys};
+
+/// Path segment under which path-based DIDs are hosted.
+pub const PATH_BASED_DIDS_SEGMENT: &str = "dids";
+
+/// did:web DIDs hosted at the base URL of the mock.
+#[derive(Clone, Debug)]
+pub struct DidWeb {
+    /// `did:web:{host}`, with the port percent-encoded.
+    did: String,
+    issuer_profiles: IssuerProfiles,
+}
+
+impl DidWeb {
+    /// Make the did:web for the host of `base_url` (e.g. `http://localhost:40080` -> `did:web:localhost%3A40080`).
+    ///
+    /// # Errors
+    ///
+    /// If `base_url` is not an HTTP(S) URL, or it has a path.
+    pub fn from_base_url(base_url: &str) -> anyhow::Result<Self> {
+        let rest = base_url
+            .strip_prefix("https://")
+            .or_else(|| base_url.strip_prefix("http://"))
+            .ok_or_else(|| anyhow!("base URL must be an HTTP(S) URL: {}", base_url))?;
+        let host = rest.trim_end_matches('/');
+        if host.is_empty() || host.contains(['/', '?', '#']) {
+            bail!("base URL must not have a path: {}", base_url);
+        }
+
+        Ok(Self {
+            did: format!("did:web:{}", host.replace(':', "%3A")),
+            issuer_profiles: IssuerProfiles::default(),
+        })
+    }
+
+    /// List the keys of each issuer profile in the DID document of `did:web:{host}:dids:{profile name}`.
+    pub fn with_issuer_profiles(mut self, issuer_profiles: IssuerProfiles) -> Self {
+        self.issuer_profiles = issuer_profiles;
+        self
+    }
+
+    /// `did:web:{host}`, hosted at `/.well-known/did.json`.
+    pub fn did(&self) -> &str {
+        &self.did
+    }
+
+    /// `did:web:{host}:dids:{name}`, hosted at `/dids/{name}/did.json`.
+    pub fn path_based_did(&self, name: &str) -> String {
+        format!("{}:{}:{}", self.did, PATH_BASED_DIDS_SEGMENT, name)
+    }
+
+    /// Whether the DID (without fragment) is hosted by the mock.
+    pub(crate) fn is_hosted(&self, did: &str) -> bool {
+        did == self.did || self.path_based_name(did).is_some()
+    }
+
+    /// `{name}` of `did:web:{host}:dids:{name}`.
+    fn path_based_name<'a>(&self, did: &'a str) -> Option<&'a str> {
+        did.strip_prefix(&self.did)
+            .and_then(|rest| rest.strip_prefix(&format!(":{}:", PATH_BASED_DIDS_SEGMENT)))
+            .filter(|name| !name.is_empty() && !name.contains(':'))
+    }
+
+    /// Keys of a hosted DID: those of the issuer profile for a path-based DID named after it, `issuer_keys` otherwise.
+    fn keys_of<'a>(&'a self, did: &str, issuer_keys: &'a IssuerKeys) -> &'a IssuerKeys {
+        self.path_based_name(did)
+            .and_then(|name| self.issuer_profiles.get(name))
+            .map_or(issuer_keys, |profile| profile.issuer_keys())
+    }
+
+    /// DID document of a hosted DID.
+    pub fn document(&self, did: &str, issuer_keys: &IssuerKeys) -> Value {
+        let verification_methods = self
+            .keys_of(did, issuer_keys)
+            .key_pairs()
+            .iter()
+            .map(|(_, vk)| verification_method(did, vk))
+            .collect::<Vec<_>>();
+        let ids = verification_methods
+            .iter()
+            .map(|vm| vm["id"].clone())
+            .collect::<Vec<_>>();
+
+        json!({
+            "@context": [
+                "https://www.w3.org/ns/did/v1",
+                "https://w3id.org/security/multikey/v1",
+                "https://w3id.org/security/suites/jws-2020/v1",
+            ],
+            "id": did,
+            "verificationMethod": verification_methods,
+            "authentication": ids,
+            "assertionMethod": ids,
+        })
+    }
+
+    /// Verification method of a hosted DID, in the same form as in [`Self::document`].
+    ///
+    /// `did_url` is either a DID, for which the first verification method is returned, or a DID URL
+    /// with a fragment. Returns `None` if the DID is not hosted or no key matches the fragment.
+    pub(crate) fn verification_method(
+        &self,
+        did_url: &str,
+        issuer_keys: &IssuerKeys,
+    ) -> Option<Value> {
+        let (did, fragment) = match did_url.split_once('#') {
+            Some((did, fragment)) => (did, Some(fragment)),
+            None => (did_url, None),
+        };
+        if !self.is_hosted(did) {
+            return None;
+        }
+
+        self.keys_of(did, issuer_keys)
+            .key_pairs()
+            .iter()
+            .find(|(_, vk)| fragment.map_or(true, |fragment| vk.thumbprint() == fragment))
+            .map(|(_, vk)| verification_method(did, vk))
+    }
+}
+
+fn verification_method(did: &str, vk: &VerificationKey) -> Value {
+    let id = format!("{}#{}", did, vk.thumbprint());
+    match JWK::from(vk).params {
+        Params::RSA(_) => json!({
+            "id": id,
+            "type": "JsonWebKey2020",
+            "controller": did,
+            "publicKeyJwk": serde_json::from_str::<Value>(&vk.to_public_jwk())
+                .expect("verification key should be a JSON object"),
+        }),
+        _ => json!({
+            "id": id,
+            "type": "Multikey",
+            "controller": did,
+            "publicKeyMultibase": vk
+                .to_did_key()
+                .strip_prefix("did:key:")
+                .expect("did:key should start with `did:key:`"),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_jwks::{
+        ISSMOCK_PRIV_EC_P384, ISSMOCK_PRIV_OKP_ED25519, ISSMOCK_PRIV_OKP_ED25519_DIDKEY,
+        JWK_RSA_PRIV,
+    };
+
+    use crate::issuer_profile::IssuerProfile;
+
+    use super::*;
+
+    const DID: &str = "did:web:localhost%3A40080";
+
+    fn did_web() -> DidWeb {
+        DidWeb::from_base_url("http://localhost:40080").unwrap()
+    }
+
+    #[test]
+    fn test_from_base_url_success() {
+        assert_eq!(did_web().did(), DID);
+        assert_eq!(
+            DidWeb::from_base_url("https://issuer.example/")
+                .unwrap()
+                .did(),
+            "did:web:issuer.example"
+        );
+        assert_eq!(
+            did_web().path_based_did("alice"),
+            "did:web:localhost%3A40080:dids:alice"
+        );
+    }
+
+    #[test]
+    fn test_from_base_url_error() {
+        for base_url in [
+            "localhost:40080",
+            "ftp://issuer.example",
+            "https://issuer.example/a",
+        ] {
+            assert!(DidWeb::from_base_url(base_url).is_err(), "{}", base_url);
+        }
+    }
+
+    #[test]
+    fn test_is_hosted() {
+        let did_web = did_web();
+        assert!(did_web.is_hosted(DID));
+        assert!(did_web.is_hosted("did:web:localhost%3A40080:dids:alice"));
+        assert!(!did_web.is_hosted("did:web:localhost%3A40080:dids:"));
+        assert!(!did_web.is_hosted("did:web:localhost%3A40080:other:alice"));
+        assert!(!did_web.is_hosted("did:web:localhost%3A40081"));
+    }
+
+    #[test]
+    fn test_document_success() {
+        let issuer_keys = IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519, JWK_RSA_PRIV]);
+        let doc = did_web().document(DID, &issuer_keys);
+
+        assert_eq!(doc["id"], DID);
+        let vms = doc["verificationMethod"].as_array().unwrap();
+        assert_eq!(vms.len(), 2);
+        assert_eq!(vms[0]["type"], "Multikey");
+        assert_eq!(vms[0]["controller"], DID);
+        assert_eq!(
+            format!("did:key:{}", vms[0]["publicKeyMultibase"].as_str().unwrap()),
+            ISSMOCK_PRIV_OKP_ED25519_DIDKEY
+        );
+        assert_eq!(vms[1]["type"], "JsonWebKey2020");
+        assert!(vms[1]["publicKeyJwk"].get("d").is_none());
+        assert_eq!(doc["assertionMethod"][1], vms[1]["id"]);
+    }
+
+    #[test]
+    fn test_document_success_issuer_profile() {
+        let issuer_keys = IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519]);
+        let profile_keys = IssuerKeys::new(vec![ISSMOCK_PRIV_EC_P384]);
+        let did_web = did_web().with_issuer_profiles(
+            IssuerProfiles::new([IssuerProfile::new("bank", profile_keys.clone()).unwrap()])
+                .unwrap(),
+        );
+        let profile_thumbprint = profile_keys.key_pairs()[0].1.thumbprint();
+
+        let did = did_web.path_based_did("bank");
+        let doc = did_web.document(&did, &issuer_keys);
+        assert_eq!(
+            doc["verificationMethod"][0]["id"],
+            format!("{}#{}", did, profile_thumbprint)
+        );
+        assert_eq!(doc["verificationMethod"].as_array().unwrap().len(), 1);
+
+        // other path-based DIDs still list the issuer keys
+        let doc = did_web.document(&did_web.path_based_did("alice"), &issuer_keys);
+        assert_eq!(
+            format!(
+                "did:key:{}",
+                doc["verificationMethod"][0]["publicKeyMultibase"]
+                    .as_str()
+                    .unwrap()
+            ),
+            ISSMOCK_PRIV_OKP_ED25519_DIDKEY
+        );
+    }
+
+    #[test]
+    fn test_verification_method_success() {
+        let issuer_keys = IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519, ISSMOCK_PRIV_EC_P384]);
+        let did_web = did_web();
+        let p384_thumbprint = issuer_keys.key_pairs()[1].1.thumbprint();
+
+        let vm = did_web.verification_method(DID, &issuer_keys).unwrap();
+        assert_eq!(
+            vm["id"],
+            format!("{}#{}", DID, issuer_keys.key_pairs()[0].1.thumbprint())
+        );
+
+        let did_url = format!("{}#{}", DID, p384_thumbprint);
+        let vm = did_web.verification_method(&did_url, &issuer_keys).unwrap();
+        assert_eq!(vm["id"], did_url);
+
+        assert!(did_web
+            .verification_method(&format!("{}#unknown", DID), &issuer_keys)
+            .is_none());
+        assert!(did_web
+            .verification_method("did:web:issuer.example", &issuer_keys)
+            .is_none());
+    }
+}
@@ -0,0 +1,83 @@
+//! Hosts the DID documents of [`DidWeb`].
+
+use axum::{extract::Path, Extension, Json};
+use serde_json::Value;
+
+use crate::{did_web::DidWeb, IssuerKeys};
+
+/// `GET /.well-known/did.json`
+#[axum::debug_handler]
+pub async fn did_document(
+    Extension(issuer_keys): Extension<IssuerKeys>,
+    Extension(did_web): Extension<DidWeb>,
+) -> Json<Value> {
+    Json(did_web.document(did_web.did(), &issuer_keys))
+}
+
+/// `GET /dids/:name/did.json`
+#[axum::debug_handler]
+pub async fn path_based_did_document(
+    Extension(issuer_keys): Extension<IssuerKeys>,
+    Extension(did_web): Extension<DidWeb>,
+    Path(name): Path<String>,
+) -> Json<Value> {
+    Json(did_web.document(&did_web.path_based_did(&name), &issuer_keys))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        extract::Request,
+        routing::get,
+        Router,
+    };
+    use tower::ServiceExt;
+
+    use crate::{
+        test_jwks::{ISSMOCK_PRIV_EC_P384, ISSMOCK_PRIV_OKP_ED25519},
+        test_tracing::init_tracing,
+    };
+
+    use super::*;
+
+    async fn get_json(uri: &str) -> (http::StatusCode, Value) {
+        init_tracing();
+
+        let app = Router::new()
+            .route("/.well-known/did.json", get(did_document))
+            .route("/dids/:name/did.json", get(path_based_did_document))
+            .layer(Extension(IssuerKeys::new(vec![
+                ISSMOCK_PRIV_OKP_ED25519,
+                ISSMOCK_PRIV_EC_P384,
+            ])))
+            .layer(Extension(
+                DidWeb::from_base_url("http://localhost:40080").unwrap(),
+            ));
+
+        let req = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let res = app.oneshot(req).await.unwrap();
+        let status = res.status();
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    #[tokio::test]
+    async fn test_did_document_success() {
+        let (status, json) = get_json("/.well-known/did.json").await;
+        assert_eq!(status, 200);
+        assert_eq!(json["id"], "did:web:localhost%3A40080");
+        assert_eq!(json["verificationMethod"].as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_path_based_did_document_success() {
+        let (status, json) = get_json("/dids/alice/did.json").await;
+        assert_eq!(status, 200);
+        assert_eq!(json["id"], "did:web:localhost%3A40080:dids:alice");
+        assert_eq!(
+            json["verificationMethod"][0]["controller"],
+            "did:web:localhost%3A40080:dids:alice"
+        );
+    }
+}
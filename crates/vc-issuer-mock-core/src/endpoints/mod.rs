@@ -1,4 +1,7 @@
 pub mod success_res;
 pub use success_res::SuccessRes;
 
+pub mod did_web;
+pub mod oid4vci;
+pub mod status_list;
 pub mod vc_api;
@@ -0,0 +1,303 @@
+//! Implements the following endpoints of the built-in authorization server for the
+//! [authorization code flow](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0.html#name-authorization-code-flow):
+//!
+//! - `GET /authorize`: Authorization endpoint. Redirects back to `redirect_uri` right away.
+//! - `POST /par`: [Pushed authorization request endpoint](https://datatracker.ietf.org/doc/html/rfc9126).
+//! - `GET /jwks`: JWK Set to verify access tokens.
+//! - `POST /authorize/consent`: Mock-specific. Script how the mock user answers upcoming
+//!   authorization requests, e.g. `{"decisions": ["deny"]}` to test how a wallet handles
+//!   `error=access_denied`.
+//!
+//! Errors in the authorization request itself (e.g. missing PKCE parameters) are returned as
+//! error responses instead of redirects, so that they show up in tests.
+
+use axum::{
+    extract::{
+        rejection::{FormRejection, JsonRejection, QueryRejection},
+        Query,
+    },
+    response::{IntoResponse, Response},
+    Extension, Form, Json,
+};
+use http::{header, HeaderValue};
+use serde_json::Value;
+
+use crate::{
+    endpoints::oid4vci::{
+        req::ScriptConsentRequest,
+        res::{
+            oid4vci_error::{Oid4vciError, Oid4vciErrorCode},
+            PushedAuthorizationResponse,
+        },
+    },
+    oid4vci::{
+        access_token::jwks as access_token_jwks, authorization::AuthorizationRequest, Oid4vciIssuer,
+    },
+    IssuerKeys,
+};
+
+/// `GET /authorize`
+#[axum::debug_handler]
+pub async fn authorize(
+    Extension(issuer): Extension<Oid4vciIssuer>,
+    req: Result<Query<AuthorizationRequest>, QueryRejection>,
+) -> Result<Response, Oid4vciError> {
+    let Query(req) = req
+        .map_err(|e| Oid4vciError::bad_request(Oid4vciErrorCode::InvalidRequest, e.body_text()))?;
+
+    let location = issuer.authorize(req)?;
+    let location = HeaderValue::try_from(location).map_err(anyhow::Error::from)?;
+    Ok((http::StatusCode::FOUND, [(header::LOCATION, location)]).into_response())
+}
+
+/// `POST /par`
+#[axum::debug_handler]
+pub async fn pushed_authorization_request(
+    Extension(issuer): Extension<Oid4vciIssuer>,
+    req: Result<Form<AuthorizationRequest>, FormRejection>,
+) -> Result<(http::StatusCode, Json<PushedAuthorizationResponse>), Oid4vciError> {
+    let Form(req) = req
+        .map_err(|e| Oid4vciError::bad_request(Oid4vciErrorCode::InvalidRequest, e.body_text()))?;
+
+    let (request_uri, expires_in) = issuer.push_authorization_request(req)?;
+    Ok((
+        http::StatusCode::CREATED,
+        Json(PushedAuthorizationResponse {
+            request_uri,
+            expires_in,
+        }),
+    ))
+}
+
+/// `GET /jwks`
+#[axum::debug_handler]
+pub async fn jwks(Extension(issuer_keys): Extension<IssuerKeys>) -> Json<Value> {
+    Json(access_token_jwks(&issuer_keys))
+}
+
+/// `POST /authorize/consent`
+#[axum::debug_handler]
+pub async fn script_consent(
+    Extension(issuer): Extension<Oid4vciIssuer>,
+    req: Result<Json<ScriptConsentRequest>, JsonRejection>,
+) -> Result<http::StatusCode, Oid4vciError> {
+    let Json(req) = req
+        .map_err(|e| Oid4vciError::bad_request(Oid4vciErrorCode::InvalidRequest, e.body_text()))?;
+
+    issuer.script_consent(req.decisions);
+    Ok(http::StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        extract::Request,
+        routing::{get, post},
+        Router,
+    };
+    use percent_encoding::{percent_decode_str, utf8_percent_encode, NON_ALPHANUMERIC};
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    use crate::{
+        endpoints::oid4vci::{credential_issuer::credential, token::token},
+        oid4vci::{
+            authorization::tests::{CODE_CHALLENGE, CODE_VERIFIER},
+            proof::tests::proof_jwt,
+        },
+        test_jwks::{ISSMOCK_PRIV_EC_P384, ISSMOCK_PRIV_OKP_ED25519, JWK_EC_P384_PRIV},
+        test_tracing::init_tracing,
+    };
+
+    use super::*;
+
+    const REDIRECT_URI: &str = "http://localhost/cb";
+
+    fn app(issuer: Oid4vciIssuer) -> Router {
+        init_tracing();
+
+        Router::new()
+            .route("/authorize", get(authorize))
+            .route("/par", post(pushed_authorization_request))
+            .route("/jwks", get(jwks))
+            .route("/authorize/consent", post(script_consent))
+            .route("/token", post(token))
+            .route("/credential", post(credential))
+            .layer(Extension(IssuerKeys::new(vec![
+                ISSMOCK_PRIV_OKP_ED25519,
+                ISSMOCK_PRIV_EC_P384,
+            ])))
+            .layer(Extension(issuer))
+    }
+
+    async fn call(app: &Router, req: Request<Body>) -> (http::StatusCode, Option<String>, Value) {
+        let res = app.clone().oneshot(req).await.unwrap();
+        let status = res.status();
+        let location = res
+            .headers()
+            .get(header::LOCATION)
+            .map(|location| location.to_str().unwrap().to_string());
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let json = serde_json::from_slice(&body).unwrap_or(Value::Null);
+        (status, location, json)
+    }
+
+    fn form_req(uri: &str, form: &str) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("content-type", "application/x-www-form-urlencoded")
+            .body(Body::from(form.to_string()))
+            .unwrap()
+    }
+
+    fn get_req(uri: &str) -> Request<Body> {
+        Request::builder().uri(uri).body(Body::empty()).unwrap()
+    }
+
+    /// Push an authorization request for `ExampleCredential_ldp_vc` and return `request_uri`.
+    async fn par(app: &Router) -> String {
+        let form = format!(
+            "response_type=code&client_id=wallet&redirect_uri=http%3A%2F%2Flocalhost%2Fcb&state=xyz\
+             &scope=ExampleCredential_ldp_vc&code_challenge={}&code_challenge_method=S256",
+            CODE_CHALLENGE
+        );
+        let (status, _, json) = call(app, form_req("/par", &form)).await;
+        assert_eq!(status, 201);
+        json["request_uri"].as_str().unwrap().to_string()
+    }
+
+    fn authorize_uri(request_uri: &str) -> String {
+        format!(
+            "/authorize?client_id=wallet&request_uri={}",
+            utf8_percent_encode(request_uri, NON_ALPHANUMERIC)
+        )
+    }
+
+    fn query_param(location: &str, name: &str) -> Option<String> {
+        let (_, query) = location.split_once('?')?;
+        query
+            .split('&')
+            .filter_map(|param| param.split_once('='))
+            .find(|(n, _)| *n == name)
+            .map(|(_, value)| {
+                percent_decode_str(value)
+                    .decode_utf8()
+                    .unwrap()
+                    .into_owned()
+            })
+    }
+
+    fn token_form(code: &str, code_verifier: &str) -> String {
+        format!(
+            "grant_type=authorization_code&code={}&client_id=wallet\
+             &redirect_uri=http%3A%2F%2Flocalhost%2Fcb&code_verifier={}",
+            code, code_verifier
+        )
+    }
+
+    #[tokio::test]
+    async fn test_authorization_code_flow_success() {
+        let issuer = Oid4vciIssuer::new("http://localhost:40080");
+        let app = app(issuer.clone());
+
+        let request_uri = par(&app).await;
+        let (status, location, _) = call(&app, get_req(&authorize_uri(&request_uri))).await;
+        assert_eq!(status, 302);
+        let location = location.unwrap();
+        assert!(location.starts_with(REDIRECT_URI));
+        assert_eq!(query_param(&location, "state").unwrap(), "xyz");
+        assert_eq!(
+            query_param(&location, "iss").unwrap(),
+            "http://localhost:40080"
+        );
+        let code = query_param(&location, "code").unwrap();
+
+        let (status, _, json) =
+            call(&app, form_req("/token", &token_form(&code, CODE_VERIFIER))).await;
+        assert_eq!(status, 200);
+        let access_token = json["access_token"].as_str().unwrap();
+        let nonce = json["c_nonce"].as_str().unwrap();
+
+        let req = Request::builder()
+            .method("POST")
+            .uri("/credential")
+            .header("content-type", "application/json")
+            .header("authorization", format!("Bearer {}", access_token))
+            .body(Body::from(
+                json!({
+                    "credential_configuration_id": "ExampleCredential_ldp_vc",
+                    "proof": {
+                        "proof_type": "jwt",
+                        "jwt": proof_jwt(&issuer, JWK_EC_P384_PRIV, None, Some(nonce)),
+                    },
+                })
+                .to_string(),
+            ))
+            .unwrap();
+        let (status, _, json) = call(&app, req).await;
+        assert_eq!(status, 200);
+        assert!(json["credentials"][0]["credential"]["proof"].is_object());
+    }
+
+    #[tokio::test]
+    async fn test_authorize_success_denied() {
+        let issuer = Oid4vciIssuer::new("http://localhost:40080");
+        let app = app(issuer);
+
+        let req = Request::builder()
+            .method("POST")
+            .uri("/authorize/consent")
+            .header("content-type", "application/json")
+            .body(Body::from(json!({ "decisions": ["deny"] }).to_string()))
+            .unwrap();
+        let (status, _, _) = call(&app, req).await;
+        assert_eq!(status, 204);
+
+        let request_uri = par(&app).await;
+        let (status, location, _) = call(&app, get_req(&authorize_uri(&request_uri))).await;
+        assert_eq!(status, 302);
+        let location = location.unwrap();
+        assert_eq!(query_param(&location, "error").unwrap(), "access_denied");
+        assert!(query_param(&location, "code").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_authorize_error_request_uri_reused() {
+        let issuer = Oid4vciIssuer::new("http://localhost:40080");
+        let app = app(issuer);
+
+        let request_uri = par(&app).await;
+        let (status, _, _) = call(&app, get_req(&authorize_uri(&request_uri))).await;
+        assert_eq!(status, 302);
+
+        let (status, _, json) = call(&app, get_req(&authorize_uri(&request_uri))).await;
+        assert_eq!(status, 400);
+        assert_eq!(json["error"], "invalid_request");
+    }
+
+    #[tokio::test]
+    async fn test_token_error_wrong_code_verifier() {
+        let issuer = Oid4vciIssuer::new("http://localhost:40080");
+        let app = app(issuer);
+
+        let request_uri = par(&app).await;
+        let (_, location, _) = call(&app, get_req(&authorize_uri(&request_uri))).await;
+        let code = query_param(&location.unwrap(), "code").unwrap();
+
+        let (status, _, json) = call(&app, form_req("/token", &token_form(&code, "wrong"))).await;
+        assert_eq!(status, 400);
+        assert_eq!(json["error"], "invalid_grant");
+    }
+
+    #[tokio::test]
+    async fn test_jwks_success() {
+        let issuer = Oid4vciIssuer::new("http://localhost:40080");
+        let app = app(issuer);
+
+        let (status, _, json) = call(&app, get_req("/jwks")).await;
+        assert_eq!(status, 200);
+        assert_eq!(json["keys"].as_array().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,574 @@
+//! Implements the following endpoints of an OID4VCI Credential Issuer:
+//!
+//! - `GET /.well-known/openid-credential-issuer`
+//! - `POST /nonce`
+//! - `POST /credential`
+//!
+//! The credential endpoint requires an access token issued by the token endpoint
+//! ([`crate::endpoints::oid4vci::token`]).
+//! Credentials are issued to the holder proving possession of its key with a JWT proof,
+//! one credential per proof when a batch is requested in `proofs`.
+//! They may be deferred to [`crate::endpoints::oid4vci::deferred`].
+//! `ldp_vc` credentials are secured with Data Integrity proofs in the same way as `POST /credentials/issue`.
+
+use anyhow::Context as _;
+use axum::{
+    extract::rejection::JsonRejection,
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use chrono::{SecondsFormat, Utc};
+use http::{header, HeaderMap, HeaderValue};
+use serde_json::{json, Value};
+
+use crate::{
+    endpoints::{
+        oid4vci::{
+            bearer_token,
+            req::CredentialRequest,
+            res::{
+                oid4vci_error::{Oid4vciError, Oid4vciErrorCode},
+                CredentialResponse, NonceResponse,
+            },
+        },
+        vc_api::{
+            credentials::create_vc_with_data_integrity,
+            req::IssueRequestOptions,
+            res::{AnyVerifiableCredential, VerifiableCredentialV2},
+        },
+    },
+    oid4vci::{
+        access_token::AccessToken,
+        proof::{verify_jwt_proofs, HolderKey},
+        CredentialConfiguration, CredentialFormat, Oid4vciIssuer,
+    },
+    sd_jwt::create_sd_jwt_vc,
+    verification_method::CustomVerificationMethodResolver,
+    IssuerKeys,
+};
+
+/// `GET /.well-known/openid-credential-issuer`
+#[axum::debug_handler]
+pub async fn credential_issuer_metadata(
+    Extension(issuer): Extension<Oid4vciIssuer>,
+) -> Json<Value> {
+    Json(issuer.metadata())
+}
+
+/// `POST /nonce`
+#[axum::debug_handler]
+pub async fn nonce(Extension(issuer): Extension<Oid4vciIssuer>) -> Response {
+    (
+        [(header::CACHE_CONTROL, HeaderValue::from_static("no-store"))],
+        Json(NonceResponse {
+            c_nonce: issuer.new_nonce(),
+        }),
+    )
+        .into_response()
+}
+
+/// `POST /credential`
+#[axum::debug_handler]
+pub async fn credential(
+    Extension(issuer_keys): Extension<IssuerKeys>,
+    Extension(issuer): Extension<Oid4vciIssuer>,
+    headers: HeaderMap,
+    req: Result<Json<CredentialRequest>, JsonRejection>,
+) -> Result<(http::StatusCode, Json<CredentialResponse>), Oid4vciError> {
+    let token = bearer_token(&headers)
+        .ok_or_else(|| Oid4vciError::invalid_token("access token is missing"))?;
+    let access_token = issuer.access_token(&issuer_keys, token)?;
+
+    let Json(req) = req.map_err(|e| {
+        Oid4vciError::bad_request(Oid4vciErrorCode::InvalidCredentialRequest, e.body_text())
+    })?;
+    let (status, res) = issue_credential(&issuer_keys, &issuer, &access_token, &req).await?;
+    Ok((status, Json(res)))
+}
+
+async fn issue_credential(
+    issuer_keys: &IssuerKeys,
+    issuer: &Oid4vciIssuer,
+    access_token: &AccessToken,
+    req: &CredentialRequest,
+) -> Result<(http::StatusCode, CredentialResponse), Oid4vciError> {
+    let configuration = issuer
+        .configuration(&req.credential_configuration_id)
+        .ok_or_else(|| {
+            Oid4vciError::bad_request(
+                Oid4vciErrorCode::UnknownCredentialConfiguration,
+                format!(
+                    "unknown credential configuration: {}",
+                    req.credential_configuration_id
+                ),
+            )
+        })?;
+    if !access_token
+        .credential_configuration_ids
+        .contains(&req.credential_configuration_id)
+    {
+        return Err(Oid4vciError::insufficient_scope(format!(
+            "access token is not authorized for the credential configuration: {}",
+            req.credential_configuration_id
+        )));
+    }
+
+    let proof_jwts = match req.jwt_proofs() {
+        Some(proof_jwts) if (1..=issuer.batch_size()).contains(&proof_jwts.len()) => proof_jwts,
+        _ => {
+            return Err(Oid4vciError::bad_request(
+                Oid4vciErrorCode::InvalidCredentialRequest,
+                format!(
+                    "1 to {} JWT proofs are required in either `proof` or `proofs`",
+                    issuer.batch_size()
+                ),
+            ))
+        }
+    };
+    let holder_keys = verify_jwt_proofs(&proof_jwts, issuer_keys, issuer).await?;
+
+    let mut credentials = Vec::with_capacity(holder_keys.len());
+    for holder_key in &holder_keys {
+        credentials.push(issue_for_holder(issuer_keys, configuration, holder_key).await?);
+    }
+    let delivery = issuer.deliver(&req.credential_configuration_id, credentials);
+    Ok(CredentialResponse::from_delivery(delivery))
+}
+
+/// Issue a credential of `configuration` to the holder.
+async fn issue_for_holder(
+    issuer_keys: &IssuerKeys,
+    configuration: &CredentialConfiguration,
+    holder_key: &HolderKey,
+) -> Result<Value, Oid4vciError> {
+    let mut credential_subject = configuration.claims.clone();
+    match (&holder_key.did, configuration.format) {
+        (Some(did), _) => {
+            credential_subject.insert("id".to_string(), Value::String(did.clone()));
+        }
+        // SD-JWT VCs are bound to the holder key with `cnf.jwk` anyway.
+        (None, CredentialFormat::DcSdJwt) => {}
+        (None, CredentialFormat::LdpVc) => return Err(Oid4vciError::bad_request(
+            Oid4vciErrorCode::InvalidProof,
+            "holder key cannot be expressed as a DID, and cannot be bound to `ldp_vc` credentials",
+        )),
+    }
+
+    let credential: VerifiableCredentialV2 = serde_json::from_value(json!({
+        "@context": configuration.contexts(),
+        "type": configuration.types(),
+        "issuer": issuer_keys.preferred_did_key(),
+        "validFrom": Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        "credentialSubject": credential_subject,
+    }))
+    .context("Failed to build a credential from the credential configuration")?;
+
+    let vm_resolver = CustomVerificationMethodResolver::new(issuer_keys.clone());
+    let vm = vm_resolver.resolve(&credential.issuer).await?;
+
+    let credential = match configuration.format {
+        CredentialFormat::LdpVc => {
+            let vc = create_vc_with_data_integrity(
+                credential,
+                &IssueRequestOptions::default(),
+                issuer_keys.clone(),
+                &vm,
+                &vm_resolver,
+            )
+            .await?;
+            serde_json::to_value(vc).expect("issued credential should be serialized into JSON")
+        }
+        CredentialFormat::DcSdJwt => {
+            let signing_key = vm.find_signing_key(issuer_keys)?;
+            let sd_jwt_vc = create_sd_jwt_vc(
+                &AnyVerifiableCredential::V2(credential),
+                &[],
+                Some(&holder_key.jwk),
+                &signing_key,
+                vm.to_id_iri().as_str(),
+            )?;
+            Value::String(sd_jwt_vc.sd_jwt)
+        }
+    };
+    Ok(credential)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        extract::Request,
+        routing::{get, post},
+        Router,
+    };
+    use tower::ServiceExt;
+
+    use crate::{
+        oid4vci::proof::tests::proof_jwt,
+        test_jwks::{ISSMOCK_PRIV_EC_P384, ISSMOCK_PRIV_OKP_ED25519, JWK_EC_P384_PRIV},
+        test_tracing::init_tracing,
+    };
+
+    use super::*;
+
+    fn setup() -> (IssuerKeys, Oid4vciIssuer) {
+        init_tracing();
+        (
+            IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519, ISSMOCK_PRIV_EC_P384]),
+            Oid4vciIssuer::new("http://localhost:40080"),
+        )
+    }
+
+    fn app(issuer_keys: IssuerKeys, issuer: Oid4vciIssuer) -> Router {
+        Router::new()
+            .route(
+                "/.well-known/openid-credential-issuer",
+                get(credential_issuer_metadata),
+            )
+            .route("/nonce", post(nonce))
+            .route("/credential", post(credential))
+            .layer(Extension(issuer_keys))
+            .layer(Extension(issuer))
+    }
+
+    async fn call(app: Router, req: Request<Body>) -> (http::StatusCode, HeaderValue, Value) {
+        let res = app.oneshot(req).await.unwrap();
+        let status = res.status();
+        let cache_control = res
+            .headers()
+            .get(header::CACHE_CONTROL)
+            .cloned()
+            .unwrap_or(HeaderValue::from_static(""));
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        (
+            status,
+            cache_control,
+            serde_json::from_slice(&body).unwrap(),
+        )
+    }
+
+    /// An access token for `ExampleCredential_ldp_vc` and `ExampleCredential_dc+sd-jwt`.
+    pub(crate) fn access_token(issuer_keys: &IssuerKeys, issuer: &Oid4vciIssuer) -> String {
+        let created = issuer
+            .create_credential_offer(
+                vec![
+                    "ExampleCredential_ldp_vc".to_string(),
+                    "ExampleCredential_dc+sd-jwt".to_string(),
+                ],
+                None,
+            )
+            .unwrap();
+        let code = created
+            .credential_offer
+            .grants
+            .pre_authorized_code
+            .pre_authorized_code;
+        issuer
+            .exchange_pre_authorized_code(issuer_keys, &code, None)
+            .unwrap()
+    }
+
+    fn credential_req(
+        issuer_keys: &IssuerKeys,
+        issuer: &Oid4vciIssuer,
+        body: Value,
+    ) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri("/credential")
+            .header("content-type", "application/json")
+            .header(
+                "authorization",
+                format!("Bearer {}", access_token(issuer_keys, issuer)),
+            )
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_credential_issuer_metadata_success() {
+        let (issuer_keys, issuer) = setup();
+        let req = Request::builder()
+            .uri("/.well-known/openid-credential-issuer")
+            .body(Body::empty())
+            .unwrap();
+
+        let (status, _, json) = call(app(issuer_keys, issuer), req).await;
+        assert_eq!(status, 200);
+        assert_eq!(json["credential_issuer"], "http://localhost:40080");
+    }
+
+    #[tokio::test]
+    async fn test_nonce_success() {
+        let (issuer_keys, issuer) = setup();
+        let req = Request::builder()
+            .method("POST")
+            .uri("/nonce")
+            .body(Body::empty())
+            .unwrap();
+
+        let (status, cache_control, json) = call(app(issuer_keys, issuer.clone()), req).await;
+        assert_eq!(status, 200);
+        assert_eq!(cache_control, "no-store");
+        assert!(issuer.consume_nonce(json["c_nonce"].as_str().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_credential_success_ldp_vc() {
+        let (issuer_keys, issuer) = setup();
+        let nonce = issuer.new_nonce();
+        let req = credential_req(
+            &issuer_keys,
+            &issuer,
+            json!({
+                "credential_configuration_id": "ExampleCredential_ldp_vc",
+                "proof": {
+                    "proof_type": "jwt",
+                    "jwt": proof_jwt(&issuer, JWK_EC_P384_PRIV, None, Some(&nonce)),
+                },
+            }),
+        );
+
+        let (status, _, json) = call(app(issuer_keys, issuer), req).await;
+        assert_eq!(status, 200);
+
+        let vc = &json["credentials"][0]["credential"];
+        assert_eq!(
+            vc["type"],
+            json!(["VerifiableCredential", "ExampleCredential"])
+        );
+        assert!(vc["credentialSubject"]["id"]
+            .as_str()
+            .unwrap()
+            .starts_with("did:key:"));
+        assert_eq!(vc["credentialSubject"]["given_name"], "Taro");
+        assert!(vc["proof"].is_object());
+    }
+
+    #[tokio::test]
+    async fn test_credential_success_sd_jwt() {
+        let (issuer_keys, issuer) = setup();
+        let nonce = issuer.new_nonce();
+        let req = credential_req(
+            &issuer_keys,
+            &issuer,
+            json!({
+                "credential_configuration_id": "ExampleCredential_dc+sd-jwt",
+                "proofs": {
+                    "jwt": [proof_jwt(&issuer, JWK_EC_P384_PRIV, None, Some(&nonce))],
+                },
+            }),
+        );
+
+        let (status, _, json) = call(app(issuer_keys, issuer), req).await;
+        assert_eq!(status, 200);
+
+        let sd_jwt = json["credentials"][0]["credential"].as_str().unwrap();
+        // 2 disclosures (`given_name` and `family_name`)
+        assert_eq!(sd_jwt.split('~').count(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_credential_success_batch() {
+        let (issuer_keys, issuer) = setup();
+        let nonce = issuer.new_nonce();
+        let req = credential_req(
+            &issuer_keys,
+            &issuer,
+            json!({
+                "credential_configuration_id": "ExampleCredential_ldp_vc",
+                "proofs": {
+                    "jwt": [
+                        proof_jwt(&issuer, JWK_EC_P384_PRIV, None, Some(&nonce)),
+                        proof_jwt(&issuer, ISSMOCK_PRIV_OKP_ED25519, None, Some(&nonce)),
+                    ],
+                },
+            }),
+        );
+
+        let (status, _, json) = call(app(issuer_keys, issuer), req).await;
+        assert_eq!(status, 200);
+
+        let credentials = json["credentials"].as_array().unwrap();
+        assert_eq!(credentials.len(), 2);
+        assert_ne!(
+            credentials[0]["credential"]["credentialSubject"]["id"],
+            credentials[1]["credential"]["credentialSubject"]["id"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_credential_error_batch_too_large() {
+        let (issuer_keys, issuer) = setup();
+        let issuer = issuer.with_batch_size(1);
+        let nonce = issuer.new_nonce();
+        let req = credential_req(
+            &issuer_keys,
+            &issuer,
+            json!({
+                "credential_configuration_id": "ExampleCredential_ldp_vc",
+                "proofs": {
+                    "jwt": [
+                        proof_jwt(&issuer, JWK_EC_P384_PRIV, None, Some(&nonce)),
+                        proof_jwt(&issuer, JWK_EC_P384_PRIV, None, Some(&nonce)),
+                    ],
+                },
+            }),
+        );
+
+        let (status, _, json) = call(app(issuer_keys, issuer), req).await;
+        assert_eq!(status, 400);
+        assert_eq!(json["error"], "invalid_credential_request");
+    }
+
+    #[tokio::test]
+    async fn test_credential_error_unknown_configuration() {
+        let (issuer_keys, issuer) = setup();
+        let nonce = issuer.new_nonce();
+        let req = credential_req(
+            &issuer_keys,
+            &issuer,
+            json!({
+                "credential_configuration_id": "UnknownCredential",
+                "proof": {
+                    "proof_type": "jwt",
+                    "jwt": proof_jwt(&issuer, JWK_EC_P384_PRIV, None, Some(&nonce)),
+                },
+            }),
+        );
+
+        let (status, cache_control, json) = call(app(issuer_keys, issuer), req).await;
+        assert_eq!(status, 400);
+        assert_eq!(cache_control, "no-store");
+        assert_eq!(json["error"], "unknown_credential_configuration");
+    }
+
+    #[tokio::test]
+    async fn test_credential_error_no_proof() {
+        let (issuer_keys, issuer) = setup();
+        let req = credential_req(
+            &issuer_keys,
+            &issuer,
+            json!({
+                "credential_configuration_id": "ExampleCredential_ldp_vc",
+            }),
+        );
+
+        let (status, _, json) = call(app(issuer_keys, issuer), req).await;
+        assert_eq!(status, 400);
+        assert_eq!(json["error"], "invalid_credential_request");
+    }
+
+    #[tokio::test]
+    async fn test_credential_error_invalid_nonce() {
+        let (issuer_keys, issuer) = setup();
+        let req = credential_req(
+            &issuer_keys,
+            &issuer,
+            json!({
+                "credential_configuration_id": "ExampleCredential_ldp_vc",
+                "proof": {
+                    "proof_type": "jwt",
+                    "jwt": proof_jwt(&issuer, JWK_EC_P384_PRIV, None, Some("not-issued")),
+                },
+            }),
+        );
+
+        let (status, _, json) = call(app(issuer_keys, issuer), req).await;
+        assert_eq!(status, 400);
+        assert_eq!(json["error"], "invalid_nonce");
+    }
+
+    #[tokio::test]
+    async fn test_credential_error_not_json() {
+        let (issuer_keys, issuer) = setup();
+        let req = Request::builder()
+            .method("POST")
+            .uri("/credential")
+            .header("content-type", "application/json")
+            .header(
+                "authorization",
+                format!("Bearer {}", access_token(&issuer_keys, &issuer)),
+            )
+            .body(Body::from("INVALID-AS-JSON"))
+            .unwrap();
+
+        let (status, _, json) = call(app(issuer_keys, issuer), req).await;
+        assert_eq!(status, 400);
+        assert_eq!(json["error"], "invalid_credential_request");
+    }
+
+    #[tokio::test]
+    async fn test_credential_error_no_access_token() {
+        let (issuer_keys, issuer) = setup();
+        let nonce = issuer.new_nonce();
+        let req = Request::builder()
+            .method("POST")
+            .uri("/credential")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({
+                    "credential_configuration_id": "ExampleCredential_ldp_vc",
+                    "proof": {
+                        "proof_type": "jwt",
+                        "jwt": proof_jwt(&issuer, JWK_EC_P384_PRIV, None, Some(&nonce)),
+                    },
+                })
+                .to_string(),
+            ))
+            .unwrap();
+
+        let res = app(issuer_keys, issuer).oneshot(req).await.unwrap();
+        assert_eq!(res.status(), 401);
+        assert_eq!(
+            res.headers().get(header::WWW_AUTHENTICATE).unwrap(),
+            "Bearer error=\"invalid_token\""
+        );
+    }
+
+    #[tokio::test]
+    async fn test_credential_error_forged_access_token() {
+        let (issuer_keys, issuer) = setup();
+        let req = Request::builder()
+            .method("POST")
+            .uri("/credential")
+            .header("content-type", "application/json")
+            .header(
+                "authorization",
+                format!("Bearer {}", access_token(&IssuerKeys::default(), &issuer)),
+            )
+            .body(Body::from(
+                json!({ "credential_configuration_id": "ExampleCredential_ldp_vc" }).to_string(),
+            ))
+            .unwrap();
+
+        let (status, _, json) = call(app(issuer_keys, issuer), req).await;
+        assert_eq!(status, 401);
+        assert_eq!(json["error"], "invalid_token");
+    }
+
+    #[tokio::test]
+    async fn test_credential_error_not_authorized_configuration() {
+        let (issuer_keys, issuer) = setup();
+        let issuer = issuer.with_configuration(
+            "OtherCredential_ldp_vc",
+            CredentialConfiguration::new(CredentialFormat::LdpVc, "OtherCredential", json!({})),
+        );
+        let nonce = issuer.new_nonce();
+        let req = credential_req(
+            &issuer_keys,
+            &issuer,
+            json!({
+                "credential_configuration_id": "OtherCredential_ldp_vc",
+                "proof": {
+                    "proof_type": "jwt",
+                    "jwt": proof_jwt(&issuer, JWK_EC_P384_PRIV, None, Some(&nonce)),
+                },
+            }),
+        );
+
+        let (status, _, json) = call(app(issuer_keys, issuer), req).await;
+        assert_eq!(status, 403);
+        assert_eq!(json["error"], "insufficient_scope");
+    }
+}
@@ -0,0 +1,150 @@
+//! Implements mock-specific endpoints to start the
+//! [pre-authorized code flow](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0.html#name-pre-authorized-code-flow):
+//!
+//! - `POST /credential-offers`: Create a credential offer with a pre-authorized code.
+//! - `GET /credential-offers/:id`: Credential offer passed by reference.
+//!
+//! In a real issuer, credential offers are created after the user is authenticated in some way.
+//! Here anyone can create them, so that tests can hand the offer to a wallet directly.
+
+use axum::{
+    extract::{rejection::JsonRejection, Path},
+    Extension, Json,
+};
+
+use crate::{
+    endpoints::oid4vci::{
+        req::CreateCredentialOfferRequest,
+        res::{
+            oid4vci_error::{Oid4vciError, Oid4vciErrorCode},
+            CreateCredentialOfferResponse,
+        },
+    },
+    oid4vci::{CredentialOffer, Oid4vciIssuer},
+};
+
+/// `POST /credential-offers`
+#[axum::debug_handler]
+pub async fn create_credential_offer(
+    Extension(issuer): Extension<Oid4vciIssuer>,
+    req: Result<Json<CreateCredentialOfferRequest>, JsonRejection>,
+) -> Result<(http::StatusCode, Json<CreateCredentialOfferResponse>), Oid4vciError> {
+    let Json(req) = req
+        .map_err(|e| Oid4vciError::bad_request(Oid4vciErrorCode::InvalidRequest, e.body_text()))?;
+
+    let created = issuer.create_credential_offer(req.credential_configuration_ids, req.tx_code)?;
+    Ok((
+        http::StatusCode::CREATED,
+        Json(CreateCredentialOfferResponse {
+            offer_by_value: created.offer_by_value(),
+            offer_by_reference: created.offer_by_reference(),
+            credential_offer: created.credential_offer,
+            credential_offer_uri: created.credential_offer_uri,
+            tx_code: created.tx_code,
+        }),
+    ))
+}
+
+/// `GET /credential-offers/:id`
+#[axum::debug_handler]
+pub async fn credential_offer(
+    Extension(issuer): Extension<Oid4vciIssuer>,
+    Path(id): Path<String>,
+) -> Result<Json<CredentialOffer>, http::StatusCode> {
+    issuer
+        .credential_offer(&id)
+        .map(Json)
+        .ok_or(http::StatusCode::NOT_FOUND)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        extract::Request,
+        routing::{get, post},
+        Router,
+    };
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    use crate::test_tracing::init_tracing;
+
+    use super::*;
+
+    fn app(issuer: Oid4vciIssuer) -> Router {
+        Router::new()
+            .route("/credential-offers", post(create_credential_offer))
+            .route("/credential-offers/:id", get(credential_offer))
+            .layer(Extension(issuer))
+    }
+
+    async fn create(issuer: &Oid4vciIssuer, body: Value) -> (http::StatusCode, Value) {
+        let req = Request::builder()
+            .method("POST")
+            .uri("/credential-offers")
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let res = app(issuer.clone()).oneshot(req).await.unwrap();
+        let status = res.status();
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_create_credential_offer_success() {
+        init_tracing();
+        let issuer = Oid4vciIssuer::new("http://localhost:40080");
+
+        let (status, json) = create(
+            &issuer,
+            json!({
+                "credential_configuration_ids": ["ExampleCredential_dc+sd-jwt"],
+                "tx_code": { "input_mode": "text", "length": 4 },
+            }),
+        )
+        .await;
+        assert_eq!(status, 201);
+        assert_eq!(json["tx_code"].as_str().unwrap().len(), 4);
+        assert!(json["offer_by_value"]
+            .as_str()
+            .unwrap()
+            .starts_with("openid-credential-offer://?credential_offer="));
+
+        // by reference
+        let uri = json["credential_offer_uri"].as_str().unwrap();
+        let path = uri.trim_start_matches("http://localhost:40080");
+        let req = Request::builder().uri(path).body(Body::empty()).unwrap();
+        let res = app(issuer).oneshot(req).await.unwrap();
+        assert_eq!(res.status(), 200);
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let offer: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(offer, json["credential_offer"]);
+    }
+
+    #[tokio::test]
+    async fn test_create_credential_offer_error_empty() {
+        init_tracing();
+        let issuer = Oid4vciIssuer::new("http://localhost:40080");
+
+        let (status, json) = create(&issuer, json!({ "credential_configuration_ids": [] })).await;
+        assert_eq!(status, 400);
+        assert_eq!(json["error"], "invalid_request");
+    }
+
+    #[tokio::test]
+    async fn test_credential_offer_error_not_found() {
+        init_tracing();
+
+        let req = Request::builder()
+            .uri("/credential-offers/unknown")
+            .body(Body::empty())
+            .unwrap();
+        let res = app(Oid4vciIssuer::new("http://localhost:40080"))
+            .oneshot(req)
+            .await
+            .unwrap();
+        assert_eq!(res.status(), 404);
+    }
+}
@@ -0,0 +1,262 @@
+//! Implements the following endpoints for
+//! [deferred credential issuance](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0.html#name-deferred-credential-endpoin):
+//!
+//! - `POST /deferred_credential`: Deferred credential endpoint. Requires the access token
+//!   used at the credential endpoint.
+//! - `PUT /deferral`: Mock-specific. Change the [`Deferral`] rule applied to upcoming credential requests,
+//!   e.g. `{"mode": "polls", "polls": 3}` to make the wallet poll 3 times.
+//! - `POST /deferred_credential/:transaction_id/ready`: Mock-specific. Make a pending transaction ready.
+
+use axum::{
+    extract::{rejection::JsonRejection, Path},
+    Extension, Json,
+};
+use http::HeaderMap;
+
+use crate::{
+    endpoints::oid4vci::{
+        bearer_token,
+        req::DeferredCredentialRequest,
+        res::{
+            oid4vci_error::{Oid4vciError, Oid4vciErrorCode},
+            CredentialResponse,
+        },
+    },
+    oid4vci::{deferred::Deferral, Oid4vciIssuer},
+    IssuerKeys,
+};
+
+/// `POST /deferred_credential`
+#[axum::debug_handler]
+pub async fn deferred_credential(
+    Extension(issuer_keys): Extension<IssuerKeys>,
+    Extension(issuer): Extension<Oid4vciIssuer>,
+    headers: HeaderMap,
+    req: Result<Json<DeferredCredentialRequest>, JsonRejection>,
+) -> Result<(http::StatusCode, Json<CredentialResponse>), Oid4vciError> {
+    let token = bearer_token(&headers)
+        .ok_or_else(|| Oid4vciError::invalid_token("access token is missing"))?;
+    let access_token = issuer.access_token(&issuer_keys, token)?;
+
+    let Json(req) = req.map_err(|e| {
+        Oid4vciError::bad_request(Oid4vciErrorCode::InvalidCredentialRequest, e.body_text())
+    })?;
+    let delivery = issuer.poll_deferred(&access_token, &req.transaction_id)?;
+    let (status, res) = CredentialResponse::from_delivery(delivery);
+    Ok((status, Json(res)))
+}
+
+/// `PUT /deferral`
+#[axum::debug_handler]
+pub async fn set_deferral(
+    Extension(issuer): Extension<Oid4vciIssuer>,
+    req: Result<Json<Deferral>, JsonRejection>,
+) -> Result<http::StatusCode, Oid4vciError> {
+    let Json(deferral) = req
+        .map_err(|e| Oid4vciError::bad_request(Oid4vciErrorCode::InvalidRequest, e.body_text()))?;
+
+    issuer.set_deferral(deferral);
+    Ok(http::StatusCode::NO_CONTENT)
+}
+
+/// `POST /deferred_credential/:transaction_id/ready`
+#[axum::debug_handler]
+pub async fn mark_ready(
+    Extension(issuer): Extension<Oid4vciIssuer>,
+    Path(transaction_id): Path<String>,
+) -> http::StatusCode {
+    match issuer.mark_deferred_ready(&transaction_id) {
+        true => http::StatusCode::NO_CONTENT,
+        false => http::StatusCode::NOT_FOUND,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        extract::Request,
+        routing::{post, put},
+        Router,
+    };
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    use crate::{
+        endpoints::oid4vci::credential_issuer::{credential, tests::access_token},
+        oid4vci::proof::tests::proof_jwt,
+        test_jwks::{ISSMOCK_PRIV_EC_P384, ISSMOCK_PRIV_OKP_ED25519, JWK_EC_P384_PRIV},
+        test_tracing::init_tracing,
+    };
+
+    use super::*;
+
+    fn setup() -> (IssuerKeys, Oid4vciIssuer, Router) {
+        init_tracing();
+
+        let issuer_keys = IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519, ISSMOCK_PRIV_EC_P384]);
+        let issuer = Oid4vciIssuer::new("http://localhost:40080");
+        let app = Router::new()
+            .route("/credential", post(credential))
+            .route("/deferred_credential", post(deferred_credential))
+            .route("/deferral", put(set_deferral))
+            .route(
+                "/deferred_credential/:transaction_id/ready",
+                post(mark_ready),
+            )
+            .layer(Extension(issuer_keys.clone()))
+            .layer(Extension(issuer.clone()));
+        (issuer_keys, issuer, app)
+    }
+
+    async fn call(
+        app: &Router,
+        method: &str,
+        uri: &str,
+        token: Option<&str>,
+        body: Value,
+    ) -> (http::StatusCode, Value) {
+        let mut req = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json");
+        if let Some(token) = token {
+            req = req.header("authorization", format!("Bearer {}", token));
+        }
+        let req = req.body(Body::from(body.to_string())).unwrap();
+
+        let res = app.clone().oneshot(req).await.unwrap();
+        let status = res.status();
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    /// Set `deferral`, request a credential, and return the access token and `transaction_id`.
+    async fn deferred_request(
+        issuer_keys: &IssuerKeys,
+        issuer: &Oid4vciIssuer,
+        app: &Router,
+        deferral: Value,
+    ) -> (String, String) {
+        let (status, _) = call(app, "PUT", "/deferral", None, deferral).await;
+        assert_eq!(status, 204);
+
+        let token = access_token(issuer_keys, issuer);
+        let nonce = issuer.new_nonce();
+        let body = json!({
+            "credential_configuration_id": "ExampleCredential_ldp_vc",
+            "proof": {
+                "proof_type": "jwt",
+                "jwt": proof_jwt(issuer, JWK_EC_P384_PRIV, None, Some(&nonce)),
+            },
+        });
+        let (status, json) = call(app, "POST", "/credential", Some(&token), body).await;
+        assert_eq!(status, 202);
+        assert!(json.get("credentials").is_none());
+        assert_eq!(json["interval"], 5);
+
+        let transaction_id = json["transaction_id"].as_str().unwrap().to_string();
+        (token, transaction_id)
+    }
+
+    #[tokio::test]
+    async fn test_deferred_credential_success_polls() {
+        let (issuer_keys, issuer, app) = setup();
+        let (token, transaction_id) = deferred_request(
+            &issuer_keys,
+            &issuer,
+            &app,
+            json!({ "mode": "polls", "polls": 2 }),
+        )
+        .await;
+        let body = json!({ "transaction_id": transaction_id });
+
+        let (status, json) = call(
+            &app,
+            "POST",
+            "/deferred_credential",
+            Some(&token),
+            body.clone(),
+        )
+        .await;
+        assert_eq!(status, 202);
+        assert_eq!(json["transaction_id"], transaction_id);
+
+        let (status, json) = call(
+            &app,
+            "POST",
+            "/deferred_credential",
+            Some(&token),
+            body.clone(),
+        )
+        .await;
+        assert_eq!(status, 200);
+        assert!(json["credentials"][0]["credential"]["proof"].is_object());
+
+        let (status, json) = call(&app, "POST", "/deferred_credential", Some(&token), body).await;
+        assert_eq!(status, 400);
+        assert_eq!(json["error"], "invalid_transaction_id");
+    }
+
+    #[tokio::test]
+    async fn test_deferred_credential_success_manual() {
+        let (issuer_keys, issuer, app) = setup();
+        let (token, transaction_id) =
+            deferred_request(&issuer_keys, &issuer, &app, json!({ "mode": "manual" })).await;
+        let body = json!({ "transaction_id": transaction_id });
+
+        let (status, _) = call(
+            &app,
+            "POST",
+            "/deferred_credential",
+            Some(&token),
+            body.clone(),
+        )
+        .await;
+        assert_eq!(status, 202);
+
+        let uri = format!("/deferred_credential/{}/ready", transaction_id);
+        let (status, _) = call(&app, "POST", &uri, None, Value::Null).await;
+        assert_eq!(status, 204);
+
+        let (status, json) = call(&app, "POST", "/deferred_credential", Some(&token), body).await;
+        assert_eq!(status, 200);
+        assert_eq!(json["credentials"].as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_deferred_credential_error_no_access_token() {
+        let (issuer_keys, issuer, app) = setup();
+        let (_, transaction_id) =
+            deferred_request(&issuer_keys, &issuer, &app, json!({ "mode": "manual" })).await;
+
+        let body = json!({ "transaction_id": transaction_id });
+        let (status, json) = call(&app, "POST", "/deferred_credential", None, body).await;
+        assert_eq!(status, 401);
+        assert_eq!(json["error"], "invalid_token");
+    }
+
+    #[tokio::test]
+    async fn test_mark_ready_error_unknown_transaction() {
+        let (_, _, app) = setup();
+
+        let (status, _) = call(
+            &app,
+            "POST",
+            "/deferred_credential/unknown/ready",
+            None,
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, 404);
+    }
+
+    #[tokio::test]
+    async fn test_set_deferral_error_unknown_mode() {
+        let (_, _, app) = setup();
+
+        let (status, json) = call(&app, "PUT", "/deferral", None, json!({ "mode": "never" })).await;
+        assert_eq!(status, 400);
+        assert_eq!(json["error"], "invalid_request");
+    }
+}
@@ -0,0 +1,22 @@
+//! [OID4VCI](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0.html) endpoints.
+
+pub(crate) mod req;
+pub(crate) mod res;
+
+pub mod authorization;
+pub mod credential_issuer;
+pub mod credential_offer;
+pub mod deferred;
+pub mod token;
+
+use http::{header, HeaderMap};
+
+/// Access token sent in the `Authorization` header
+/// ([RFC 6750](https://datatracker.ietf.org/doc/html/rfc6750#section-2.1)).
+pub(crate) fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+}
@@ -0,0 +1,87 @@
+//! Request parameters of OID4VCI endpoints.
+
+use serde::Deserialize;
+
+use crate::oid4vci::{authorization::ConsentDecision, grants::TxCode};
+
+/// Request body of the [Credential Endpoint](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0.html#name-credential-request).
+#[derive(Clone, Debug, Deserialize)]
+pub struct CredentialRequest {
+    /// Key of `credential_configurations_supported` in the issuer metadata.
+    pub credential_configuration_id: String,
+    /// A single proof of possession of the holder key.
+    pub proof: Option<CredentialRequestProof>,
+    /// Proofs of possession of the holder keys, grouped by proof type.
+    pub proofs: Option<CredentialRequestProofs>,
+}
+
+/// `proof` parameter of [`CredentialRequest`].
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "proof_type", rename_all = "snake_case")]
+pub enum CredentialRequestProof {
+    Jwt { jwt: String },
+}
+
+/// `proofs` parameter of [`CredentialRequest`].
+#[derive(Clone, Debug, Deserialize)]
+pub struct CredentialRequestProofs {
+    #[serde(default)]
+    pub jwt: Vec<String>,
+}
+
+impl CredentialRequest {
+    /// JWT proofs in either `proof` or `proofs`.
+    ///
+    /// Returns `None` if both or neither of them are present.
+    pub(crate) fn jwt_proofs(&self) -> Option<Vec<&str>> {
+        match (&self.proof, &self.proofs) {
+            (Some(CredentialRequestProof::Jwt { jwt }), None) => Some(vec![jwt.as_str()]),
+            (None, Some(proofs)) => Some(proofs.jwt.iter().map(String::as_str).collect()),
+            _ => None,
+        }
+    }
+}
+
+/// Request body of the [Deferred Credential Endpoint](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0.html#name-deferred-credential-request).
+#[derive(Clone, Debug, Deserialize)]
+pub struct DeferredCredentialRequest {
+    pub transaction_id: String,
+}
+
+/// Request body of `POST /credential-offers`, a mock-specific endpoint to start the pre-authorized code flow.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CreateCredentialOfferRequest {
+    /// Credential configurations to offer.
+    pub credential_configuration_ids: Vec<String>,
+    /// If present, a transaction code is required at the token endpoint.
+    pub tx_code: Option<TxCode>,
+}
+
+/// Request parameters of the [Token Endpoint](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0.html#name-token-request)
+/// (`application/x-www-form-urlencoded`).
+#[derive(Clone, Debug, Deserialize)]
+pub struct TokenRequest {
+    pub grant_type: String,
+    /// Pre-authorized code flow.
+    #[serde(rename = "pre-authorized_code")]
+    pub pre_authorized_code: Option<String>,
+    /// Pre-authorized code flow.
+    pub tx_code: Option<String>,
+    /// Authorization code flow.
+    pub code: Option<String>,
+    /// Authorization code flow.
+    pub redirect_uri: Option<String>,
+    /// Authorization code flow.
+    pub code_verifier: Option<String>,
+    /// Authorization code flow (public clients).
+    pub client_id: Option<String>,
+}
+
+/// Request body of `POST /authorize/consent`, a mock-specific endpoint to script how the mock user
+/// answers upcoming authorization requests.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScriptConsentRequest {
+    pub decisions: Vec<ConsentDecision>,
+}
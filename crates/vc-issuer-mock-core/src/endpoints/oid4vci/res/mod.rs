@@ -0,0 +1,100 @@
+//! Response bodies of OID4VCI endpoints.
+
+pub(crate) mod oid4vci_error;
+
+use http::StatusCode;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::oid4vci::{deferred::CredentialDelivery, CredentialOffer};
+
+/// Response body of the [Nonce Endpoint](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0.html#name-nonce-response).
+#[derive(Debug, Serialize)]
+pub struct NonceResponse {
+    pub c_nonce: String,
+}
+
+/// Response body of the [Credential Endpoint](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0.html#name-credential-response)
+/// and the [Deferred Credential Endpoint](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0.html#name-deferred-credential-respons).
+///
+/// Has either `credentials`, or `transaction_id` and `interval` if the issuance is deferred.
+#[derive(Debug, Serialize)]
+pub struct CredentialResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credentials: Option<Vec<IssuedCredentialObject>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval: Option<u64>,
+}
+
+/// An entry of `credentials` in [`CredentialResponse`].
+#[derive(Debug, Serialize)]
+pub struct IssuedCredentialObject {
+    /// A JSON object for `ldp_vc`, or a string for `dc+sd-jwt`.
+    pub credential: Value,
+}
+
+impl CredentialResponse {
+    /// The response body and its status code: `200 OK` with credentials, or `202 Accepted` if deferred.
+    pub(crate) fn from_delivery(delivery: CredentialDelivery) -> (StatusCode, Self) {
+        match delivery {
+            CredentialDelivery::Issued(credentials) => (
+                StatusCode::OK,
+                Self {
+                    credentials: Some(
+                        credentials
+                            .into_iter()
+                            .map(|credential| IssuedCredentialObject { credential })
+                            .collect(),
+                    ),
+                    transaction_id: None,
+                    interval: None,
+                },
+            ),
+            CredentialDelivery::Deferred {
+                transaction_id,
+                interval,
+            } => (
+                StatusCode::ACCEPTED,
+                Self {
+                    credentials: None,
+                    transaction_id: Some(transaction_id),
+                    interval: Some(interval),
+                },
+            ),
+        }
+    }
+}
+
+/// Response body of `POST /credential-offers`.
+#[derive(Debug, Serialize)]
+pub struct CreateCredentialOfferResponse {
+    pub credential_offer: CredentialOffer,
+    /// URL where the credential offer is passed by reference.
+    pub credential_offer_uri: String,
+    /// `openid-credential-offer://` URI with the credential offer by value.
+    pub offer_by_value: String,
+    /// `openid-credential-offer://` URI with the credential offer by reference.
+    pub offer_by_reference: String,
+    /// Transaction code to be input by the user, if required.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_code: Option<String>,
+}
+
+/// Response body of the [Token Endpoint](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0.html#name-successful-token-response).
+#[derive(Debug, Serialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub token_type: &'static str,
+    pub expires_in: u64,
+    pub c_nonce: String,
+    pub c_nonce_expires_in: u64,
+}
+
+/// Response body of the [Pushed Authorization Request Endpoint](https://datatracker.ietf.org/doc/html/rfc9126#section-2.2).
+#[derive(Debug, Serialize)]
+pub struct PushedAuthorizationResponse {
+    pub request_uri: String,
+    pub expires_in: u64,
+}
@@ -0,0 +1,150 @@
+//! Error responses of OID4VCI endpoints.
+
+use core::fmt;
+
+use axum::{
+    response::{IntoResponse, Response},
+    Json,
+};
+use http::{header, HeaderValue, StatusCode};
+use serde::Serialize;
+use thiserror::Error;
+use tracing::{debug, error};
+
+use crate::vcdm_v2::problem_details::ProblemDetails;
+
+/// The error response body used in OID4VCI
+/// ([Credential Error Response](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0.html#name-credential-error-response))
+/// and by the built-in authorization server ([RFC 6749](https://datatracker.ietf.org/doc/html/rfc6749#section-5.2)).
+#[derive(Debug, Error, Serialize)]
+pub struct Oid4vciError {
+    #[serde(skip)]
+    pub(crate) status: StatusCode,
+    pub(crate) error: Oid4vciErrorCode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) error_description: Option<String>,
+}
+
+/// `error` parameter of the error response.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Oid4vciErrorCode {
+    // RFC 6749
+    InvalidRequest,
+    InvalidGrant,
+    UnsupportedGrantType,
+    // RFC 6750
+    InvalidToken,
+    InsufficientScope,
+    // OID4VCI
+    InvalidCredentialRequest,
+    UnknownCredentialConfiguration,
+    InvalidProof,
+    InvalidNonce,
+    InvalidTransactionId,
+    ServerError,
+}
+
+impl Oid4vciError {
+    /// An error response with `400 Bad Request`.
+    pub(crate) fn bad_request(
+        error: Oid4vciErrorCode,
+        error_description: impl Into<String>,
+    ) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, error, error_description)
+    }
+
+    /// `invalid_token` error response with `401 Unauthorized`.
+    pub(crate) fn invalid_token(error_description: impl Into<String>) -> Self {
+        Self::new(
+            StatusCode::UNAUTHORIZED,
+            Oid4vciErrorCode::InvalidToken,
+            error_description,
+        )
+    }
+
+    /// `insufficient_scope` error response with `403 Forbidden`.
+    pub(crate) fn insufficient_scope(error_description: impl Into<String>) -> Self {
+        Self::new(
+            StatusCode::FORBIDDEN,
+            Oid4vciErrorCode::InsufficientScope,
+            error_description,
+        )
+    }
+
+    fn new(
+        status: StatusCode,
+        error: Oid4vciErrorCode,
+        error_description: impl Into<String>,
+    ) -> Self {
+        let error_description = error_description.into();
+        debug!("{}: {:?}: {}", status, error, error_description);
+
+        Self {
+            status,
+            error,
+            error_description: Some(error_description),
+        }
+    }
+}
+
+impl fmt::Display for Oid4vciError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "status={}, error={:?}, error_description={:?}",
+            self.status, self.error, self.error_description
+        )
+    }
+}
+
+impl IntoResponse for Oid4vciError {
+    fn into_response(self) -> Response {
+        let mut res = (
+            self.status,
+            [(header::CACHE_CONTROL, HeaderValue::from_static("no-store"))],
+            Json(&self),
+        )
+            .into_response();
+
+        // <https://datatracker.ietf.org/doc/html/rfc6750#section-3>
+        if matches!(
+            self.error,
+            Oid4vciErrorCode::InvalidToken | Oid4vciErrorCode::InsufficientScope
+        ) {
+            let error = serde_json::to_value(self.error).expect("error code should be serialized");
+            let challenge = format!("Bearer error=\"{}\"", error.as_str().unwrap_or_default());
+            if let Ok(challenge) = HeaderValue::from_str(&challenge) {
+                res.headers_mut()
+                    .insert(header::WWW_AUTHENTICATE, challenge);
+            }
+        }
+        res
+    }
+}
+
+impl From<ProblemDetails> for Oid4vciError {
+    fn from(problem_details: ProblemDetails) -> Self {
+        // Issuance from a valid credential request fails only by a server-side problem
+        // (e.g. issuer keys not usable for the credential configuration).
+        error!("InternalServerError: {:?}", problem_details);
+
+        Self {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            error: Oid4vciErrorCode::ServerError,
+            error_description: Some(problem_details.detail.clone()),
+        }
+    }
+}
+
+impl From<anyhow::Error> for Oid4vciError {
+    fn from(e: anyhow::Error) -> Self {
+        error!("InternalServerError: {:?}", e);
+
+        Self {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            error: Oid4vciErrorCode::ServerError,
+            error_description: Some(e.to_string()),
+        }
+    }
+}
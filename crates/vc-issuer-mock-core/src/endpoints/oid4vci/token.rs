@@ -0,0 +1,208 @@
+//! Implements the following endpoints of the built-in authorization server:
+//!
+//! - `GET /.well-known/oauth-authorization-server`
+//! - `POST /token`
+//!
+//! Supported grants are the [authorization code grant](https://datatracker.ietf.org/doc/html/rfc6749#section-4.1.3)
+//! with PKCE and the [pre-authorized code grant](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0.html#name-token-request).
+
+use axum::{
+    extract::rejection::FormRejection,
+    response::{IntoResponse, Response},
+    Extension, Form, Json,
+};
+use http::{header, HeaderValue};
+use serde_json::Value;
+
+use crate::{
+    endpoints::oid4vci::{
+        req::TokenRequest,
+        res::{
+            oid4vci_error::{Oid4vciError, Oid4vciErrorCode},
+            TokenResponse,
+        },
+    },
+    oid4vci::{
+        access_token::ACCESS_TOKEN_LIFETIME,
+        grants::{AUTHORIZATION_CODE_GRANT_TYPE, PRE_AUTHORIZED_CODE_GRANT_TYPE},
+        Oid4vciIssuer, NONCE_LIFETIME,
+    },
+    IssuerKeys,
+};
+
+/// `GET /.well-known/oauth-authorization-server`
+#[axum::debug_handler]
+pub async fn authorization_server_metadata(
+    Extension(issuer): Extension<Oid4vciIssuer>,
+) -> Json<Value> {
+    Json(issuer.authorization_server_metadata())
+}
+
+/// `POST /token`
+#[axum::debug_handler]
+pub async fn token(
+    Extension(issuer_keys): Extension<IssuerKeys>,
+    Extension(issuer): Extension<Oid4vciIssuer>,
+    req: Result<Form<TokenRequest>, FormRejection>,
+) -> Result<Response, Oid4vciError> {
+    let Form(req) = req
+        .map_err(|e| Oid4vciError::bad_request(Oid4vciErrorCode::InvalidRequest, e.body_text()))?;
+
+    let access_token = match req.grant_type.as_str() {
+        AUTHORIZATION_CODE_GRANT_TYPE => {
+            let code = required("code", &req.code)?;
+            issuer.exchange_authorization_code(
+                &issuer_keys,
+                code,
+                req.client_id.as_deref(),
+                req.redirect_uri.as_deref(),
+                req.code_verifier.as_deref(),
+            )?
+        }
+        PRE_AUTHORIZED_CODE_GRANT_TYPE => {
+            let code = required("pre-authorized_code", &req.pre_authorized_code)?;
+            issuer.exchange_pre_authorized_code(&issuer_keys, code, req.tx_code.as_deref())?
+        }
+        grant_type => {
+            return Err(Oid4vciError::bad_request(
+                Oid4vciErrorCode::UnsupportedGrantType,
+                format!("unsupported grant_type: {}", grant_type),
+            ))
+        }
+    };
+
+    Ok((
+        [(header::CACHE_CONTROL, HeaderValue::from_static("no-store"))],
+        Json(TokenResponse {
+            access_token,
+            token_type: "Bearer",
+            expires_in: ACCESS_TOKEN_LIFETIME.as_secs(),
+            c_nonce: issuer.new_nonce(),
+            c_nonce_expires_in: NONCE_LIFETIME.as_secs(),
+        }),
+    )
+        .into_response())
+}
+
+fn required<'a>(name: &str, value: &'a Option<String>) -> Result<&'a str, Oid4vciError> {
+    value.as_deref().ok_or_else(|| {
+        Oid4vciError::bad_request(
+            Oid4vciErrorCode::InvalidRequest,
+            format!("`{}` is required", name),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::to_bytes, extract::Request, routing::post, Router};
+    use tower::ServiceExt;
+
+    use crate::{
+        oid4vci::grants::TxCode,
+        test_jwks::{ISSMOCK_PRIV_EC_P384, ISSMOCK_PRIV_OKP_ED25519},
+        test_tracing::init_tracing,
+    };
+
+    use super::*;
+
+    fn issuer_keys() -> IssuerKeys {
+        IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519, ISSMOCK_PRIV_EC_P384])
+    }
+
+    async fn call_token(issuer: &Oid4vciIssuer, form: &str) -> (http::StatusCode, Value) {
+        init_tracing();
+
+        let app = Router::new()
+            .route("/token", post(token))
+            .layer(Extension(issuer_keys()))
+            .layer(Extension(issuer.clone()));
+        let req = Request::builder()
+            .method("POST")
+            .uri("/token")
+            .header("content-type", "application/x-www-form-urlencoded")
+            .body(axum::body::Body::from(form.to_string()))
+            .unwrap();
+        let res = app.oneshot(req).await.unwrap();
+        let status = res.status();
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    fn form(code: &str, tx_code: Option<&str>) -> String {
+        let mut form = format!(
+            "grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Apre-authorized_code&pre-authorized_code={}",
+            code
+        );
+        if let Some(tx_code) = tx_code {
+            form.push_str(&format!("&tx_code={}", tx_code));
+        }
+        form
+    }
+
+    fn offer(issuer: &Oid4vciIssuer, tx_code: Option<TxCode>) -> (String, Option<String>) {
+        let created = issuer
+            .create_credential_offer(vec!["ExampleCredential_ldp_vc".to_string()], tx_code)
+            .unwrap();
+        (
+            created
+                .credential_offer
+                .grants
+                .pre_authorized_code
+                .pre_authorized_code,
+            created.tx_code,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_token_success() {
+        let issuer = Oid4vciIssuer::new("http://localhost:40080");
+        let (code, tx_code) = offer(&issuer, Some(TxCode::default()));
+
+        let (status, json) = call_token(&issuer, &form(&code, tx_code.as_deref())).await;
+        assert_eq!(status, 200);
+        assert_eq!(json["token_type"], "Bearer");
+
+        let access_token = issuer
+            .access_token(&issuer_keys(), json["access_token"].as_str().unwrap())
+            .unwrap();
+        assert_eq!(
+            access_token.credential_configuration_ids,
+            vec!["ExampleCredential_ldp_vc"]
+        );
+        assert!(issuer.consume_nonce(json["c_nonce"].as_str().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_token_error_code_reused() {
+        let issuer = Oid4vciIssuer::new("http://localhost:40080");
+        let (code, _) = offer(&issuer, None);
+
+        let (status, _) = call_token(&issuer, &form(&code, None)).await;
+        assert_eq!(status, 200);
+
+        let (status, json) = call_token(&issuer, &form(&code, None)).await;
+        assert_eq!(status, 400);
+        assert_eq!(json["error"], "invalid_grant");
+    }
+
+    #[tokio::test]
+    async fn test_token_error_wrong_tx_code() {
+        let issuer = Oid4vciIssuer::new("http://localhost:40080");
+        let (code, _) = offer(&issuer, Some(TxCode::default()));
+
+        let (status, json) = call_token(&issuer, &form(&code, Some("wrong"))).await;
+        assert_eq!(status, 400);
+        assert_eq!(json["error"], "invalid_grant");
+    }
+
+    #[tokio::test]
+    async fn test_token_error_unsupported_grant_type() {
+        let issuer = Oid4vciIssuer::new("http://localhost:40080");
+
+        let (status, json) =
+            call_token(&issuer, "grant_type=client_credentials&client_id=wallet").await;
+        assert_eq!(status, 400);
+        assert_eq!(json["error"], "unsupported_grant_type");
+    }
+}
@@ -0,0 +1,97 @@
+//! Publishes [Bitstring Status List](https://www.w3.org/TR/vc-bitstring-status-list/) credentials.
+//!
+//! The route path is up to the server as long as it matches the base URL of [`StatusLists`].
+
+use axum::{extract::Path, Extension};
+
+use crate::{
+    did_web::DidWeb,
+    endpoints::{
+        vc_api::res::{vc_api_error::VcApiError, VerifiableCredentialV2DataIntegrity},
+        SuccessRes,
+    },
+    status_list::{StatusLists, StatusPurpose},
+    IssuerKeys,
+};
+
+/// `GET {status list base path}/:purpose`
+///
+/// Returns the signed status list credential. It is signed on the first request.
+#[axum::debug_handler]
+pub async fn status_list_credential(
+    Extension(issuer_keys): Extension<IssuerKeys>,
+    Extension(status_lists): Extension<StatusLists>,
+    did_web: Option<Extension<DidWeb>>,
+    Path(purpose): Path<StatusPurpose>,
+) -> Result<SuccessRes<VerifiableCredentialV2DataIntegrity>, VcApiError> {
+    let did_web = did_web.as_ref().map(|Extension(did_web)| did_web);
+    let vc = match status_lists.signed_credential(purpose) {
+        Some(vc) => vc,
+        None => status_lists.sign(&issuer_keys, did_web, purpose).await?,
+    };
+    Ok(SuccessRes {
+        status: http::StatusCode::OK,
+        body: vc,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        extract::Request,
+        routing::get,
+        Router,
+    };
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    use crate::{
+        test_jwks::{ISSMOCK_PRIV_EC_P384, ISSMOCK_PRIV_OKP_ED25519},
+        test_tracing::init_tracing,
+    };
+
+    use super::*;
+
+    fn app() -> Router {
+        Router::new()
+            .route("/status-lists/:purpose", get(status_list_credential))
+            .layer(Extension(IssuerKeys::new(vec![
+                ISSMOCK_PRIV_OKP_ED25519,
+                ISSMOCK_PRIV_EC_P384,
+            ])))
+            .layer(Extension(StatusLists::new(
+                "http://localhost:40080/status-lists",
+            )))
+    }
+
+    #[tokio::test]
+    async fn test_status_list_credential_success() {
+        init_tracing();
+
+        let req = Request::builder()
+            .uri("/status-lists/suspension")
+            .body(Body::empty())
+            .unwrap();
+        let res = app().oneshot(req).await.unwrap();
+        assert_eq!(res.status(), 200);
+
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["type"][1], "BitstringStatusListCredential");
+        assert_eq!(json["credentialSubject"]["statusPurpose"], "suspension");
+        assert!(json["proof"].is_object());
+    }
+
+    #[tokio::test]
+    async fn test_status_list_credential_error_unknown_purpose() {
+        init_tracing();
+
+        let req = Request::builder()
+            .uri("/status-lists/refresh")
+            .body(Body::empty())
+            .unwrap();
+        let res = app().oneshot(req).await.unwrap();
+        assert_eq!(res.status(), 400);
+    }
+}
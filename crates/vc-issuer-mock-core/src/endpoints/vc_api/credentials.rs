@@ -1,62 +1,602 @@
 //! Implements the following endpoints from [VC-API](https://w3c-ccg.github.io/vc-api/):
 //!
 //! - `POST /credentials/issue`
+//! - `POST /credentials/verify`
+//! - `POST /credentials/status`
+//! - `GET /credentials/{id}`
+//! - `DELETE /credentials/{id}`
+//!
+//! `POST /credentials/issue` accepts both [VCDM v2.0](https://www.w3.org/TR/vc-data-model-2.0/) and
+//! [VCDM v1.1](https://www.w3.org/TR/vc-data-model-1.1/) credentials.
+//! VCDM v1.1 credentials can be secured with Data Integrity proofs or SD-JWT only.
+
+use std::sync::Arc;
 
-use anyhow::anyhow;
-use axum::Extension;
+use anyhow::{anyhow, Context};
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Response},
+    Extension,
+};
+use http::{header, HeaderMap};
+use serde_json::Value;
 use ssi::{
     claims::{
-        data_integrity::{AnyInputOptions, AnySignatureOptions},
+        data_integrity::{
+            AnyInputOptions, AnySelectionOptions, AnySignatureOptions, AnySuite,
+            CryptographicSuiteSigning,
+        },
         vc::v2::Credential,
-        SignatureEnvironment,
+        Invalid, SignatureEnvironment, VerificationParameters,
     },
-    prelude::CryptographicSuite,
-    verification_methods::ReferenceOrOwned,
+    json_ld::iref::{Iri, UriBuf},
+    prelude::{CryptographicSuite, DataIntegrity},
+    verification_methods::{LocalSigner, ReferenceOrOwned},
 };
+use tracing::{debug, warn};
 
 use crate::{
+    credential_id::CredentialIdMinter,
+    credential_store::{CredentialStore, DynCredentialStore, ScopedCredentialStore},
+    cryptosuite::RequestedCryptosuite,
+    did_web::DidWeb,
     endpoints::{
         vc_api::{
-            req::{json_req::JsonReq, IssueRequest},
-            res::{vc_api_error::VcApiError, IssueResponse, VerifiableCredentialV2DataIntegrity},
+            req::{
+                json_req::JsonReq, DeriveRequest, IssueRequest, IssueRequestOptions,
+                SecuringMechanism, UpdateCredentialStatusRequest, VerifyRequest,
+                VerifyRequestOptions,
+            },
+            res::{
+                vc_api_error::{custom_problem_types::CustomProblemType, VcApiError},
+                AnyVerifiableCredential, AnyVerifiableCredentialDataIntegrity, DeriveResponse,
+                GetCredentialResponse, IssueResponse, IssuedCredential, VerifiableCredentialV2,
+                VerifyResponse,
+            },
         },
         SuccessRes,
     },
+    issuer_profile::DefaultVcProperties,
+    sd_jwt::create_sd_jwt_vc,
+    status_list::{StatusLists, StatusPurpose},
+    vc_jose_cose::{
+        cose::MEDIA_TYPE_VC_COSE, create_enveloped_vc_with_cose, create_enveloped_vc_with_jose,
+    },
     vcdm_v2::problem_details::{PredefinedProblemType, ProblemDetails},
     verification_method::{CustomVerificationMethodResolver, VerificationMethod},
     IssuerKeys,
 };
 
 /// `POST /credentials/issue``
+///
+/// A credential secured with COSE is returned as raw `COSE_Sign1` bytes if the client accepts `application/vc+cose`.
+/// Otherwise, the response is always a JSON.
+///
+/// If [`StatusLists`] is available, `BitstringStatusListEntry`s are added to a VCDM v2.0 credential unless it is
+/// secured as an SD-JWT VC. An `id` is minted for it if it has no identifier, so that its status can be updated.
+/// VCDM v1.1 credentials never get `credentialStatus`.
+/// If [`CredentialIdMinter`] is available, an `id` is minted for a credential without one.
+/// If [`DynCredentialStore`] is available, the issued credential is recorded in it, apart from those of issuer profiles.
+/// If [`DidWeb`] is available, its DIDs can be used as the issuer.
+/// If [`DefaultVcProperties`] is available, they are set to a credential without them.
 #[axum::debug_handler]
+#[allow(clippy::too_many_arguments)]
 pub async fn issue(
+    headers: HeaderMap,
     Extension(issuer_keys): Extension<IssuerKeys>,
-    JsonReq(req): JsonReq<IssueRequest>,
+    status_lists: Option<Extension<StatusLists>>,
+    credential_id_minter: Option<Extension<CredentialIdMinter>>,
+    credential_store: Option<Extension<DynCredentialStore>>,
+    did_web: Option<Extension<DidWeb>>,
+    default_properties: Option<Extension<DefaultVcProperties>>,
+    JsonReq(mut req): JsonReq<Value>,
+) -> Result<Response, VcApiError> {
+    if let Some(Extension(default_properties)) = default_properties {
+        default_properties.apply(&mut req);
+    }
+    let req = parse_issue_request(req)?;
+
+    let services = IssueServices {
+        status_lists: status_lists.map(|Extension(status_lists)| status_lists),
+        credential_id_minter: credential_id_minter.map(|Extension(minter)| minter),
+        credential_store: credential_store.map(|Extension(store)| {
+            Arc::new(ScopedCredentialStore::global(store)) as DynCredentialStore
+        }),
+        did_web: did_web.map(|Extension(did_web)| did_web),
+    };
+    let res = issue_credential(issuer_keys, &services, req).await?;
+    Ok(issue_response(&headers, res))
+}
+
+/// Parse a `POST /credentials/issue` request body, after defaults are filled into it.
+pub(crate) fn parse_issue_request(req: Value) -> Result<IssueRequest, ProblemDetails> {
+    serde_json::from_value(req).map_err(|e| {
+        ProblemDetails::new(
+            PredefinedProblemType::ParsingError,
+            "JSON parse error".to_string(),
+            e.to_string(),
+            anyhow!("Invalid issue request: {:?}", e),
+        )
+    })
+}
+
+/// Response of `POST /credentials/issue`, in COSE if the client accepts it.
+pub(crate) fn issue_response(headers: &HeaderMap, res: SuccessRes<IssueResponse>) -> Response {
+    let accepts_cose = headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|media_type| media_type.trim().starts_with(MEDIA_TYPE_VC_COSE));
+
+    match &res.body.verifiable_credential {
+        IssuedCredential::Cose(vc) if accepts_cose => (
+            res.status,
+            [(header::CONTENT_TYPE, MEDIA_TYPE_VC_COSE)],
+            vc.cose_sign1.clone(),
+        )
+            .into_response(),
+        _ => res.into_response(),
+    }
+}
+
+/// Optional services of `POST /credentials/issue`, each enabled by adding it as an [`Extension`].
+#[derive(Clone, Debug, Default)]
+pub(crate) struct IssueServices {
+    pub(crate) status_lists: Option<StatusLists>,
+    pub(crate) credential_id_minter: Option<CredentialIdMinter>,
+    pub(crate) credential_store: Option<DynCredentialStore>,
+    pub(crate) did_web: Option<DidWeb>,
+}
+
+pub(crate) async fn issue_credential(
+    issuer_keys: IssuerKeys,
+    services: &IssueServices,
+    mut req: IssueRequest,
 ) -> Result<SuccessRes<IssueResponse>, VcApiError> {
     validate_issue_request(&req)?;
 
+    if let Some(minter) = &services.credential_id_minter {
+        if req.credential.id().is_none() {
+            req.credential.set_id(mint_credential_id(minter)?);
+        }
+    }
+
     let issuer = req.credential.issuer();
-    let vm_resolver = CustomVerificationMethodResolver::new(issuer_keys.clone());
-    let vm = vm_resolver
-        .resolve(issuer)
-        .await
-        .map_err(|problem_details| VcApiError {
-            status: http::StatusCode::BAD_REQUEST,
-            problem_details,
-        })?;
+    let vm_resolver = CustomVerificationMethodResolver::new(issuer_keys.clone())
+        .with_did_web(services.did_web.clone());
+    let vm = match &req.options.verification_method {
+        Some(method) => {
+            let method = Iri::new(method.as_str())
+                .expect("`verificationMethod` option is validated in validate_issue_request()");
+            vm_resolver.resolve_method(issuer, method).await
+        }
+        None => vm_resolver.resolve(issuer).await,
+    }
+    .map_err(|problem_details| VcApiError {
+        status: http::StatusCode::BAD_REQUEST,
+        problem_details,
+    })?;
+
+    // The signing key and the cryptosuite are resolved before status entries are allocated,
+    // so that requests failing on them do not waste entries.
+    let signing_key = vm.find_signing_key(&issuer_keys)?;
+    let suite = match req.options.securing_mechanism {
+        SecuringMechanism::DataIntegrity => Some(select_data_integrity_suite(&req.options, &vm)?),
+        _ => None,
+    };
+
+    // SD-JWT VCs and VCDM v1.1 credentials do not get `credentialStatus`.
+    if let Some(status_lists) = &services.status_lists {
+        add_credential_status(status_lists, services, &mut req)?;
+    }
 
-    let vc = create_vc_with_data_integrity(&req, issuer_keys, &vm, &vm_resolver).await?;
+    let vc = match (req.options.securing_mechanism, &req.credential) {
+        (SecuringMechanism::DataIntegrity, AnyVerifiableCredential::V1(credential)) => {
+            IssuedCredential::DataIntegrityV1(
+                sign_with_data_integrity(
+                    credential.clone(),
+                    &req.options,
+                    suite.expect("suite is selected for Data Integrity above"),
+                    issuer_keys,
+                    &vm,
+                    &vm_resolver,
+                )
+                .await?,
+            )
+        }
+        (SecuringMechanism::DataIntegrity, AnyVerifiableCredential::V2(credential)) => {
+            IssuedCredential::DataIntegrity(
+                sign_with_data_integrity(
+                    credential.clone(),
+                    &req.options,
+                    suite.expect("suite is selected for Data Integrity above"),
+                    issuer_keys,
+                    &vm,
+                    &vm_resolver,
+                )
+                .await?,
+            )
+        }
+        (SecuringMechanism::Jose, credential) => {
+            let credential = credential
+                .as_v2()
+                .expect("VCDM v1.1 credentials are rejected in validate_issue_request()");
+            IssuedCredential::Enveloped(create_enveloped_vc_with_jose(
+                credential,
+                &signing_key,
+                vm.to_id_iri().as_str(),
+            )?)
+        }
+        (SecuringMechanism::Cose, credential) => {
+            let credential = credential
+                .as_v2()
+                .expect("VCDM v1.1 credentials are rejected in validate_issue_request()");
+            IssuedCredential::Cose(create_enveloped_vc_with_cose(
+                credential,
+                &signing_key,
+                vm.to_id_iri().as_str(),
+            )?)
+        }
+        (SecuringMechanism::SdJwt, _) => IssuedCredential::SdJwt(create_sd_jwt_vc(
+            &req.credential,
+            req.options
+                .mandatory_pointers
+                .as_deref()
+                .unwrap_or_default(),
+            req.options.holder_jwk.as_ref(),
+            &signing_key,
+            vm.to_id_iri().as_str(),
+        )?),
+    };
     let res = IssueResponse::new(vc);
+
+    if let Some(store) = &services.credential_store {
+        match req.credential_id() {
+            Some(id) => {
+                let credential =
+                    serde_json::to_value(&res).context("Failed to serialize issued credential")?;
+                store.put(id, credential)?;
+            }
+            None => debug!("Issued credential is not stored since it has no identifier"),
+        }
+    }
+
     Ok(SuccessRes {
         status: http::StatusCode::CREATED,
         body: res,
     })
 }
 
+/// `POST /credentials/verify`
+#[axum::debug_handler]
+pub async fn verify(
+    Extension(issuer_keys): Extension<IssuerKeys>,
+    did_web: Option<Extension<DidWeb>>,
+    JsonReq(req): JsonReq<VerifyRequest>,
+) -> Result<VerifyResponse, VcApiError> {
+    verify_credential(issuer_keys, did_web.map(|Extension(did_web)| did_web), req).await
+}
+
+pub(crate) async fn verify_credential(
+    issuer_keys: IssuerKeys,
+    did_web: Option<DidWeb>,
+    req: VerifyRequest,
+) -> Result<VerifyResponse, VcApiError> {
+    let vm_resolver = CustomVerificationMethodResolver::new(issuer_keys).with_did_web(did_web);
+    let params = VerificationParameters::from_resolver(&vm_resolver);
+
+    let verification = match &req.verifiable_credential {
+        AnyVerifiableCredentialDataIntegrity::V1(vc) => vc.verify(&params).await,
+        AnyVerifiableCredentialDataIntegrity::V2(vc) => vc.verify(&params).await,
+    }
+    .map_err(ProblemDetails::from)?;
+
+    let mut res = match verification {
+        Ok(()) => VerifyResponse::verified(vec!["proof".to_string()]),
+        Err(Invalid::Proof(e)) => VerifyResponse::failed(vec![ProblemDetails::new(
+            PredefinedProblemType::CryptographicSecurityError,
+            "invalid proof".to_string(),
+            e.to_string(),
+            anyhow!("Invalid proof: {:?}", e),
+        )]),
+        Err(Invalid::Claims(e)) => VerifyResponse::failed(vec![ProblemDetails::new(
+            PredefinedProblemType::MalformedValueError,
+            "invalid claims".to_string(),
+            e.to_string(),
+            anyhow!("Invalid claims: {:?}", e),
+        )]),
+    };
+    res.warnings = req
+        .options
+        .unsupported_checks()
+        .map(|check| {
+            ProblemDetails::new(
+                PredefinedProblemType::MalformedValueError,
+                "unsupported check".to_string(),
+                format!(
+                    "The check `{}` is not performed. Supported checks: {:?}",
+                    check,
+                    VerifyRequestOptions::SUPPORTED_CHECKS
+                ),
+                anyhow!("Unsupported check: {}", check),
+            )
+        })
+        .collect();
+    Ok(res)
+}
+
+/// `POST /credentials/derive`
+///
+/// Derives a selective disclosure from a credential with an `ecdsa-sd-2023` or `bbs-2023` base proof,
+/// as a holder would. The base proof is not verified here.
+#[axum::debug_handler]
+pub async fn derive(
+    Extension(issuer_keys): Extension<IssuerKeys>,
+    did_web: Option<Extension<DidWeb>>,
+    JsonReq(req): JsonReq<DeriveRequest>,
+) -> Result<SuccessRes<DeriveResponse>, VcApiError> {
+    let vm_resolver = CustomVerificationMethodResolver::new(issuer_keys)
+        .with_did_web(did_web.map(|Extension(did_web)| did_web));
+    let params = VerificationParameters::from_resolver(&vm_resolver);
+    let options = AnySelectionOptions {
+        selective_pointers: req.options.selective_pointers,
+        ..Default::default()
+    };
+
+    let derived = req
+        .verifiable_credential
+        .select(params, options)
+        .await
+        .map_err(|e| {
+            ProblemDetails::new(
+                PredefinedProblemType::MalformedValueError,
+                "derivation error".to_string(),
+                format!("failed to derive a credential: {}", e),
+                anyhow!("Failed to derive a credential: {:?}", e),
+            )
+        })?;
+
+    Ok(SuccessRes {
+        status: http::StatusCode::CREATED,
+        body: DeriveResponse {
+            verifiable_credential: derived,
+        },
+    })
+}
+
+/// `POST /credentials/status`
+///
+/// Updates the status of a credential issued with `BitstringStatusListEntry`s and signs the updated
+/// status list credentials again.
+#[axum::debug_handler]
+pub async fn update_status(
+    Extension(issuer_keys): Extension<IssuerKeys>,
+    Extension(status_lists): Extension<StatusLists>,
+    did_web: Option<Extension<DidWeb>>,
+    JsonReq(req): JsonReq<UpdateCredentialStatusRequest>,
+) -> Result<http::StatusCode, VcApiError> {
+    update_credential_status(
+        &issuer_keys,
+        &status_lists,
+        did_web.as_ref().map(|Extension(did_web)| did_web),
+        req,
+    )
+    .await
+}
+
+pub(crate) async fn update_credential_status(
+    issuer_keys: &IssuerKeys,
+    status_lists: &StatusLists,
+    did_web: Option<&DidWeb>,
+    req: UpdateCredentialStatusRequest,
+) -> Result<http::StatusCode, VcApiError> {
+    let updates = req
+        .credential_status
+        .iter()
+        .map(|update| (update.status_purpose, update.status))
+        .collect::<Vec<_>>();
+    status_lists.update_statuses(&req.credential_id, &updates)?;
+
+    let updated_purposes = StatusPurpose::ALL.into_iter().filter(|purpose| {
+        req.credential_status
+            .iter()
+            .any(|update| update.status_purpose == *purpose)
+    });
+    for purpose in updated_purposes {
+        status_lists.sign(issuer_keys, did_web, purpose).await?;
+    }
+
+    Ok(http::StatusCode::OK)
+}
+
+/// `GET /credentials/{id}`
+///
+/// Credentials issued by issuer profiles are not found here.
+#[axum::debug_handler]
+pub async fn get_credential(
+    Extension(credential_store): Extension<DynCredentialStore>,
+    Path(id): Path<String>,
+) -> Result<SuccessRes<GetCredentialResponse>, VcApiError> {
+    find_credential(&ScopedCredentialStore::global(credential_store), &id)
+}
+
+/// `DELETE /credentials/{id}`
+///
+/// Credentials issued by issuer profiles are not found here.
+#[axum::debug_handler]
+pub async fn delete_credential(
+    Extension(credential_store): Extension<DynCredentialStore>,
+    Path(id): Path<String>,
+) -> Result<http::StatusCode, VcApiError> {
+    remove_credential(&ScopedCredentialStore::global(credential_store), &id)
+}
+
+/// Get the credential stored under `id`, for `GET .../credentials/{id}`.
+pub(crate) fn find_credential(
+    credential_store: &dyn CredentialStore,
+    id: &str,
+) -> Result<SuccessRes<GetCredentialResponse>, VcApiError> {
+    let verifiable_credential = credential_store
+        .get(id)?
+        .ok_or_else(|| credential_not_found_error(id))?;
+    Ok(SuccessRes {
+        status: http::StatusCode::OK,
+        body: GetCredentialResponse {
+            verifiable_credential,
+        },
+    })
+}
+
+/// Delete the credential stored under `id`, for `DELETE .../credentials/{id}`.
+pub(crate) fn remove_credential(
+    credential_store: &dyn CredentialStore,
+    id: &str,
+) -> Result<http::StatusCode, VcApiError> {
+    if credential_store.delete(id)? {
+        Ok(http::StatusCode::NO_CONTENT)
+    } else {
+        Err(credential_not_found_error(id).into())
+    }
+}
+
+/// Add `BitstringStatusListEntry`s to a VCDM v2.0 credential secured without SD-JWT.
+///
+/// An `id` is minted for a credential without identifiers, so that its status can be updated later.
+fn add_credential_status(
+    status_lists: &StatusLists,
+    services: &IssueServices,
+    req: &mut IssueRequest,
+) -> Result<(), ProblemDetails> {
+    let AnyVerifiableCredential::V2(credential) = &req.credential else {
+        return Ok(());
+    };
+    if req.options.securing_mechanism == SecuringMechanism::SdJwt
+        || StatusLists::has_credential_status(credential)?
+    {
+        return Ok(());
+    }
+
+    if req.credential_id().is_none() {
+        let minter = services.credential_id_minter.clone().unwrap_or_default();
+        req.credential.set_id(mint_credential_id(&minter)?);
+    }
+    let credential_id = req
+        .credential_id()
+        .expect("an id is minted above")
+        .to_string();
+    let credential = req
+        .credential
+        .as_v2()
+        .expect("VCDM v1.1 credentials are returned above");
+    let credential = status_lists.add_credential_status(credential, &credential_id)?;
+    req.credential = AnyVerifiableCredential::V2(credential);
+    Ok(())
+}
+
+fn credential_not_found_error(id: &str) -> ProblemDetails {
+    ProblemDetails::new(
+        CustomProblemType::CredentialNotFoundError,
+        "credential not found".to_string(),
+        format!("No credential found for `{}`.", id),
+        anyhow!("No credential found for `{}`", id),
+    )
+}
+
+fn mint_credential_id(minter: &CredentialIdMinter) -> Result<UriBuf, ProblemDetails> {
+    let id = minter.mint();
+    UriBuf::new(id.clone().into_bytes()).map_err(|_| {
+        ProblemDetails::new(
+            CustomProblemType::UnknownError,
+            "invalid credential id".to_string(),
+            format!("Minted credential id `{}` is not a valid URI.", id),
+            anyhow!("Minted credential id `{}` is not a valid URI", id),
+        )
+    })
+}
+
 fn validate_issue_request(req: &IssueRequest) -> Result<(), VcApiError> {
+    match &req.credential {
+        AnyVerifiableCredential::V1(_) => validate_vcdm_v1_credential(req)?,
+        AnyVerifiableCredential::V2(credential) => validate_vcdm_v2_credential(credential)?,
+    }
+
+    // `type` and `cryptosuite` options are only meaningful for Data Integrity proofs.
+    if req.options.securing_mechanism != SecuringMechanism::DataIntegrity
+        && (req.options.r#type.is_some() || req.options.cryptosuite.is_some())
+    {
+        return Err(VcApiError {
+            status: http::StatusCode::BAD_REQUEST,
+            problem_details: ProblemDetails::new(
+                PredefinedProblemType::MalformedValueError,
+                "validation error (options)".to_string(),
+                "`type` and `cryptosuite` options can only be used with the `data-integrity` securing mechanism."
+                    .to_string(),
+                anyhow!("`type` and `cryptosuite` options can only be used with the `data-integrity` securing mechanism."),
+            ),
+        });
+    }
+
+    if let Some(method) = &req.options.verification_method {
+        if Iri::new(method.as_str()).is_err() {
+            return Err(VcApiError {
+                status: http::StatusCode::BAD_REQUEST,
+                problem_details: ProblemDetails::new(
+                    PredefinedProblemType::MalformedValueError,
+                    "validation error (options)".to_string(),
+                    "`verificationMethod` option must be an IRI.".to_string(),
+                    anyhow!("`verificationMethod` option is not an IRI: {}", method),
+                ),
+            });
+        }
+    }
+
+    if req.options.securing_mechanism != SecuringMechanism::SdJwt
+        && req.options.holder_jwk.is_some()
+    {
+        return Err(VcApiError {
+            status: http::StatusCode::BAD_REQUEST,
+            problem_details: ProblemDetails::new(
+                PredefinedProblemType::MalformedValueError,
+                "validation error (options)".to_string(),
+                "`holderJwk` option can only be used with the `sd-jwt` securing mechanism."
+                    .to_string(),
+                anyhow!(
+                    "`holderJwk` option can only be used with the `sd-jwt` securing mechanism."
+                ),
+            ),
+        });
+    }
+
+    Ok(())
+}
+
+fn validate_vcdm_v1_credential(req: &IssueRequest) -> Result<(), VcApiError> {
+    // <https://www.w3.org/TR/vc-jose-cose/#securing-vcs-with-jose>
+    // VC-JOSE-COSE secures VCDM v2.0 credentials only.
+    if matches!(
+        req.options.securing_mechanism,
+        SecuringMechanism::Jose | SecuringMechanism::Cose
+    ) {
+        return Err(VcApiError {
+            status: http::StatusCode::BAD_REQUEST,
+            problem_details: ProblemDetails::new(
+                PredefinedProblemType::MalformedValueError,
+                "validation error (options)".to_string(),
+                "`jose` and `cose` securing mechanisms can only be used with VCDM v2.0 credentials."
+                    .to_string(),
+                anyhow!("`jose` and `cose` securing mechanisms can only be used with VCDM v2.0 credentials."),
+            ),
+        });
+    }
+
+    Ok(())
+}
+
+fn validate_vcdm_v2_credential(credential: &VerifiableCredentialV2) -> Result<(), VcApiError> {
     // <https://www.w3.org/TR/vc-data-model-2.0/#credential-subject>
     // > A verifiable credential contains claims about one or more subjects.
-    let sub = req.credential.credential_subjects();
+    let sub = credential.credential_subjects();
     if sub.is_empty() || sub.iter().any(|s| s.is_empty()) {
         return Err(VcApiError {
             status: http::StatusCode::BAD_REQUEST,
@@ -73,17 +613,59 @@ fn validate_issue_request(req: &IssueRequest) -> Result<(), VcApiError> {
     Ok(())
 }
 
-async fn create_vc_with_data_integrity(
-    req: &IssueRequest,
+pub(crate) async fn create_vc_with_data_integrity<T>(
+    credential: T,
+    options: &IssueRequestOptions,
     issuer_keys: IssuerKeys,
     vm: &VerificationMethod,
     vm_resolver: &CustomVerificationMethodResolver,
-) -> Result<VerifiableCredentialV2DataIntegrity, ProblemDetails> {
-    let suite = vm.try_to_suite()?;
+) -> Result<DataIntegrity<T, AnySuite>, ProblemDetails>
+where
+    AnySuite: for<'a> CryptographicSuiteSigning<
+        T,
+        SignatureEnvironment,
+        &'a CustomVerificationMethodResolver,
+        LocalSigner<IssuerKeys>,
+    >,
+{
+    let suite = select_data_integrity_suite(options, vm)?;
+    sign_with_data_integrity(credential, options, suite, issuer_keys, vm, vm_resolver).await
+}
+
+/// Select the cryptosuite to sign with `vm`, from the `type`, `cryptosuite` and `mandatoryPointers` options.
+fn select_data_integrity_suite(
+    options: &IssueRequestOptions,
+    vm: &VerificationMethod,
+) -> Result<AnySuite, ProblemDetails> {
+    let mut requested = options.requested_cryptosuite()?;
+    if options.mandatory_pointers.is_some() {
+        let jwk = vm.is_multikey().then(|| vm.try_to_jwk()).transpose()?;
+        if jwk.is_none() && requested.is_none() {
+            warn!("`mandatoryPointers` are ignored: the verification method is not a Multikey");
+        }
+        requested = RequestedCryptosuite::for_mandatory_pointers(requested, jwk.as_ref())?;
+    }
+    vm.try_to_suite(requested)
+}
 
+async fn sign_with_data_integrity<T>(
+    credential: T,
+    options: &IssueRequestOptions,
+    suite: AnySuite,
+    issuer_keys: IssuerKeys,
+    vm: &VerificationMethod,
+    vm_resolver: &CustomVerificationMethodResolver,
+) -> Result<DataIntegrity<T, AnySuite>, ProblemDetails>
+where
+    AnySuite: for<'a> CryptographicSuiteSigning<
+        T,
+        SignatureEnvironment,
+        &'a CustomVerificationMethodResolver,
+        LocalSigner<IssuerKeys>,
+    >,
+{
     let mut signature_options: AnySignatureOptions = Default::default();
-    signature_options.mandatory_pointers =
-        req.options.mandatory_pointers.clone().unwrap_or_default();
+    signature_options.mandatory_pointers = options.mandatory_pointers.clone().unwrap_or_default();
 
     let proof_options = AnyInputOptions {
         verification_method: Some(ReferenceOrOwned::Reference(vm.to_id_iri())),
@@ -93,7 +675,7 @@ async fn create_vc_with_data_integrity(
     let vc = suite
         .sign_with(
             SignatureEnvironment::default(),
-            req.credential.clone(),
+            credential,
             vm_resolver,
             issuer_keys.into_local_signer(),
             proof_options,
@@ -106,13 +688,30 @@ async fn create_vc_with_data_integrity(
 
 #[cfg(test)]
 mod tests {
-    use ssi::{claims::vc::v2::Credential, verification_methods::ProofPurpose};
+    use std::sync::Arc;
+
+    use josekit::{
+        jwk::Jwk,
+        jws::{self, EdDSA, JwsVerifier, ES256, ES256K, ES384},
+    };
+    use serde_json::{json, Value};
+    use ssi::{claims::vc::v2::Credential, verification_methods::ProofPurpose, JWK};
 
     use crate::{
-        test_jwks::{ISSMOCK_PRIV_EC_P384, ISSMOCK_PRIV_OKP_ED25519},
+        credential_store::InMemoryCredentialStore,
+        test_jwks::{
+            ISSMOCK_PRIV_EC_P256, ISSMOCK_PRIV_EC_P256_DIDKEY, ISSMOCK_PRIV_EC_P384,
+            ISSMOCK_PRIV_EC_SECP256K1, ISSMOCK_PRIV_OKP_ED25519, ISSMOCK_PRIV_OKP_ED25519_DIDKEY,
+            ISSMOCK_PUB_EC_P256, ISSMOCK_PUB_EC_P384, ISSMOCK_PUB_EC_SECP256K1,
+            ISSMOCK_PUB_OKP_ED25519, JWK_EC_P384_PRIV,
+        },
         test_tracing::init_tracing,
         test_vc_json::{
-            misc::{ISSUER_DIDKEY_EC_P384, ISSUER_DIDKEY_OKP_ED25519},
+            misc::{
+                ISSUER_DIDKEY_EC_P256, ISSUER_DIDKEY_EC_P384, ISSUER_DIDKEY_EC_SECP256K1,
+                ISSUER_DIDKEY_OKP_ED25519,
+            },
+            vc_data_model_1_1,
             vc_data_model_2_0_test_suite::{
                 CREDENTIAL_OK, CREDENTIAL_SUBJECT_NO_CLAIMS_FAIL, README_ALUMNI,
             },
@@ -125,12 +724,110 @@ mod tests {
     async fn issue_(req: IssueRequest) -> Result<SuccessRes<IssueResponse>, VcApiError> {
         init_tracing();
 
+        let issuer_keys = IssuerKeys::new(vec![
+            ISSMOCK_PRIV_OKP_ED25519,
+            ISSMOCK_PRIV_EC_P384,
+            ISSMOCK_PRIV_EC_P256,
+            ISSMOCK_PRIV_EC_SECP256K1,
+        ]);
+        issue_credential(issuer_keys, &IssueServices::default(), req).await
+    }
+
+    fn with_status_lists(status_lists: &StatusLists) -> IssueServices {
+        IssueServices {
+            status_lists: Some(status_lists.clone()),
+            ..Default::default()
+        }
+    }
+
+    async fn verify_(verifiable_credential: Value) -> Result<VerifyResponse, VcApiError> {
+        verify_with_checks_(verifiable_credential, &["proof"]).await
+    }
+
+    async fn verify_with_checks_(
+        verifiable_credential: Value,
+        checks: &[&str],
+    ) -> Result<VerifyResponse, VcApiError> {
+        init_tracing();
+
         let issuer_keys = Extension(IssuerKeys::new(vec![
             ISSMOCK_PRIV_OKP_ED25519,
             ISSMOCK_PRIV_EC_P384,
+            ISSMOCK_PRIV_EC_P256,
+            ISSMOCK_PRIV_EC_SECP256K1,
         ]));
-        let req = JsonReq(req);
-        issue(issuer_keys, req.clone()).await
+        let req: VerifyRequest = serde_json::from_value(json!({
+            "verifiableCredential": verifiable_credential,
+            "options": { "checks": checks },
+        }))
+        .expect("Failed to deserialize VerifyRequest");
+        verify(issuer_keys, None, JsonReq(req)).await
+    }
+
+    async fn derive_(
+        verifiable_credential: Value,
+        selective_pointers: &[&str],
+    ) -> Result<SuccessRes<DeriveResponse>, VcApiError> {
+        init_tracing();
+
+        let issuer_keys = Extension(IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519]));
+        let req: DeriveRequest = serde_json::from_value(json!({
+            "verifiableCredential": verifiable_credential,
+            "options": { "selectivePointers": selective_pointers },
+        }))
+        .expect("Failed to deserialize DeriveRequest");
+        derive(issuer_keys, None, JsonReq(req)).await
+    }
+
+    /// Derive a selective disclosure and returns it as a JSON value.
+    async fn derived_vc_json(vc: Value, selective_pointers: &[&str]) -> anyhow::Result<Value> {
+        let res = derive_(vc, selective_pointers).await?;
+        assert_eq!(res.status, 201);
+        Ok(serde_json::to_value(&res.body)?)
+    }
+
+    /// Issue a credential and returns it as a JSON value.
+    async fn issued_vc_json(req: &str) -> anyhow::Result<Value> {
+        let req: IssueRequest = serde_json::from_str(req)?;
+        let res = issue_(req).await?;
+        Ok(serde_json::to_value(&res.body)?)
+    }
+
+    /// Issue a credential with the given `cryptosuite` option.
+    async fn issue_with_cryptosuite(
+        req: &str,
+        cryptosuite: &str,
+    ) -> Result<SuccessRes<IssueResponse>, VcApiError> {
+        let mut req: Value = serde_json::from_str(req).expect("invalid request JSON");
+        req["options"] = json!({ "cryptosuite": cryptosuite });
+        let req: IssueRequest = serde_json::from_value(req).expect("invalid IssueRequest");
+        issue_(req).await
+    }
+
+    async fn assert_issue_with_cryptosuite_success(
+        req: &str,
+        cryptosuite: &str,
+    ) -> anyhow::Result<()> {
+        let res = issue_with_cryptosuite(req, cryptosuite).await?;
+        assert_eq!(res.status, 201);
+
+        let vc = serde_json::to_value(&res.body)?;
+        assert_eq!(vc["proof"]["type"], "DataIntegrityProof");
+        assert_eq!(vc["proof"]["cryptosuite"], cryptosuite);
+
+        let verified = verify_(vc).await?;
+        assert!(verified.is_verified());
+
+        Ok(())
+    }
+
+    async fn assert_issue_with_cryptosuite_error(req: &str, cryptosuite: &str) {
+        let vc_api_error = issue_with_cryptosuite(req, cryptosuite).await.unwrap_err();
+        assert_eq!(vc_api_error.status, http::StatusCode::BAD_REQUEST);
+        assert_eq!(
+            vc_api_error.problem_details.code().unwrap(),
+            CustomProblemType::InvalidCryptosuiteError.code()
+        );
     }
 
     async fn assert_issue_with_data_integrity_proof_success(
@@ -142,8 +839,15 @@ mod tests {
         let res = issue_(req.clone()).await?;
         assert_eq!(res.status, 201);
 
-        let req_cred = &req.credential;
-        let res_cred = &res.body.verifiable_credential;
+        let req_cred = req
+            .credential
+            .as_v2()
+            .expect("should be a VCDM v2.0 credential");
+        let res_cred = res
+            .body
+            .verifiable_credential
+            .as_data_integrity()
+            .expect("should be secured with Data Integrity proof");
 
         // Other than `proof`, the response properties should be the same as the request.
         {
@@ -219,6 +923,16 @@ mod tests {
         .await
     }
 
+    #[tokio::test]
+    async fn test_issue_with_data_integrity_proof_success_issuer_didkey_ec_secp256k1(
+    ) -> anyhow::Result<()> {
+        assert_issue_with_data_integrity_proof_success(
+            ISSUER_DIDKEY_EC_SECP256K1,
+            "EcdsaSecp256k1Signature2019",
+        )
+        .await
+    }
+
     #[tokio::test]
     async fn test_issue_with_data_integrity_proof_error_empty_credential_subject(
     ) -> anyhow::Result<()> {
@@ -235,4 +949,981 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_issue_with_data_integrity_proof_success_vcdm_v1() -> anyhow::Result<()> {
+        let req: IssueRequest = serde_json::from_str(vc_data_model_1_1::CREDENTIAL_OK)?;
+
+        let res = issue_(req).await?;
+        assert_eq!(res.status, 201);
+
+        let vc = res
+            .body
+            .verifiable_credential
+            .as_data_integrity_v1()
+            .expect("should be secured with Data Integrity");
+        assert_eq!(vc.proofs.iter().count(), 1);
+
+        let json = serde_json::to_value(&res.body)?;
+        assert_eq!(
+            json["@context"][0],
+            "https://www.w3.org/2018/credentials/v1"
+        );
+        assert_eq!(json["issuanceDate"], "2010-01-01T19:23:24Z");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_jose_error_vcdm_v1() -> anyhow::Result<()> {
+        let mut req: Value = serde_json::from_str(vc_data_model_1_1::CREDENTIAL_OK)?;
+        req["options"] = json!({ "securingMechanism": "jose" });
+        let req: IssueRequest = serde_json::from_value(req)?;
+
+        let vc_api_error = issue_(req).await.unwrap_err();
+        assert_eq!(vc_api_error.status, http::StatusCode::BAD_REQUEST);
+        assert_eq!(
+            vc_api_error.problem_details.code().unwrap(),
+            PredefinedProblemType::MalformedValueError.code()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_status_lists_success() -> anyhow::Result<()> {
+        init_tracing();
+
+        let issuer_keys = IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519, ISSMOCK_PRIV_EC_P384]);
+        let status_lists = StatusLists::new("http://localhost:40080/status-lists");
+
+        let req: IssueRequest = serde_json::from_str(ISSUER_DIDKEY_OKP_ED25519)?;
+        let res = issue_credential(issuer_keys, &with_status_lists(&status_lists), req).await?;
+        assert_eq!(res.status, 201);
+
+        let json = serde_json::to_value(&res.body)?;
+        let entries = json["credentialStatus"].as_array().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["type"], "BitstringStatusListEntry");
+        assert_eq!(entries[0]["statusPurpose"], "revocation");
+        assert_eq!(entries[0]["statusListIndex"], "0");
+        assert_eq!(
+            entries[0]["statusListCredential"],
+            "http://localhost:40080/status-lists/revocation"
+        );
+        assert_eq!(entries[1]["statusPurpose"], "suspension");
+        // An id is minted to update the status later.
+        let id = json["id"].as_str().unwrap().to_string();
+        assert!(id.starts_with("urn:uuid:"));
+
+        // The proof covers the status entries.
+        let res = verify_(json).await?;
+        assert!(res.is_verified());
+
+        status_lists.update_status(&id, StatusPurpose::Revocation, true)?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_status_lists_error_no_index_allocated() -> anyhow::Result<()> {
+        init_tracing();
+
+        let issuer_keys = IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519, ISSMOCK_PRIV_EC_P384]);
+        let status_lists = StatusLists::new("http://localhost:40080/status-lists");
+        let services = with_status_lists(&status_lists);
+
+        // unresolvable issuer
+        let mut req: Value = serde_json::from_str(README_ALUMNI)?;
+        req["credential"]["issuer"] = json!("did:example:unknown-issuer");
+        let vc_api_error =
+            issue_credential(issuer_keys.clone(), &services, serde_json::from_value(req)?)
+                .await
+                .unwrap_err();
+        assert_eq!(vc_api_error.status, http::StatusCode::BAD_REQUEST);
+
+        // cryptosuite not supported by the issuer key
+        let mut req: Value = serde_json::from_str(README_ALUMNI)?;
+        req["credential"]["issuer"] = json!(ISSMOCK_PRIV_OKP_ED25519_DIDKEY);
+        req["options"] = json!({ "cryptosuite": "ecdsa-rdfc-2019" });
+        let vc_api_error =
+            issue_credential(issuer_keys.clone(), &services, serde_json::from_value(req)?)
+                .await
+                .unwrap_err();
+        assert_eq!(
+            vc_api_error.problem_details.code().unwrap(),
+            CustomProblemType::InvalidCryptosuiteError.code()
+        );
+
+        // issuer key not held by the mock
+        let mut req: Value = serde_json::from_str(README_ALUMNI)?;
+        req["credential"]["issuer"] = json!(ISSMOCK_PRIV_EC_P256_DIDKEY);
+        let vc_api_error =
+            issue_credential(issuer_keys.clone(), &services, serde_json::from_value(req)?)
+                .await
+                .unwrap_err();
+        assert_eq!(
+            vc_api_error.problem_details.code().unwrap(),
+            CustomProblemType::VerificationMethodResolutionError.code()
+        );
+
+        // duplicate id
+        let mut req: Value = serde_json::from_str(README_ALUMNI)?;
+        req["credential"]["issuer"] = json!(ISSMOCK_PRIV_OKP_ED25519_DIDKEY);
+        issue_credential(
+            issuer_keys.clone(),
+            &services,
+            serde_json::from_value(req.clone())?,
+        )
+        .await?;
+        let vc_api_error = issue_credential(issuer_keys, &services, serde_json::from_value(req)?)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            vc_api_error.problem_details.code().unwrap(),
+            PredefinedProblemType::MalformedValueError.code()
+        );
+
+        // Only the first issuance of the duplicate id took an index.
+        let req: IssueRequest = serde_json::from_str(ISSUER_DIDKEY_OKP_ED25519)?;
+        let vc =
+            status_lists.add_credential_status(req.credential.as_v2().unwrap(), "urn:uuid:next")?;
+        let json = serde_json::to_value(&vc)?;
+        assert_eq!(json["credentialStatus"][0]["statusListIndex"], "1");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_credential_id_minter_success() -> anyhow::Result<()> {
+        init_tracing();
+
+        let issuer_keys = IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519, ISSMOCK_PRIV_EC_P384]);
+        let services = IssueServices {
+            credential_id_minter: Some(CredentialIdMinter::new(
+                "https://issuer.example/credentials/",
+            )),
+            ..Default::default()
+        };
+
+        // The credential has no `id`.
+        let req: IssueRequest = serde_json::from_str(ISSUER_DIDKEY_OKP_ED25519)?;
+        let res = issue_credential(issuer_keys.clone(), &services, req).await?;
+        let json = serde_json::to_value(&res.body)?;
+        assert!(json["id"]
+            .as_str()
+            .unwrap()
+            .starts_with("https://issuer.example/credentials/"));
+
+        // `id` given by the client is kept.
+        let mut req: Value = serde_json::from_str(README_ALUMNI)?;
+        req["credential"]["issuer"] = json!(ISSMOCK_PRIV_OKP_ED25519_DIDKEY);
+        let req: IssueRequest = serde_json::from_value(req)?;
+        let res = issue_credential(issuer_keys, &services, req).await?;
+        let json = serde_json::to_value(&res.body)?;
+        assert_eq!(json["id"], "http://university.example/credentials/1872");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_cryptosuite_success_eddsa_rdfc_2022() -> anyhow::Result<()> {
+        assert_issue_with_cryptosuite_success(ISSUER_DIDKEY_OKP_ED25519, "eddsa-rdfc-2022").await
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_cryptosuite_success_eddsa_jcs_2022() -> anyhow::Result<()> {
+        assert_issue_with_cryptosuite_success(ISSUER_DIDKEY_OKP_ED25519, "eddsa-jcs-2022").await
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_cryptosuite_success_ecdsa_rdfc_2019() -> anyhow::Result<()> {
+        assert_issue_with_cryptosuite_success(ISSUER_DIDKEY_EC_P384, "ecdsa-rdfc-2019").await
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_cryptosuite_success_ecdsa_jcs_2019() -> anyhow::Result<()> {
+        assert_issue_with_cryptosuite_success(ISSUER_DIDKEY_EC_P384, "ecdsa-jcs-2019").await
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_cryptosuite_success_ecdsa_rdfc_2019_p256() -> anyhow::Result<()> {
+        assert_issue_with_cryptosuite_success(ISSUER_DIDKEY_EC_P256, "ecdsa-rdfc-2019").await
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_cryptosuite_success_bbs_2023() -> anyhow::Result<()> {
+        init_tracing();
+
+        let bls12381g2 = JWK::generate_bls12381g2().to_string();
+        let issuer_keys = IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519, &bls12381g2]);
+        let issuer = issuer_keys.key_pairs()[1].1.to_did_key();
+
+        let mut req: Value = serde_json::from_str(ISSUER_DIDKEY_OKP_ED25519)?;
+        req["credential"]["issuer"] = json!(issuer);
+        req["credential"]["credentialSubject"]["name"] = json!("Alice");
+        req["options"] = json!({
+            "cryptosuite": "bbs-2023",
+            "mandatoryPointers": ["/issuer"],
+        });
+        let req: IssueRequest = serde_json::from_value(req)?;
+
+        let res = issue_credential(issuer_keys, &IssueServices::default(), req).await?;
+        assert_eq!(res.status, 201);
+
+        let vc = serde_json::to_value(&res.body)?;
+        assert_eq!(vc["proof"]["type"], "DataIntegrityProof");
+        assert_eq!(vc["proof"]["cryptosuite"], "bbs-2023");
+        assert_eq!(
+            vc["proof"]["verificationMethod"]
+                .as_str()
+                .unwrap()
+                .split('#')
+                .next(),
+            Some(issuer.as_str())
+        );
+        // base proof header (0xd9 0x5d 0x02) in multibase base64url
+        assert!(vc["proof"]["proofValue"]
+            .as_str()
+            .unwrap()
+            .starts_with("u2V0C"));
+
+        let derived = derived_vc_json(vc, &["/credentialSubject/name"]).await?;
+        assert_eq!(derived["issuer"], issuer);
+        assert_eq!(derived["credentialSubject"]["name"], "Alice");
+        assert!(derived["credentialSubject"].get("id").is_none());
+        assert_eq!(derived["proof"]["cryptosuite"], "bbs-2023");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_derive_error_not_base_proof() -> anyhow::Result<()> {
+        let vc = issued_vc_json(ISSUER_DIDKEY_OKP_ED25519).await?;
+
+        let vc_api_error = derive_(vc, &["/credentialSubject/id"]).await.unwrap_err();
+        assert_eq!(vc_api_error.status, http::StatusCode::BAD_REQUEST);
+        assert_eq!(
+            vc_api_error.problem_details.code().unwrap(),
+            PredefinedProblemType::MalformedValueError.code()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_mandatory_pointers_success_ecdsa_sd_2023() -> anyhow::Result<()> {
+        let mut req: Value = serde_json::from_str(ISSUER_DIDKEY_EC_P256)?;
+        req["credential"]["credentialSubject"]["name"] = json!("Alice");
+        req["credential"]["credentialSubject"]["age"] = json!(20);
+        req["options"] = json!({ "mandatoryPointers": ["/issuer"] });
+        let req: IssueRequest = serde_json::from_value(req)?;
+
+        let res = issue_(req).await?;
+        assert_eq!(res.status, 201);
+
+        let vc = serde_json::to_value(&res.body)?;
+        assert_eq!(vc["proof"]["cryptosuite"], "ecdsa-sd-2023");
+        // base proof header (0xd9 0x5d 0x00) in multibase base64url
+        assert!(vc["proof"]["proofValue"]
+            .as_str()
+            .unwrap()
+            .starts_with("u2V0A"));
+
+        let derived = derived_vc_json(vc, &["/credentialSubject/name"]).await?;
+        assert_eq!(derived["issuer"], ISSMOCK_PRIV_EC_P256_DIDKEY);
+        assert_eq!(derived["credentialSubject"]["name"], "Alice");
+        assert!(derived["credentialSubject"].get("age").is_none());
+        // derived proof header (0xd9 0x5d 0x01)
+        assert!(derived["proof"]["proofValue"]
+            .as_str()
+            .unwrap()
+            .starts_with("u2V0B"));
+
+        let verified = verify_(derived).await?;
+        assert!(verified.is_verified());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_mandatory_pointers_success_not_multikey() -> anyhow::Result<()> {
+        // A URL issuer is signed with JsonWebKey2020, and the pointers have no effect.
+        let mut req: Value = serde_json::from_str(CREDENTIAL_OK)?;
+        req["options"] = json!({ "mandatoryPointers": ["/issuer"] });
+        let req: IssueRequest = serde_json::from_value(req)?;
+
+        let res = issue_(req).await?;
+        assert_eq!(res.status, 201);
+
+        let vc = serde_json::to_value(&res.body)?;
+        assert_eq!(vc["proof"]["type"], "JsonWebSignature2020");
+        let verified = verify_(vc).await?;
+        assert!(verified.is_verified());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_mandatory_pointers_error_no_sd_key() -> anyhow::Result<()> {
+        for req in [ISSUER_DIDKEY_OKP_ED25519, ISSUER_DIDKEY_EC_P384] {
+            let mut req: Value = serde_json::from_str(req)?;
+            req["options"] = json!({ "mandatoryPointers": ["/issuer"] });
+            let req: IssueRequest = serde_json::from_value(req)?;
+
+            let vc_api_error = issue_(req).await.unwrap_err();
+            assert_eq!(vc_api_error.status, http::StatusCode::BAD_REQUEST);
+            assert_eq!(
+                vc_api_error.problem_details.code().unwrap(),
+                CustomProblemType::InvalidCryptosuiteError.code()
+            );
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_mandatory_pointers_error_not_sd_cryptosuite() -> anyhow::Result<()> {
+        let mut req: Value = serde_json::from_str(ISSUER_DIDKEY_EC_P256)?;
+        req["options"] =
+            json!({ "mandatoryPointers": ["/issuer"], "cryptosuite": "ecdsa-rdfc-2019" });
+        let req: IssueRequest = serde_json::from_value(req)?;
+
+        let vc_api_error = issue_(req).await.unwrap_err();
+        assert_eq!(vc_api_error.status, http::StatusCode::BAD_REQUEST);
+        assert_eq!(
+            vc_api_error.problem_details.code().unwrap(),
+            CustomProblemType::InvalidCryptosuiteError.code()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_cryptosuite_error_key_mismatch() {
+        assert_issue_with_cryptosuite_error(ISSUER_DIDKEY_EC_P384, "eddsa-rdfc-2022").await;
+        assert_issue_with_cryptosuite_error(ISSUER_DIDKEY_OKP_ED25519, "ecdsa-rdfc-2019").await;
+        assert_issue_with_cryptosuite_error(ISSUER_DIDKEY_OKP_ED25519, "bbs-2023").await;
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_cryptosuite_error_unknown() {
+        assert_issue_with_cryptosuite_error(ISSUER_DIDKEY_OKP_ED25519, "unknown-2099").await;
+    }
+
+    /// Issue a credential with `securingMechanism: jose`, and verify the enveloped JWS with the issuer's public key.
+    async fn assert_issue_with_jose_success(
+        req: &str,
+        issuer_public_jwk: &str,
+        expected_alg: &str,
+    ) -> anyhow::Result<()> {
+        let mut req: Value = serde_json::from_str(req)?;
+        req["options"] = json!({ "securingMechanism": "jose" });
+        let req: IssueRequest = serde_json::from_value(req)?;
+
+        let res = issue_(req.clone()).await?;
+        assert_eq!(res.status, 201);
+
+        let enveloped = res
+            .body
+            .verifiable_credential
+            .as_enveloped()
+            .expect("should be an EnvelopedVerifiableCredential");
+        assert_eq!(enveloped.r#type, "EnvelopedVerifiableCredential");
+
+        let jwt = enveloped
+            .id
+            .strip_prefix("data:application/vc+jwt,")
+            .expect("id should be a data URL of application/vc+jwt");
+
+        let public_jwk = Jwk::from_bytes(issuer_public_jwk)?;
+        let verifier: Box<dyn JwsVerifier> = match expected_alg {
+            "EdDSA" => Box::new(EdDSA.verifier_from_jwk(&public_jwk)?),
+            "ES256" => Box::new(ES256.verifier_from_jwk(&public_jwk)?),
+            "ES384" => Box::new(ES384.verifier_from_jwk(&public_jwk)?),
+            "ES256K" => Box::new(ES256K.verifier_from_jwk(&public_jwk)?),
+            _ => unreachable!(),
+        };
+        let (payload, header) = jws::deserialize_compact(jwt, verifier.as_ref())?;
+        assert_eq!(header.algorithm(), Some(expected_alg));
+        assert_eq!(header.token_type(), Some("vc+jwt"));
+
+        let payload: Value = serde_json::from_slice(&payload)?;
+        assert_eq!(payload, serde_json::to_value(&req.credential)?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_jose_success_issuer_didkey_okp_ed25519() -> anyhow::Result<()> {
+        assert_issue_with_jose_success(ISSUER_DIDKEY_OKP_ED25519, ISSMOCK_PUB_OKP_ED25519, "EdDSA")
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_jose_success_issuer_didkey_ec_p384() -> anyhow::Result<()> {
+        assert_issue_with_jose_success(ISSUER_DIDKEY_EC_P384, ISSMOCK_PUB_EC_P384, "ES384").await
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_jose_success_issuer_didkey_ec_p256() -> anyhow::Result<()> {
+        assert_issue_with_jose_success(ISSUER_DIDKEY_EC_P256, ISSMOCK_PUB_EC_P256, "ES256").await
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_jose_success_issuer_didkey_ec_secp256k1() -> anyhow::Result<()> {
+        assert_issue_with_jose_success(
+            ISSUER_DIDKEY_EC_SECP256K1,
+            ISSMOCK_PUB_EC_SECP256K1,
+            "ES256K",
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_jose_error_cryptosuite() -> anyhow::Result<()> {
+        let mut req: Value = serde_json::from_str(ISSUER_DIDKEY_OKP_ED25519)?;
+        req["options"] = json!({ "securingMechanism": "jose", "cryptosuite": "eddsa-rdfc-2022" });
+        let req: IssueRequest = serde_json::from_value(req)?;
+
+        let vc_api_error = issue_(req).await.unwrap_err();
+        assert_eq!(vc_api_error.status, http::StatusCode::BAD_REQUEST);
+        assert_eq!(
+            vc_api_error.problem_details.code().unwrap(),
+            PredefinedProblemType::MalformedValueError.code()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_cose_success() -> anyhow::Result<()> {
+        let mut req: Value = serde_json::from_str(ISSUER_DIDKEY_EC_P384)?;
+        req["options"] = json!({ "securingMechanism": "cose" });
+        let req: IssueRequest = serde_json::from_value(req)?;
+
+        let res = issue_(req).await?;
+        assert_eq!(res.status, 201);
+
+        let json = serde_json::to_value(&res.body)?;
+        assert_eq!(json["type"], "EnvelopedVerifiableCredential");
+        assert!(json["id"]
+            .as_str()
+            .unwrap()
+            .starts_with("data:application/vc+cose;base64,"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_sd_jwt_success() -> anyhow::Result<()> {
+        let mut req: Value = serde_json::from_str(README_ALUMNI)?;
+        req["credential"]["issuer"] = json!(ISSMOCK_PRIV_OKP_ED25519_DIDKEY);
+        req["options"] = json!({
+            "securingMechanism": "sd-jwt",
+            "holderJwk": serde_json::from_str::<Value>(ISSMOCK_PUB_EC_P384)?,
+        });
+        let req: IssueRequest = serde_json::from_value(req)?;
+
+        let res = issue_(req).await?;
+        assert_eq!(res.status, 201);
+
+        let sd_jwt = res
+            .body
+            .verifiable_credential
+            .as_sd_jwt()
+            .expect("should be an SD-JWT VC");
+        assert_eq!(sd_jwt.disclosures.len(), 1);
+        assert_eq!(sd_jwt.disclosures[0].claim, "alumniOf");
+
+        let mut parts = sd_jwt.sd_jwt.split('~');
+        let jwt = parts.next().unwrap();
+        assert_eq!(
+            parts.next(),
+            Some(sd_jwt.disclosures[0].disclosure.as_str())
+        );
+        assert_eq!(parts.next(), Some(""));
+
+        let verifier = EdDSA.verifier_from_jwk(&Jwk::from_bytes(ISSMOCK_PUB_OKP_ED25519)?)?;
+        let (payload, header) = jws::deserialize_compact(jwt, &verifier)?;
+        assert_eq!(header.token_type(), Some("dc+sd-jwt"));
+
+        let payload: Value = serde_json::from_slice(&payload)?;
+        assert_eq!(payload["iss"], ISSMOCK_PRIV_OKP_ED25519_DIDKEY);
+        assert_eq!(payload["cnf"]["jwk"]["crv"], "P-384");
+        assert_eq!(payload["_sd"][0], json!(sd_jwt.disclosures[0].digest));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_holder_jwk_error_not_sd_jwt() -> anyhow::Result<()> {
+        let mut req: Value = serde_json::from_str(ISSUER_DIDKEY_OKP_ED25519)?;
+        req["options"] = json!({
+            "holderJwk": serde_json::from_str::<Value>(ISSMOCK_PUB_EC_P384)?,
+        });
+        let req: IssueRequest = serde_json::from_value(req)?;
+
+        let vc_api_error = issue_(req).await.unwrap_err();
+        assert_eq!(
+            vc_api_error.problem_details.code().unwrap(),
+            PredefinedProblemType::MalformedValueError.code()
+        );
+
+        Ok(())
+    }
+
+    async fn update_status_(
+        status_lists: &StatusLists,
+        req: Value,
+    ) -> Result<http::StatusCode, VcApiError> {
+        let issuer_keys = IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519, ISSMOCK_PRIV_EC_P384]);
+        let req: UpdateCredentialStatusRequest = serde_json::from_value(req)
+            .expect("Failed to deserialize UpdateCredentialStatusRequest");
+        update_status(
+            Extension(issuer_keys),
+            Extension(status_lists.clone()),
+            None,
+            JsonReq(req),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_update_status_success() -> anyhow::Result<()> {
+        init_tracing();
+
+        let issuer_keys = IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519, ISSMOCK_PRIV_EC_P384]);
+        let status_lists = StatusLists::new("http://localhost:40080/status-lists");
+
+        let mut req: Value = serde_json::from_str(README_ALUMNI)?;
+        req["credential"]["issuer"] = json!(ISSMOCK_PRIV_OKP_ED25519_DIDKEY);
+        let req: IssueRequest = serde_json::from_value(req)?;
+        issue_credential(issuer_keys, &with_status_lists(&status_lists), req).await?;
+
+        let status = update_status_(
+            &status_lists,
+            json!({
+                "credentialId": "http://university.example/credentials/1872",
+                "credentialStatus": [
+                    { "type": "BitstringStatusListEntry", "statusPurpose": "revocation" }
+                ],
+            }),
+        )
+        .await?;
+        assert_eq!(status, http::StatusCode::OK);
+
+        // Only the updated status list is signed again.
+        assert!(status_lists
+            .signed_credential(StatusPurpose::Revocation)
+            .is_some());
+        assert!(status_lists
+            .signed_credential(StatusPurpose::Suspension)
+            .is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_status_success_credential_id_option() -> anyhow::Result<()> {
+        init_tracing();
+
+        let issuer_keys = IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519, ISSMOCK_PRIV_EC_P384]);
+        let status_lists = StatusLists::new("http://localhost:40080/status-lists");
+
+        let mut req: Value = serde_json::from_str(ISSUER_DIDKEY_OKP_ED25519)?;
+        req["options"] =
+            json!({ "credentialId": "example.com/ad5d541f-db7a-4bff-97e1-d403ce403767" });
+        let req: IssueRequest = serde_json::from_value(req)?;
+        issue_credential(issuer_keys, &with_status_lists(&status_lists), req).await?;
+
+        let status = update_status_(
+            &status_lists,
+            json!({
+                "credentialId": "example.com/ad5d541f-db7a-4bff-97e1-d403ce403767",
+                "credentialStatus": [
+                    { "type": "BitstringStatusListEntry", "statusPurpose": "suspension" }
+                ],
+            }),
+        )
+        .await?;
+        assert_eq!(status, http::StatusCode::OK);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_status_error_partially_invalid() -> anyhow::Result<()> {
+        init_tracing();
+
+        let issuer_keys = IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519, ISSMOCK_PRIV_EC_P384]);
+        let status_lists = StatusLists::new("http://localhost:40080/status-lists");
+
+        let mut req: Value = serde_json::from_str(README_ALUMNI)?;
+        req["credential"]["issuer"] = json!(ISSMOCK_PRIV_OKP_ED25519_DIDKEY);
+        let req: IssueRequest = serde_json::from_value(req)?;
+        issue_credential(issuer_keys.clone(), &with_status_lists(&status_lists), req).await?;
+
+        let id = "http://university.example/credentials/1872";
+        let revoke = json!({
+            "credentialId": id,
+            "credentialStatus": [
+                { "type": "BitstringStatusListEntry", "statusPurpose": "revocation" }
+            ],
+        });
+        update_status_(&status_lists, revoke).await?;
+        let suspension_list = status_lists
+            .sign(&issuer_keys, None, StatusPurpose::Suspension)
+            .await?;
+
+        // The valid suspension is not applied either.
+        let vc_api_error = update_status_(
+            &status_lists,
+            json!({
+                "credentialId": id,
+                "credentialStatus": [
+                    { "type": "BitstringStatusListEntry", "statusPurpose": "suspension" },
+                    { "type": "BitstringStatusListEntry", "statusPurpose": "revocation", "status": false }
+                ],
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(vc_api_error.status, http::StatusCode::BAD_REQUEST);
+        assert_eq!(
+            vc_api_error.problem_details.code().unwrap(),
+            PredefinedProblemType::MalformedValueError.code()
+        );
+        // Any change of the bitstring drops the signed status list credential.
+        let signed = status_lists
+            .signed_credential(StatusPurpose::Suspension)
+            .expect("suspension status list is unchanged");
+        assert_eq!(
+            serde_json::to_value(signed)?,
+            serde_json::to_value(suspension_list)?
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_status_error_unknown_credential() -> anyhow::Result<()> {
+        init_tracing();
+
+        let status_lists = StatusLists::new("http://localhost:40080/status-lists");
+        let vc_api_error = update_status_(
+            &status_lists,
+            json!({
+                "credentialId": "http://university.example/credentials/1872",
+                "credentialStatus": [
+                    { "type": "BitstringStatusListEntry", "statusPurpose": "suspension" }
+                ],
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(vc_api_error.status, http::StatusCode::NOT_FOUND);
+        assert_eq!(
+            vc_api_error.problem_details.code().unwrap(),
+            CustomProblemType::CredentialNotFoundError.code()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_and_delete_credential_success() -> anyhow::Result<()> {
+        init_tracing();
+
+        let issuer_keys = IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519, ISSMOCK_PRIV_EC_P384]);
+        let store: DynCredentialStore = Arc::new(InMemoryCredentialStore::default());
+        let services = IssueServices {
+            credential_store: Some(store.clone()),
+            ..Default::default()
+        };
+
+        let mut req: Value = serde_json::from_str(README_ALUMNI)?;
+        req["credential"]["issuer"] = json!(ISSMOCK_PRIV_OKP_ED25519_DIDKEY);
+        let req: IssueRequest = serde_json::from_value(req)?;
+        let res = issue_credential(issuer_keys, &services, req).await?;
+        let issued = serde_json::to_value(&res.body)?;
+
+        let id = "http://university.example/credentials/1872".to_string();
+        let res = get_credential(Extension(store.clone()), Path(id.clone())).await?;
+        assert_eq!(res.status, http::StatusCode::OK);
+        assert_eq!(res.body.verifiable_credential, issued);
+
+        let status = delete_credential(Extension(store.clone()), Path(id.clone())).await?;
+        assert_eq!(status, http::StatusCode::NO_CONTENT);
+        assert_eq!(store.get(&id)?, None);
+
+        let vc_api_error = get_credential(Extension(store.clone()), Path(id.clone()))
+            .await
+            .unwrap_err();
+        assert_eq!(vc_api_error.status, http::StatusCode::NOT_FOUND);
+        assert_eq!(
+            vc_api_error.problem_details.code().unwrap(),
+            CustomProblemType::CredentialNotFoundError.code()
+        );
+        let vc_api_error = delete_credential(Extension(store), Path(id))
+            .await
+            .unwrap_err();
+        assert_eq!(vc_api_error.status, http::StatusCode::NOT_FOUND);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_did_web_success() -> anyhow::Result<()> {
+        init_tracing();
+
+        let issuer_keys = IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519, ISSMOCK_PRIV_EC_P384]);
+        let did_web = DidWeb::from_base_url("http://localhost:40080")?;
+        let services = IssueServices {
+            did_web: Some(did_web.clone()),
+            ..Default::default()
+        };
+
+        let mut req: Value = serde_json::from_str(ISSUER_DIDKEY_OKP_ED25519)?;
+        req["credential"]["issuer"] = json!(did_web.path_based_did("alice"));
+        let req: IssueRequest = serde_json::from_value(req)?;
+
+        let res = issue_credential(issuer_keys.clone(), &services, req).await?;
+        assert_eq!(res.status, 201);
+
+        let vc = serde_json::to_value(&res.body)?;
+        let vm = vc["proof"]["verificationMethod"].as_str().unwrap();
+        assert_eq!(
+            vm,
+            format!(
+                "did:web:localhost%3A40080:dids:alice#{}",
+                issuer_keys.key_pairs()[0].1.thumbprint()
+            )
+        );
+
+        let req: VerifyRequest = serde_json::from_value(json!({ "verifiableCredential": vc }))?;
+        let verified = verify(
+            Extension(issuer_keys),
+            Some(Extension(did_web)),
+            JsonReq(req),
+        )
+        .await?;
+        assert!(verified.is_verified());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_verification_method_success() -> anyhow::Result<()> {
+        init_tracing();
+
+        let issuer_keys = IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519, ISSMOCK_PRIV_EC_P384]);
+        let did_web = DidWeb::from_base_url("http://localhost:40080")?;
+        let services = IssueServices {
+            did_web: Some(did_web.clone()),
+            ..Default::default()
+        };
+        // not the first verification method, which is picked from the issuer
+        let method = format!(
+            "{}#{}",
+            did_web.did(),
+            issuer_keys.key_pairs()[1].1.thumbprint()
+        );
+
+        let mut req: Value = serde_json::from_str(ISSUER_DIDKEY_OKP_ED25519)?;
+        req["credential"]["issuer"] = json!(did_web.did());
+        req["options"] = json!({ "verificationMethod": method });
+        let req: IssueRequest = serde_json::from_value(req)?;
+
+        let res = issue_credential(issuer_keys.clone(), &services, req).await?;
+        assert_eq!(res.status, 201);
+
+        let vc = serde_json::to_value(&res.body)?;
+        assert_eq!(vc["proof"]["verificationMethod"], method);
+
+        let req: VerifyRequest = serde_json::from_value(json!({ "verifiableCredential": vc }))?;
+        let verified = verify(
+            Extension(issuer_keys),
+            Some(Extension(did_web)),
+            JsonReq(req),
+        )
+        .await?;
+        assert!(verified.is_verified());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_verification_method_error_no_private_key() -> anyhow::Result<()> {
+        let did_key = IssuerKeys::new(vec![JWK_EC_P384_PRIV]).key_pairs()[0]
+            .1
+            .to_did_key();
+        let method = format!("{}#{}", did_key, did_key.strip_prefix("did:key:").unwrap());
+
+        let mut req: Value = serde_json::from_str(ISSUER_DIDKEY_OKP_ED25519)?;
+        req["credential"]["issuer"] = json!(did_key);
+        req["options"] = json!({ "verificationMethod": method });
+        let req: IssueRequest = serde_json::from_value(req)?;
+
+        let vc_api_error = issue_(req).await.unwrap_err();
+        assert_eq!(vc_api_error.status, http::StatusCode::BAD_REQUEST);
+        assert_eq!(
+            vc_api_error.problem_details.code().unwrap(),
+            CustomProblemType::VerificationMethodResolutionError.code()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_verification_method_error_other_did() -> anyhow::Result<()> {
+        init_tracing();
+
+        // The mock holds both keys, but the method is not of the issuer DID.
+        let issuer_keys = IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519, ISSMOCK_PRIV_EC_P384]);
+        let did_key = issuer_keys.key_pairs()[1].1.to_did_key();
+        let method = format!("{}#{}", did_key, did_key.strip_prefix("did:key:").unwrap());
+
+        let mut req: Value = serde_json::from_str(ISSUER_DIDKEY_OKP_ED25519)?;
+        req["options"] = json!({ "verificationMethod": method });
+        let req: IssueRequest = serde_json::from_value(req)?;
+
+        let vc_api_error = issue_credential(issuer_keys.clone(), &IssueServices::default(), req)
+            .await
+            .unwrap_err();
+        assert_eq!(vc_api_error.status, http::StatusCode::BAD_REQUEST);
+        assert_eq!(
+            vc_api_error.problem_details.code().unwrap(),
+            CustomProblemType::VerificationMethodResolutionError.code()
+        );
+
+        // URL issuers may sign with any method of the mock.
+        let mut req: Value = serde_json::from_str(CREDENTIAL_OK)?;
+        req["options"] = json!({ "verificationMethod": method });
+        let req: IssueRequest = serde_json::from_value(req)?;
+
+        let res = issue_credential(issuer_keys, &IssueServices::default(), req).await?;
+        assert_eq!(res.status, 201);
+        let vc = serde_json::to_value(&res.body)?;
+        assert_eq!(vc["proof"]["verificationMethod"], method);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_did_jwk_success() -> anyhow::Result<()> {
+        init_tracing();
+
+        let issuer_keys = IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519, ISSMOCK_PRIV_EC_P384]);
+        let did_jwk = issuer_keys.key_pairs()[1].1.to_did_jwk();
+
+        for issuer in [did_jwk.clone(), format!("{}#0", did_jwk)] {
+            let mut req: Value = serde_json::from_str(ISSUER_DIDKEY_OKP_ED25519)?;
+            req["credential"]["issuer"] = json!(issuer);
+            let req: IssueRequest = serde_json::from_value(req)?;
+
+            let res = issue_credential(issuer_keys.clone(), &IssueServices::default(), req).await?;
+            assert_eq!(res.status, 201);
+
+            let vc = serde_json::to_value(&res.body)?;
+            assert_eq!(vc["proof"]["verificationMethod"], format!("{}#0", did_jwk));
+
+            let req: VerifyRequest = serde_json::from_value(json!({ "verifiableCredential": vc }))?;
+            let verified = verify(Extension(issuer_keys.clone()), None, JsonReq(req)).await?;
+            assert!(verified.is_verified());
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_issue_with_credential_store_success_no_identifier() -> anyhow::Result<()> {
+        init_tracing();
+
+        let issuer_keys = IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519, ISSMOCK_PRIV_EC_P384]);
+        let store: DynCredentialStore = Arc::new(InMemoryCredentialStore::default());
+        let services = IssueServices {
+            credential_store: Some(store.clone()),
+            ..Default::default()
+        };
+
+        let req: IssueRequest = serde_json::from_str(ISSUER_DIDKEY_OKP_ED25519)?;
+        let res = issue_credential(issuer_keys, &services, req).await?;
+        assert_eq!(res.status, 201);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_success_issuer_didkey_okp_ed25519() -> anyhow::Result<()> {
+        let vc = issued_vc_json(ISSUER_DIDKEY_OKP_ED25519).await?;
+
+        let res = verify_(vc).await?;
+        assert!(res.is_verified());
+        assert_eq!(res.checks, vec!["proof"]);
+        assert!(res.warnings.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_success_issuer_didkey_ec_secp256k1() -> anyhow::Result<()> {
+        let vc = issued_vc_json(ISSUER_DIDKEY_EC_SECP256K1).await?;
+        let res = verify_(vc).await?;
+        assert!(res.is_verified());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_success_issuer_didkey_ec_p384() -> anyhow::Result<()> {
+        let vc = issued_vc_json(ISSUER_DIDKEY_EC_P384).await?;
+
+        let res = verify_(vc).await?;
+        assert!(res.is_verified());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_success_vcdm_v1() -> anyhow::Result<()> {
+        let vc = issued_vc_json(vc_data_model_1_1::CREDENTIAL_OK).await?;
+
+        let res = verify_(vc).await?;
+        assert!(res.is_verified());
+        assert_eq!(res.checks, vec!["proof"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_success_warning_unsupported_check() -> anyhow::Result<()> {
+        let vc = issued_vc_json(ISSUER_DIDKEY_OKP_ED25519).await?;
+
+        let res = verify_with_checks_(vc, &["proof", "credentialStatus"]).await?;
+        assert!(res.is_verified());
+        assert_eq!(res.checks, vec!["proof"]);
+        assert_eq!(res.warnings.len(), 1);
+        assert_eq!(
+            res.warnings[0].code().unwrap(),
+            PredefinedProblemType::MalformedValueError.code()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_error_tampered_credential_subject() -> anyhow::Result<()> {
+        let mut vc = issued_vc_json(ISSUER_DIDKEY_OKP_ED25519).await?;
+        vc["credentialSubject"]["id"] = json!("did:example:tampered");
+
+        let res = verify_(vc).await?;
+        assert!(!res.is_verified());
+        assert!(res.checks.is_empty());
+        assert_eq!(
+            res.errors[0].code().unwrap(),
+            PredefinedProblemType::CryptographicSecurityError.code()
+        );
+
+        Ok(())
+    }
 }
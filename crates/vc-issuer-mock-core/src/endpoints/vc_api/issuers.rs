@@ -0,0 +1,469 @@
+//! Implements VC-API endpoints of each [`IssuerProfile`](crate::issuer_profile::IssuerProfile):
+//!
+//! - `POST /issuers/{name}/credentials/issue`
+//! - `POST /issuers/{name}/credentials/verify`
+//! - `POST /issuers/{name}/credentials/status`
+//! - `GET /issuers/{name}/credentials/{id}`
+//! - `DELETE /issuers/{name}/credentials/{id}`
+//! - `GET /issuers/{name}{status list base path}/{statusPurpose}`
+//!
+//! They work as the endpoints without `/issuers/{name}`, but only with the keys, the status lists and the issued
+//! credentials of the profile.
+//! The defaults of the profile, then the global [`DefaultVcProperties`], are filled into issue requests.
+
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use axum::{extract::Path, response::Response, Extension};
+use http::HeaderMap;
+use serde_json::Value;
+
+use crate::{
+    credential_id::CredentialIdMinter,
+    credential_store::{DynCredentialStore, ScopedCredentialStore},
+    did_web::DidWeb,
+    endpoints::{
+        vc_api::{
+            credentials::{
+                find_credential, issue_credential, issue_response, parse_issue_request,
+                remove_credential, update_credential_status, verify_credential, IssueServices,
+            },
+            req::{json_req::JsonReq, UpdateCredentialStatusRequest, VerifyRequest},
+            res::{
+                vc_api_error::{custom_problem_types::CustomProblemType, VcApiError},
+                GetCredentialResponse, VerifiableCredentialV2DataIntegrity, VerifyResponse,
+            },
+        },
+        SuccessRes,
+    },
+    issuer_profile::{DefaultVcProperties, IssuerProfile, IssuerProfiles},
+    status_list::{StatusLists, StatusPurpose},
+    vcdm_v2::problem_details::ProblemDetails,
+};
+
+/// `POST /issuers/{name}/credentials/issue`
+///
+/// Credentials refer to the status lists of the profile, if any, and are recorded apart from those of other profiles.
+#[axum::debug_handler]
+#[allow(clippy::too_many_arguments)]
+pub async fn issue(
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    Extension(issuer_profiles): Extension<IssuerProfiles>,
+    credential_id_minter: Option<Extension<CredentialIdMinter>>,
+    credential_store: Option<Extension<DynCredentialStore>>,
+    did_web: Option<Extension<DidWeb>>,
+    default_properties: Option<Extension<DefaultVcProperties>>,
+    JsonReq(mut req): JsonReq<Value>,
+) -> Result<Response, VcApiError> {
+    let profile = find_profile(&issuer_profiles, &name)?;
+
+    profile.apply_defaults(&mut req);
+    if let Some(Extension(default_properties)) = default_properties {
+        default_properties.apply(&mut req);
+    }
+    let req = parse_issue_request(req)?;
+
+    let services = IssueServices {
+        status_lists: profile.status_lists().cloned(),
+        credential_id_minter: credential_id_minter.map(|Extension(minter)| minter),
+        credential_store: credential_store.map(|Extension(store)| {
+            Arc::new(ScopedCredentialStore::for_profile(store, profile.name()))
+                as DynCredentialStore
+        }),
+        did_web: did_web.map(|Extension(did_web)| did_web),
+    };
+    let res = issue_credential(profile.issuer_keys().clone(), &services, req).await?;
+    Ok(issue_response(&headers, res))
+}
+
+/// `POST /issuers/{name}/credentials/verify`
+#[axum::debug_handler]
+pub async fn verify(
+    Path(name): Path<String>,
+    Extension(issuer_profiles): Extension<IssuerProfiles>,
+    did_web: Option<Extension<DidWeb>>,
+    JsonReq(req): JsonReq<VerifyRequest>,
+) -> Result<VerifyResponse, VcApiError> {
+    let profile = find_profile(&issuer_profiles, &name)?;
+    verify_credential(
+        profile.issuer_keys().clone(),
+        did_web.map(|Extension(did_web)| did_web),
+        req,
+    )
+    .await
+}
+
+/// `POST /issuers/{name}/credentials/status`
+#[axum::debug_handler]
+pub async fn update_status(
+    Path(name): Path<String>,
+    Extension(issuer_profiles): Extension<IssuerProfiles>,
+    did_web: Option<Extension<DidWeb>>,
+    JsonReq(req): JsonReq<UpdateCredentialStatusRequest>,
+) -> Result<http::StatusCode, VcApiError> {
+    let profile = find_profile(&issuer_profiles, &name)?;
+    update_credential_status(
+        profile.issuer_keys(),
+        find_status_lists(profile)?,
+        did_web.as_ref().map(|Extension(did_web)| did_web),
+        req,
+    )
+    .await
+}
+
+/// `GET /issuers/{name}/credentials/{id}`
+///
+/// Only credentials issued by the profile are found.
+#[axum::debug_handler]
+pub async fn get_credential(
+    Path((name, id)): Path<(String, String)>,
+    Extension(issuer_profiles): Extension<IssuerProfiles>,
+    Extension(credential_store): Extension<DynCredentialStore>,
+) -> Result<SuccessRes<GetCredentialResponse>, VcApiError> {
+    let profile = find_profile(&issuer_profiles, &name)?;
+    find_credential(
+        &ScopedCredentialStore::for_profile(credential_store, profile.name()),
+        &id,
+    )
+}
+
+/// `DELETE /issuers/{name}/credentials/{id}`
+///
+/// Only credentials issued by the profile are found.
+#[axum::debug_handler]
+pub async fn delete_credential(
+    Path((name, id)): Path<(String, String)>,
+    Extension(issuer_profiles): Extension<IssuerProfiles>,
+    Extension(credential_store): Extension<DynCredentialStore>,
+) -> Result<http::StatusCode, VcApiError> {
+    let profile = find_profile(&issuer_profiles, &name)?;
+    remove_credential(
+        &ScopedCredentialStore::for_profile(credential_store, profile.name()),
+        &id,
+    )
+}
+
+/// `GET /issuers/{name}{status list base path}/:purpose`
+///
+/// Returns the status list credential of the profile, signed with its keys. It is signed on the first request.
+#[axum::debug_handler]
+pub async fn status_list_credential(
+    Path((name, purpose)): Path<(String, StatusPurpose)>,
+    Extension(issuer_profiles): Extension<IssuerProfiles>,
+    did_web: Option<Extension<DidWeb>>,
+) -> Result<SuccessRes<VerifiableCredentialV2DataIntegrity>, VcApiError> {
+    let profile = find_profile(&issuer_profiles, &name)?;
+    let status_lists = find_status_lists(profile)?;
+    let vc = match status_lists.signed_credential(purpose) {
+        Some(vc) => vc,
+        None => {
+            let did_web = did_web.as_ref().map(|Extension(did_web)| did_web);
+            status_lists
+                .sign(profile.issuer_keys(), did_web, purpose)
+                .await?
+        }
+    };
+    Ok(SuccessRes {
+        status: http::StatusCode::OK,
+        body: vc,
+    })
+}
+
+fn find_status_lists(profile: &IssuerProfile) -> Result<&StatusLists, ProblemDetails> {
+    profile.status_lists().ok_or_else(|| {
+        ProblemDetails::new(
+            CustomProblemType::IssuerNotFoundError,
+            "status lists not found".to_string(),
+            format!("Issuer profile `{}` has no status lists.", profile.name()),
+            anyhow!("Issuer profile `{}` has no status lists", profile.name()),
+        )
+    })
+}
+
+fn find_profile<'a>(
+    issuer_profiles: &'a IssuerProfiles,
+    name: &str,
+) -> Result<&'a IssuerProfile, ProblemDetails> {
+    issuer_profiles.get(name).ok_or_else(|| {
+        ProblemDetails::new(
+            CustomProblemType::IssuerNotFoundError,
+            "issuer not found".to_string(),
+            format!("No issuer profile found for `{}`.", name),
+            anyhow!("No issuer profile found for `{}`", name),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        extract::Request,
+        routing::{get, post},
+        Router,
+    };
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    use crate::{
+        credential_store::InMemoryCredentialStore,
+        endpoints::vc_api::credentials,
+        test_jwks::{ISSMOCK_PRIV_EC_P384, ISSMOCK_PRIV_OKP_ED25519},
+        test_tracing::init_tracing,
+        IssuerKeys,
+    };
+
+    use super::*;
+
+    fn setup() -> (IssuerProfiles, Router) {
+        init_tracing();
+
+        let did_web = DidWeb::from_base_url("http://localhost:40080").unwrap();
+        let university = IssuerProfile::new(
+            "university",
+            IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519]),
+        )
+        .unwrap()
+        .with_issuer(Some(did_web.path_based_did("university")))
+        .with_cryptosuite(Some("eddsa-jcs-2022".to_string()))
+        .unwrap()
+        .with_default_properties(
+            json!({ "description": "A university credential" })
+                .as_object()
+                .unwrap()
+                .clone(),
+        );
+        let bank_status_lists =
+            StatusLists::new("http://localhost:40080/issuers/bank/status-lists")
+                .with_issuer(Some(did_web.path_based_did("bank")));
+        let bank = IssuerProfile::new("bank", IssuerKeys::new(vec![ISSMOCK_PRIV_EC_P384]))
+            .unwrap()
+            .with_issuer(Some(did_web.path_based_did("bank")))
+            .with_status_lists(Some(bank_status_lists));
+        let issuer_profiles = IssuerProfiles::new([university, bank]).unwrap();
+        let did_web = did_web.with_issuer_profiles(issuer_profiles.clone());
+
+        let app = Router::new()
+            .route("/issuers/:name/credentials/issue", post(issue))
+            .route("/issuers/:name/credentials/verify", post(verify))
+            .route("/issuers/:name/credentials/status", post(update_status))
+            .route(
+                "/issuers/:name/credentials/:id",
+                get(get_credential).delete(delete_credential),
+            )
+            .route(
+                "/issuers/:name/status-lists/:purpose",
+                get(status_list_credential),
+            )
+            .route(
+                "/credentials/:id",
+                get(credentials::get_credential).delete(credentials::delete_credential),
+            )
+            .layer(Extension(issuer_profiles.clone()))
+            .layer(Extension(did_web))
+            .layer(Extension(
+                Arc::new(InMemoryCredentialStore::default()) as DynCredentialStore
+            ))
+            .layer(Extension(CredentialIdMinter::new("urn:uuid:")))
+            .layer(Extension(DefaultVcProperties::new(
+                json!({ "name": "Mock credential", "description": "A mock credential" })
+                    .as_object()
+                    .unwrap()
+                    .clone(),
+            )));
+        (issuer_profiles, app)
+    }
+
+    async fn post_json(app: &Router, uri: &str, body: Value) -> (http::StatusCode, Value) {
+        let req = Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        send(app, req).await
+    }
+
+    async fn get_json(app: &Router, uri: &str) -> (http::StatusCode, Value) {
+        let req = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        send(app, req).await
+    }
+
+    async fn delete(app: &Router, uri: &str) -> http::StatusCode {
+        let req = Request::builder()
+            .method("DELETE")
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap();
+        send(app, req).await.0
+    }
+
+    async fn send(app: &Router, req: Request) -> (http::StatusCode, Value) {
+        let res = app.clone().oneshot(req).await.unwrap();
+        let status = res.status();
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    fn credential() -> Value {
+        json!({
+            "credential": {
+                "@context": ["https://www.w3.org/ns/credentials/v2"],
+                "type": ["VerifiableCredential"],
+                "credentialSubject": { "id": "did:example:subject" },
+            },
+        })
+    }
+
+    #[tokio::test]
+    async fn test_issue_success() {
+        let (issuer_profiles, app) = setup();
+
+        let (status, vc) =
+            post_json(&app, "/issuers/university/credentials/issue", credential()).await;
+        assert_eq!(status, 201);
+        assert_eq!(vc["issuer"], "did:web:localhost%3A40080:dids:university");
+        assert_eq!(vc["proof"]["cryptosuite"], "eddsa-jcs-2022");
+        let university_key = &issuer_profiles
+            .get("university")
+            .unwrap()
+            .issuer_keys()
+            .key_pairs()[0]
+            .1;
+        assert_eq!(
+            vc["proof"]["verificationMethod"],
+            format!(
+                "did:web:localhost%3A40080:dids:university#{}",
+                university_key.thumbprint()
+            )
+        );
+
+        let body = json!({ "verifiableCredential": vc });
+        let (status, json) = post_json(&app, "/issuers/university/credentials/verify", body).await;
+        assert_eq!(status, 200);
+        assert!(json["errors"].as_array().map_or(true, Vec::is_empty));
+    }
+
+    #[tokio::test]
+    async fn test_issue_success_own_status_lists() {
+        let (_, app) = setup();
+
+        let (status, vc) = post_json(&app, "/issuers/bank/credentials/issue", credential()).await;
+        assert_eq!(status, 201);
+        assert_eq!(
+            vc["credentialStatus"][0]["statusListCredential"],
+            "http://localhost:40080/issuers/bank/status-lists/revocation"
+        );
+
+        let body = json!({
+            "credentialId": vc["id"],
+            "credentialStatus": [
+                { "type": "BitstringStatusListEntry", "statusPurpose": "revocation" }
+            ],
+        });
+        let (status, _) = post_json(&app, "/issuers/bank/credentials/status", body).await;
+        assert_eq!(status, 200);
+
+        // The status list credential is issued by the bank, with its key.
+        let (status, status_list) = get_json(&app, "/issuers/bank/status-lists/revocation").await;
+        assert_eq!(status, 200);
+        assert_eq!(status_list["issuer"], vc["issuer"]);
+        let body = json!({ "verifiableCredential": status_list });
+        let (status, json) = post_json(&app, "/issuers/bank/credentials/verify", body).await;
+        assert_eq!(status, 200);
+        assert!(json["errors"].as_array().map_or(true, Vec::is_empty));
+
+        // The university has no status lists.
+        let (status, vc) =
+            post_json(&app, "/issuers/university/credentials/issue", credential()).await;
+        assert_eq!(status, 201);
+        assert!(vc.get("credentialStatus").is_none());
+        let (status, _) = get_json(&app, "/issuers/university/status-lists/revocation").await;
+        assert_eq!(status, 404);
+    }
+
+    #[tokio::test]
+    async fn test_issue_success_default_properties() {
+        let (_, app) = setup();
+
+        // the defaults of the profile take precedence over the global ones
+        let (status, vc) =
+            post_json(&app, "/issuers/university/credentials/issue", credential()).await;
+        assert_eq!(status, 201);
+        assert_eq!(vc["description"], "A university credential");
+        assert_eq!(vc["name"], "Mock credential");
+
+        let (status, vc) = post_json(&app, "/issuers/bank/credentials/issue", credential()).await;
+        assert_eq!(status, 201);
+        assert_eq!(vc["description"], "A mock credential");
+        assert_eq!(vc["name"], "Mock credential");
+    }
+
+    #[tokio::test]
+    async fn test_get_credential_success_own_credentials_only() {
+        let (_, app) = setup();
+
+        let (status, vc) = post_json(&app, "/issuers/bank/credentials/issue", credential()).await;
+        assert_eq!(status, 201);
+        let id = vc["id"].as_str().unwrap().to_string();
+
+        let (status, json) = get_json(&app, &format!("/issuers/bank/credentials/{}", id)).await;
+        assert_eq!(status, 200);
+        assert_eq!(json["verifiableCredential"], vc);
+
+        // other tenants and the global endpoints cannot see it
+        let (status, json) =
+            get_json(&app, &format!("/issuers/university/credentials/{}", id)).await;
+        assert_eq!(status, 404);
+        assert_eq!(
+            json["problemDetails"]["type"],
+            CustomProblemType::CredentialNotFoundError.to_string()
+        );
+        let (status, _) = get_json(&app, &format!("/credentials/{}", id)).await;
+        assert_eq!(status, 404);
+        let (status, _) = get_json(
+            &app,
+            &format!("/credentials/issuers%2Fbank%2F{}", id.replace(':', "%3A")),
+        )
+        .await;
+        assert_eq!(status, 404);
+
+        // nor delete it
+        let status = delete(&app, &format!("/issuers/university/credentials/{}", id)).await;
+        assert_eq!(status, 404);
+        let status = delete(&app, &format!("/credentials/{}", id)).await;
+        assert_eq!(status, 404);
+        let (status, _) = get_json(&app, &format!("/issuers/bank/credentials/{}", id)).await;
+        assert_eq!(status, 200);
+
+        let status = delete(&app, &format!("/issuers/bank/credentials/{}", id)).await;
+        assert_eq!(status, 204);
+        let (status, _) = get_json(&app, &format!("/issuers/bank/credentials/{}", id)).await;
+        assert_eq!(status, 404);
+    }
+
+    #[tokio::test]
+    async fn test_issue_error_other_tenant_issuer() {
+        let (_, app) = setup();
+
+        // the bank cannot sign as the university
+        let mut req = credential();
+        req["credential"]["issuer"] = json!("did:web:localhost%3A40080:dids:university");
+        let (status, _) = post_json(&app, "/issuers/bank/credentials/issue", req).await;
+        assert_eq!(status, 400);
+    }
+
+    #[tokio::test]
+    async fn test_issue_error_unknown_issuer() {
+        let (_, app) = setup();
+
+        let (status, json) =
+            post_json(&app, "/issuers/government/credentials/issue", credential()).await;
+        assert_eq!(status, 404);
+        assert_eq!(
+            json["problemDetails"]["type"],
+            CustomProblemType::IssuerNotFoundError.to_string()
+        );
+    }
+}
@@ -4,3 +4,4 @@ pub(crate) mod req;
 pub(crate) mod res;
 
 pub mod credentials;
+pub mod issuers;
@@ -7,13 +7,19 @@
 pub(crate) mod json_req;
 
 use serde::{Deserialize, Deserializer};
-use serde_json::Value;
+use serde_json::{Map, Value};
 use serde_with::{serde_as, DeserializeAs};
 use ssi::claims::data_integrity::JsonPointerBuf;
 
 use crate::{
-    endpoints::vc_api::res::VerifiableCredentialV2,
-    vcdm_v2::default_vc_properties::VC_DEFAULT_ISSUER,
+    cryptosuite::RequestedCryptosuite,
+    endpoints::vc_api::res::{
+        AnyVerifiableCredential, AnyVerifiableCredentialDataIntegrity, VerifiableCredentialV1,
+        VerifiableCredentialV1DataIntegrity, VerifiableCredentialV2,
+        VerifiableCredentialV2DataIntegrity,
+    },
+    status_list::StatusPurpose,
+    vcdm_v2::{default_vc_properties::VC_DEFAULT_ISSUER, problem_details::ProblemDetails},
 };
 
 /// Request body for the [`POST /credentials/issue` endpoint](https://w3c-ccg.github.io/vc-api/#issue-credential).
@@ -23,15 +29,15 @@ use crate::{
 pub struct IssueRequest {
     /// `credential` property.
     ///
-    /// Currently, we do not support VCDM v1.
+    /// Both VCDM v1.1 and v2.0 are supported. The data model is decided from the first `@context`.
     ///
-    /// A request parameter not always contains all necessary properties as VCDM v2.
+    /// A request parameter not always contains all necessary properties as VCDM.
     /// For example, "issuer" property is mandatory [in VCDM v2](https://www.w3.org/TR/vc-data-model-2.0/#issuer),
     /// but not in the [VC-API's request parameter](https://w3c-ccg.github.io/vc-api/#issue-credential).
     ///
-    /// [`self::VerifiableCredentialV2WithDefault`] is a wrapper struct to provide default values for missing properties.
-    #[serde_as(as = "VerifiableCredentialV2WithDefault")]
-    pub credential: VerifiableCredentialV2,
+    /// [`self::VerifiableCredentialWithDefault`] is a wrapper struct to provide default values for missing properties.
+    #[serde_as(as = "VerifiableCredentialWithDefault")]
+    pub credential: AnyVerifiableCredential,
 
     /// `options` property.
     #[serde(default)]
@@ -43,16 +49,187 @@ pub struct IssueRequest {
 #[serde_as]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct IssueRequestOptions {
+    /// JSON pointers to the claims always disclosed.
+    ///
+    /// With Data Integrity proofs, `ecdsa-sd-2023` (P-256 only, not P-384) or `bbs-2023` is used.
+    /// Issuer keys supporting neither are rejected. Verification methods other than Multikey (e.g. `JsonWebKey2020`
+    /// of URL issuers) are signed with their default suite, and the pointers have no effect.
+    /// With SD-JWT VCs, the pointers must be under `/credentialSubject/`.
     #[serde_as(as = "Option<Vec<DisplayFromStr>>")]
     pub mandatory_pointers: Option<Vec<JsonPointerBuf>>,
-    #[allow(dead_code)]
+    /// Identifier to refer to the issued credential later (e.g. in `POST /credentials/status`).
+    ///
+    /// Defaults to the `id` of the credential. It is not embedded in the credential.
     pub credential_id: Option<String>,
+
+    /// How the issued credential is secured. Defaults to an embedded Data Integrity proof.
+    #[serde(default)]
+    pub securing_mechanism: SecuringMechanism,
+
+    /// IRI of the verification method to sign with (e.g. `did:key:z6Mk...#z6Mk...`).
+    ///
+    /// The issuer keys must hold its private key. If omitted, a verification method is resolved from the `issuer`.
+    pub verification_method: Option<String>,
+
+    /// Holder's public key (JWK) bound to an SD-JWT VC as `cnf.jwk`.
+    ///
+    /// Only valid with the `sd-jwt` securing mechanism.
+    pub holder_jwk: Option<Map<String, Value>>,
+
+    /// Proof `type` (e.g. `DataIntegrityProof`). Can be omitted when `cryptosuite` is specified.
+    #[serde(rename = "type")]
+    pub r#type: Option<String>,
+    /// Data Integrity `cryptosuite` (e.g. `eddsa-rdfc-2022`).
+    ///
+    /// If neither `type` nor `cryptosuite` is specified, a suite is chosen from the issuer key.
+    pub cryptosuite: Option<String>,
+}
+
+/// `securingMechanism` option in [`self::IssueRequestOptions`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SecuringMechanism {
+    /// Embedded proof ([Verifiable Credential Data Integrity](https://www.w3.org/TR/vc-data-integrity/)).
+    #[default]
+    DataIntegrity,
+    /// Enveloping proof with JOSE ([VC-JOSE-COSE](https://www.w3.org/TR/vc-jose-cose/#securing-with-jose)).
+    Jose,
+    /// Enveloping proof with COSE ([VC-JOSE-COSE](https://www.w3.org/TR/vc-jose-cose/#securing-with-cose)).
+    Cose,
+    /// [SD-JWT VC](https://datatracker.ietf.org/doc/draft-ietf-oauth-sd-jwt-vc/).
+    ///
+    /// `mandatoryPointers` option is used as the always-disclosed `credentialSubject` claims.
+    SdJwt,
+}
+
+impl IssueRequest {
+    /// Identifier to refer to the issued credential later: `credentialId` option or the `id` of the credential.
+    pub(crate) fn credential_id(&self) -> Option<&str> {
+        self.options
+            .credential_id
+            .as_deref()
+            .or_else(|| self.credential.id())
+    }
+}
+
+impl IssueRequestOptions {
+    /// Cryptosuite requested by `type` and `cryptosuite` options.
+    pub(crate) fn requested_cryptosuite(
+        &self,
+    ) -> Result<Option<RequestedCryptosuite>, ProblemDetails> {
+        RequestedCryptosuite::from_options(self.r#type.as_deref(), self.cryptosuite.as_deref())
+    }
+}
+
+/// Request body for the [`POST /credentials/verify` endpoint](https://w3c-ccg.github.io/vc-api/#verify-credential).
+#[serde_as]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct VerifyRequest {
+    /// `verifiableCredential` property.
+    ///
+    /// Currently, only VCDM v1.1 and v2.0 credentials secured with an embedded Data Integrity proof are supported.
+    #[serde_as(as = "VerifiableCredentialDataIntegrityByVersion")]
+    pub verifiable_credential: AnyVerifiableCredentialDataIntegrity,
+
+    /// `options` property.
+    #[serde(default)]
+    pub options: VerifyRequestOptions,
+}
+
+/// `options` field in [`self::VerifyRequest`].
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct VerifyRequestOptions {
+    /// Checks requested by the client (e.g. `["proof"]`).
+    ///
+    /// The proof is always checked regardless of this option.
+    /// Other checks are not supported, and reported as warnings.
+    pub checks: Option<Vec<String>>,
+}
+
+impl VerifyRequestOptions {
+    /// Checks performed by `POST /credentials/verify`.
+    pub(crate) const SUPPORTED_CHECKS: [&'static str; 1] = ["proof"];
+
+    /// Requested checks that are not performed.
+    pub(crate) fn unsupported_checks(&self) -> impl Iterator<Item = &str> {
+        self.checks
+            .iter()
+            .flatten()
+            .map(String::as_str)
+            .filter(|check| !Self::SUPPORTED_CHECKS.contains(check))
+    }
+}
+
+/// Request body for the [`POST /credentials/derive` endpoint](https://w3c-ccg.github.io/vc-api/#derive-credential).
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct DeriveRequest {
+    /// `verifiableCredential` property, secured with an `ecdsa-sd-2023` or `bbs-2023` base proof.
+    pub verifiable_credential: VerifiableCredentialV2DataIntegrity,
+
+    /// `options` property.
+    #[serde(default)]
+    pub options: DeriveRequestOptions,
+}
+
+/// `options` field in [`self::DeriveRequest`].
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct DeriveRequestOptions {
+    /// Claims to disclose in addition to the mandatory ones.
+    #[serde(default)]
+    pub selective_pointers: Vec<JsonPointerBuf>,
+}
+
+/// Request body for the [`POST /credentials/status` endpoint](https://w3c-ccg.github.io/vc-api/#update-status).
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct UpdateCredentialStatusRequest {
+    /// `id` of the issued credential.
+    pub credential_id: String,
+
+    /// Status updates to apply.
+    pub credential_status: Vec<CredentialStatusUpdate>,
+}
+
+/// An element of `credentialStatus` field in [`self::UpdateCredentialStatusRequest`].
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CredentialStatusUpdate {
+    /// Only `BitstringStatusListEntry` is supported.
+    #[serde(rename = "type")]
+    #[allow(dead_code)]
+    pub r#type: CredentialStatusType,
+
+    /// Which status list to update.
+    pub status_purpose: StatusPurpose,
+
+    /// `true` to set the status (revoke or suspend), `false` to clear it. Defaults to `true`.
+    ///
+    /// Revocation cannot be cleared.
+    #[serde(default = "default_status")]
+    pub status: bool,
+}
+
+/// `type` of a credential status entry.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize)]
+pub enum CredentialStatusType {
+    BitstringStatusListEntry,
+}
+
+fn default_status() -> bool {
+    true
 }
 
-struct VerifiableCredentialV2WithDefault;
+/// The base context of [VCDM v1.1](https://www.w3.org/TR/vc-data-model-1.1/#base-context).
+const VCDM_V1_CONTEXT: &str = "https://www.w3.org/2018/credentials/v1";
 
-impl<'de> DeserializeAs<'de, VerifiableCredentialV2> for VerifiableCredentialV2WithDefault {
-    fn deserialize_as<D>(deserializer: D) -> Result<VerifiableCredentialV2, D::Error>
+struct VerifiableCredentialWithDefault;
+
+impl<'de> DeserializeAs<'de, AnyVerifiableCredential> for VerifiableCredentialWithDefault {
+    fn deserialize_as<D>(deserializer: D) -> Result<AnyVerifiableCredential, D::Error>
     where
         D: Deserializer<'de>,
     {
@@ -67,7 +244,48 @@ impl<'de> DeserializeAs<'de, VerifiableCredentialV2> for VerifiableCredentialV2W
             }
         }
 
-        VerifiableCredentialV2::deserialize(value).map_err(serde::de::Error::custom)
+        if is_vcdm_v1(&value) {
+            VerifiableCredentialV1::deserialize(value)
+                .map(AnyVerifiableCredential::V1)
+                .map_err(serde::de::Error::custom)
+        } else {
+            VerifiableCredentialV2::deserialize(value)
+                .map(AnyVerifiableCredential::V2)
+                .map_err(serde::de::Error::custom)
+        }
+    }
+}
+
+/// Deserializes a credential with a Data Integrity proof as VCDM v1.1 or v2.0, according to its `@context`.
+struct VerifiableCredentialDataIntegrityByVersion;
+
+impl<'de> DeserializeAs<'de, AnyVerifiableCredentialDataIntegrity>
+    for VerifiableCredentialDataIntegrityByVersion
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<AnyVerifiableCredentialDataIntegrity, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value: Value = Value::deserialize(deserializer)?;
+
+        if is_vcdm_v1(&value) {
+            VerifiableCredentialV1DataIntegrity::deserialize(value)
+                .map(AnyVerifiableCredentialDataIntegrity::V1)
+                .map_err(serde::de::Error::custom)
+        } else {
+            VerifiableCredentialV2DataIntegrity::deserialize(value)
+                .map(AnyVerifiableCredentialDataIntegrity::V2)
+                .map_err(serde::de::Error::custom)
+        }
+    }
+}
+
+/// Whether the first `@context` of a credential is the VCDM v1.1 one.
+fn is_vcdm_v1(value: &Value) -> bool {
+    match &value["@context"] {
+        Value::Array(contexts) => contexts.first().and_then(Value::as_str) == Some(VCDM_V1_CONTEXT),
+        Value::String(context) => context == VCDM_V1_CONTEXT,
+        _ => false,
     }
 }
 
@@ -76,11 +294,47 @@ mod tests {
 
     use crate::{
         test_tracing::init_tracing,
-        test_vc_json::{vc_data_model_2_0_test_suite, vc_issuer_api_openapi_spec},
+        test_vc_json::{
+            vc_data_model_1_1, vc_data_model_2_0_test_suite, vc_issuer_api_openapi_spec,
+        },
     };
 
     use super::*;
 
+    #[test]
+    fn test_deserialize_update_credential_status_request() {
+        init_tracing();
+
+        let req: UpdateCredentialStatusRequest = serde_json::from_str(
+            r#"{
+                "credentialId": "urn:uuid:b7a1e8e0-2b0a-4a8c-9b6c-2c6f3e3c5a10",
+                "credentialStatus": [
+                    { "type": "BitstringStatusListEntry", "statusPurpose": "suspension" },
+                    { "type": "BitstringStatusListEntry", "statusPurpose": "suspension", "status": false }
+                ]
+            }"#,
+        )
+        .expect("Failed to deserialize UpdateCredentialStatusRequest");
+        assert_eq!(
+            req.credential_id,
+            "urn:uuid:b7a1e8e0-2b0a-4a8c-9b6c-2c6f3e3c5a10"
+        );
+        assert_eq!(
+            req.credential_status[0].status_purpose,
+            StatusPurpose::Suspension
+        );
+        assert!(req.credential_status[0].status);
+        assert!(!req.credential_status[1].status);
+
+        let unknown_type = serde_json::from_str::<UpdateCredentialStatusRequest>(
+            r#"{
+                "credentialId": "urn:uuid:b7a1e8e0-2b0a-4a8c-9b6c-2c6f3e3c5a10",
+                "credentialStatus": [{ "type": "StatusList2021Entry", "statusPurpose": "revocation" }]
+            }"#,
+        );
+        assert!(unknown_type.is_err());
+    }
+
     #[test]
     fn test_deserialize_issue_request() {
         init_tracing();
@@ -88,8 +342,14 @@ mod tests {
         let readme_alumni: IssueRequest =
             serde_json::from_str(vc_data_model_2_0_test_suite::README_ALUMNI)
                 .expect("Failed to deserialize vc_data_model_2_0_test_suite::README_ALUMNI");
+        assert!(readme_alumni.credential.as_v2().is_some());
         assert!(readme_alumni.options.credential_id.is_none());
         assert!(readme_alumni.options.mandatory_pointers.is_none());
+        assert!(readme_alumni.options.cryptosuite.is_none());
+        assert_eq!(
+            readme_alumni.options.securing_mechanism,
+            SecuringMechanism::DataIntegrity
+        );
 
         let request_sample: IssueRequest =
             serde_json::from_str(vc_issuer_api_openapi_spec::REQUEST_SAMPLE)
@@ -104,4 +364,35 @@ mod tests {
         assert_eq!(ptr[1].as_str(), "/validFrom");
         assert_eq!(ptr[2].as_str(), "/validUntil");
     }
+
+    #[test]
+    fn test_deserialize_issue_request_vcdm_v1() {
+        init_tracing();
+
+        let req: IssueRequest = serde_json::from_str(vc_data_model_1_1::CREDENTIAL_OK)
+            .expect("Failed to deserialize vc_data_model_1_1::CREDENTIAL_OK");
+        assert!(matches!(req.credential, AnyVerifiableCredential::V1(_)));
+    }
+
+    #[test]
+    fn test_deserialize_issue_request_cryptosuite() {
+        init_tracing();
+
+        let req: IssueRequest = serde_json::from_str(
+            r#"
+{
+  "credential": {
+    "@context": ["https://www.w3.org/ns/credentials/v2"],
+    "type": ["VerifiableCredential"],
+    "credentialSubject": { "id": "did:example:subject" }
+  },
+  "options": { "type": "DataIntegrityProof", "cryptosuite": "ecdsa-rdfc-2019" }
+}"#,
+        )
+        .expect("Failed to deserialize IssueRequest with cryptosuite");
+        assert_eq!(
+            req.options.requested_cryptosuite().unwrap(),
+            Some(RequestedCryptosuite::EcdsaRdfc2019)
+        );
+    }
 }
@@ -2,31 +2,274 @@
 
 pub mod vc_api_error;
 
+use axum::{
+    response::{IntoResponse, Response},
+    Json,
+};
+use http::StatusCode;
 use serde::Serialize;
 use ssi::{
-    claims::{data_integrity, vc::v2},
+    claims::{
+        data_integrity,
+        vc::{
+            syntax::{IdOr, IdentifiedObject},
+            v1, v2,
+        },
+    },
+    json_ld::iref::UriBuf,
     prelude::DataIntegrity,
 };
 
+use crate::vcdm_v2::problem_details::ProblemDetails;
+
+pub(crate) type VerifiableCredentialV1 =
+    v1::syntax::SpecializedJsonCredential<json_syntax::Object, (), ()>;
+pub(crate) type VerifiableCredentialV1DataIntegrity =
+    DataIntegrity<VerifiableCredentialV1, data_integrity::AnySuite>;
 pub(crate) type VerifiableCredentialV2 =
     v2::syntax::SpecializedJsonCredential<json_syntax::Object, (), ()>;
 pub(crate) type VerifiableCredentialV2DataIntegrity =
     DataIntegrity<VerifiableCredentialV2, data_integrity::AnySuite>;
+pub(crate) type DerivedCredential = DataIntegrity<json_syntax::Object, data_integrity::AnySuite>;
+
+/// A credential either in [VCDM v1.1](https://www.w3.org/TR/vc-data-model-1.1/) or
+/// [VCDM v2.0](https://www.w3.org/TR/vc-data-model-2.0/).
+#[derive(Clone, Debug, Serialize)]
+#[serde(untagged)]
+pub enum AnyVerifiableCredential {
+    V1(VerifiableCredentialV1),
+    V2(VerifiableCredentialV2),
+}
+
+/// A credential either in VCDM v1.1 or VCDM v2.0, secured with an embedded Data Integrity proof.
+#[derive(Clone, Debug, Serialize)]
+#[serde(untagged)]
+pub enum AnyVerifiableCredentialDataIntegrity {
+    V1(VerifiableCredentialV1DataIntegrity),
+    V2(VerifiableCredentialV2DataIntegrity),
+}
+
+impl AnyVerifiableCredential {
+    /// `issuer` property.
+    pub fn issuer(&self) -> &IdOr<IdentifiedObject> {
+        match self {
+            Self::V1(vc) => &vc.issuer,
+            Self::V2(vc) => &vc.issuer,
+        }
+    }
+
+    /// `id` property.
+    pub fn id(&self) -> Option<&str> {
+        match self {
+            Self::V1(vc) => vc.id.as_ref().map(|id| id.as_str()),
+            Self::V2(vc) => vc.id.as_ref().map(|id| id.as_str()),
+        }
+    }
+
+    /// Set `id` property.
+    pub(crate) fn set_id(&mut self, id: UriBuf) {
+        match self {
+            Self::V1(vc) => vc.id = Some(id),
+            Self::V2(vc) => vc.id = Some(id),
+        }
+    }
+
+    /// Get the VCDM v2.0 credential, if any.
+    pub fn as_v2(&self) -> Option<&VerifiableCredentialV2> {
+        match self {
+            Self::V2(vc) => Some(vc),
+            _ => None,
+        }
+    }
+}
 
 /// Response body of `POST /credentials/issue`.
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct IssueResponse {
-    /// A JSON-LD Verifiable Credential with a proof.
+    /// The issued credential.
     #[serde(flatten)]
-    pub verifiable_credential: VerifiableCredentialV2DataIntegrity,
-    // TODO EnvelopedVerifiableCredential
+    pub verifiable_credential: IssuedCredential,
 }
 
 impl IssueResponse {
-    pub(crate) fn new(verifiable_credential: VerifiableCredentialV2DataIntegrity) -> Self {
+    pub(crate) fn new(verifiable_credential: IssuedCredential) -> Self {
         Self {
             verifiable_credential,
         }
     }
 }
+
+/// Response body of `POST /credentials/derive`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeriveResponse {
+    /// The credential with a derived proof, disclosing the mandatory and selected claims only.
+    #[serde(flatten)]
+    pub verifiable_credential: DerivedCredential,
+}
+
+/// Response body of `GET /credentials/{id}`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetCredentialResponse {
+    /// The credential as it was returned from `POST /credentials/issue`.
+    pub verifiable_credential: serde_json::Value,
+}
+
+/// A credential secured either with an embedded or an enveloping proof.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum IssuedCredential {
+    /// A JSON-LD Verifiable Credential (VCDM v1.1) with a proof.
+    DataIntegrityV1(VerifiableCredentialV1DataIntegrity),
+    /// A JSON-LD Verifiable Credential with a proof.
+    DataIntegrity(VerifiableCredentialV2DataIntegrity),
+    /// A Verifiable Credential secured with an enveloping proof (e.g. JOSE).
+    Enveloped(EnvelopedVerifiableCredential),
+    /// A Verifiable Credential secured with COSE.
+    Cose(CoseCredential),
+    /// An SD-JWT VC.
+    SdJwt(SdJwtCredential),
+}
+
+impl IssuedCredential {
+    /// Get the credential with a Data Integrity proof, if any.
+    pub fn as_data_integrity(&self) -> Option<&VerifiableCredentialV2DataIntegrity> {
+        match self {
+            Self::DataIntegrity(vc) => Some(vc),
+            _ => None,
+        }
+    }
+
+    /// Get the VCDM v1.1 credential with a Data Integrity proof, if any.
+    pub fn as_data_integrity_v1(&self) -> Option<&VerifiableCredentialV1DataIntegrity> {
+        match self {
+            Self::DataIntegrityV1(vc) => Some(vc),
+            _ => None,
+        }
+    }
+
+    /// Get the enveloped credential, if any.
+    pub fn as_enveloped(&self) -> Option<&EnvelopedVerifiableCredential> {
+        match self {
+            Self::Enveloped(vc) => Some(vc),
+            Self::Cose(vc) => Some(&vc.enveloped),
+            _ => None,
+        }
+    }
+
+    /// Get the SD-JWT VC, if any.
+    pub fn as_sd_jwt(&self) -> Option<&SdJwtCredential> {
+        match self {
+            Self::SdJwt(vc) => Some(vc),
+            _ => None,
+        }
+    }
+}
+
+/// [Enveloped Verifiable Credential](https://www.w3.org/TR/vc-data-model-2.0/#enveloped-verifiable-credentials).
+///
+/// The secured credential is embedded in `id` as a [data URL](https://www.rfc-editor.org/rfc/rfc2397).
+#[derive(Clone, Debug, Serialize)]
+pub struct EnvelopedVerifiableCredential {
+    #[serde(rename = "@context")]
+    pub context: &'static str,
+    pub id: String,
+    #[serde(rename = "type")]
+    pub r#type: &'static str,
+}
+
+impl EnvelopedVerifiableCredential {
+    pub(crate) fn new(id: String) -> Self {
+        Self {
+            context: "https://www.w3.org/ns/credentials/v2",
+            id,
+            r#type: "EnvelopedVerifiableCredential",
+        }
+    }
+}
+
+/// A Verifiable Credential secured with [COSE](https://www.w3.org/TR/vc-jose-cose/#securing-with-cose).
+///
+/// Serialized as an [`EnvelopedVerifiableCredential`] with a `data:application/vc+cose;base64,...` URL.
+/// The raw `COSE_Sign1` bytes are returned instead if the client accepts `application/vc+cose`.
+#[derive(Clone, Debug, Serialize)]
+pub struct CoseCredential {
+    #[serde(flatten)]
+    pub enveloped: EnvelopedVerifiableCredential,
+    #[serde(skip)]
+    pub cose_sign1: Vec<u8>,
+}
+
+/// [SD-JWT VC](https://datatracker.ietf.org/doc/draft-ietf-oauth-sd-jwt-vc/) (`application/dc+sd-jwt`).
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SdJwtCredential {
+    /// Compact serialization: `<Issuer-signed JWT>~<Disclosure 1>~...~<Disclosure N>~`.
+    pub sd_jwt: String,
+    /// Disclosures contained in `sd_jwt`, so that clients can pick ones to present.
+    pub disclosures: Vec<SdJwtDisclosure>,
+}
+
+/// A [Disclosure](https://datatracker.ietf.org/doc/html/draft-ietf-oauth-selective-disclosure-jwt#name-disclosures)
+/// of a selectively disclosable claim.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SdJwtDisclosure {
+    /// Name of the disclosed claim.
+    pub claim: String,
+    /// Base64url-encoded disclosure.
+    pub disclosure: String,
+    /// Digest of `disclosure` embedded in the `_sd` claim.
+    pub digest: String,
+}
+
+/// Response body of `POST /credentials/verify`.
+///
+/// VC-API returns the same shape both for verified and unverified credentials.
+/// The HTTP status is `200` when `errors` is empty, and `400` otherwise.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyResponse {
+    /// Checks that passed (e.g. `proof`).
+    pub checks: Vec<String>,
+    /// Non-fatal problems found during verification.
+    pub warnings: Vec<ProblemDetails>,
+    /// Problems that made the verification fail.
+    pub errors: Vec<ProblemDetails>,
+}
+
+impl VerifyResponse {
+    pub(crate) fn verified(checks: Vec<String>) -> Self {
+        Self {
+            checks,
+            warnings: vec![],
+            errors: vec![],
+        }
+    }
+
+    pub(crate) fn failed(errors: Vec<ProblemDetails>) -> Self {
+        Self {
+            checks: vec![],
+            warnings: vec![],
+            errors,
+        }
+    }
+
+    /// Whether the credential has been verified without errors.
+    pub fn is_verified(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl IntoResponse for VerifyResponse {
+    fn into_response(self) -> Response {
+        let status = if self.is_verified() {
+            StatusCode::OK
+        } else {
+            StatusCode::BAD_REQUEST
+        };
+        (status, Json(self)).into_response()
+    }
+}
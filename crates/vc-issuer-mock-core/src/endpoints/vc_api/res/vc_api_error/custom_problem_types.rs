@@ -13,6 +13,8 @@ pub(crate) enum CustomProblemType {
     InvalidCryptosuiteError,
     VerificationMethodResolutionError,
     SignatureError,
+    CredentialNotFoundError,
+    IssuerNotFoundError,
     UnknownError,
 }
 
@@ -28,6 +30,12 @@ impl ProblemType for CustomProblemType {
             CustomProblemType::SignatureError => {
                 "https://github.com/laysakura/vc-issuer-mock#SIGNATURE_ERROR"
             }
+            CustomProblemType::CredentialNotFoundError => {
+                "https://github.com/laysakura/vc-issuer-mock#CREDENTIAL_NOT_FOUND_ERROR"
+            }
+            CustomProblemType::IssuerNotFoundError => {
+                "https://github.com/laysakura/vc-issuer-mock#ISSUER_NOT_FOUND_ERROR"
+            }
             CustomProblemType::UnknownError => {
                 "https://github.com/laysakura/vc-issuer-mock#UNKNOWN_ERROR"
             }
@@ -39,6 +47,8 @@ impl ProblemType for CustomProblemType {
             CustomProblemType::InvalidCryptosuiteError => -400,
             CustomProblemType::VerificationMethodResolutionError => -401,
             CustomProblemType::SignatureError => -402,
+            CustomProblemType::CredentialNotFoundError => -403,
+            CustomProblemType::IssuerNotFoundError => -404,
             CustomProblemType::UnknownError => -500,
         }
     }
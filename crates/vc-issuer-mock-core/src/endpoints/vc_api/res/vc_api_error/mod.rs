@@ -52,6 +52,11 @@ impl From<ProblemDetails> for VcApiError {
         let status = if code == CustomProblemType::UnknownError.code() {
             error!("InternalServerError: {:?}", problem_details);
             StatusCode::INTERNAL_SERVER_ERROR
+        } else if code == CustomProblemType::CredentialNotFoundError.code()
+            || code == CustomProblemType::IssuerNotFoundError.code()
+        {
+            debug!("NotFound: {:?}", problem_details);
+            StatusCode::NOT_FOUND
         } else {
             debug!("BadRequest: {:?}", problem_details);
             StatusCode::BAD_REQUEST
@@ -4,7 +4,7 @@
 //!
 //! - Private keys (JWK)
 //!   - Stored in a key manager. Deployed [servers](crate::server) use them to initialize [crate::IssuerKeys].
-//! - Public keys (JWK & did:key)
+//! - Public keys (JWK, did:key & did:jwk)
 //!   - `did:key`s are put into DID documents, and passed from [W3C test suites as issuer ids](https://github.com/laysakura/vc-issuer-mock/blob/main/crates/vc-issuer-mock-core/tests-vc-api/localConfig.cjs).
 
 use vc_issuer_mock_core::IssuerKeys;
@@ -19,6 +19,7 @@ fn main() {
         println!("Private key (JWK): {}", sk.to_private_jwk());
         println!("Public key (JWK): {}", vk.to_public_jwk());
         println!("Public key (DID): {}", vk.to_did_key());
+        println!("Public key (did:jwk): {}", vk.to_did_jwk());
         println!();
     }
 }
@@ -1,10 +1,14 @@
 use std::str::FromStr;
 
 use anyhow::bail;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
 use derive_more::Display;
-use josekit::jwk::{
-    alg::{ec::EcCurve, ed::EdCurve},
-    Jwk,
+use josekit::{
+    jwk::{
+        alg::{ec::EcCurve, ed::EdCurve},
+        Jwk,
+    },
+    jws::{EdDSA, JwsSigner, JwsVerifier, ES256, ES256K, ES384, RS256},
 };
 use ssi::{
     claims::SignatureError,
@@ -12,8 +16,11 @@ use ssi::{
     verification_methods::{LocalSigner, MaybeJwkVerificationMethod, Signer},
     JWK,
 };
+use thiserror::Error;
 use tracing::warn;
 
+use crate::cryptosuite::RequestedCryptosuite;
+
 /// A Container of pairs of signing (private) keys and verification (public) keys held by the issuer.
 ///
 /// Keys are represented in [JWK Set Format](https://datatracker.ietf.org/doc/html/rfc7517#section-5).
@@ -31,14 +38,19 @@ use tracing::warn;
 /// # Supported key types (`kty`)
 ///
 /// - RSA
-/// - EC
-/// - OKP ([RFC 8037](https://datatracker.ietf.org/doc/html/rfc8037))
+/// - EC (P-256, P-384, secp256k1, BLS12381G2)
+/// - OKP (Ed25519, [RFC 8037](https://datatracker.ietf.org/doc/html/rfc8037))
+///
+/// BLS12-381 G2 keys are only used for `bbs-2023` and cannot sign JWS.
 ///
 /// # Generated keys by default
 ///
 /// - RSA (2048 bits)
 /// - EC (P-384)
 /// - OKP (Ed25519)
+/// - EC (P-256)
+/// - EC (secp256k1)
+/// - EC (BLS12381G2)
 #[derive(Clone, Debug)]
 pub struct IssuerKeys(Vec<SigningKey>);
 
@@ -50,6 +62,47 @@ pub struct SigningKey(Jwk);
 #[derive(Clone, Eq, PartialEq, Debug, Display)]
 pub struct VerificationKey(Jwk);
 
+/// Error from [`IssuerKeys::try_new`].
+#[derive(Debug, Error)]
+pub enum IssuerKeysError {
+    /// No signing key is given.
+    #[error("empty signing keys")]
+    Empty,
+    /// The signing key at `index` (0-based) is rejected.
+    #[error("signing key #{index}: {source}")]
+    InvalidKey {
+        index: usize,
+        #[source]
+        source: KeyError,
+    },
+}
+
+/// Why a JWK is rejected as a signing or verification key.
+///
+/// Messages never contain the key itself, so that private keys are not leaked into logs.
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum KeyError {
+    /// Not a JWK, or its key material is corrupt. Has the reason.
+    #[error("invalid JWK: {0}")]
+    InvalidJwk(String),
+    /// A signing key without the private part (`d`).
+    #[error("({0}) not a private key")]
+    NotPrivateKey(String),
+    /// A verification key with the private part (`d`).
+    #[error("({0}) is a private key")]
+    NotPublicKey(String),
+    #[error("unsupported key type: {0}")]
+    UnsupportedKeyType(String),
+    #[error("unsupported curve for {kty}: {crv}")]
+    UnsupportedCurve { kty: String, crv: String },
+}
+
+/// Curves of signing keys, by key type.
+const SUPPORTED_CURVES: [(&str, &[&str]); 2] = [
+    ("EC", &["P-256", "P-384", "secp256k1", "BLS12381G2"]),
+    ("OKP", &["Ed25519"]),
+];
+
 impl IssuerKeys {
     /// Create a new `IssuerKeys` instance from given signing keys.
     ///
@@ -57,13 +110,7 @@ impl IssuerKeys {
     ///
     /// # Panics
     ///
-    /// This method panics if:
-    ///
-    /// - `signing_key_jwks` is empty.
-    /// - any signing key is:
-    ///   - not a valid JWK.
-    ///   - not a verification (private) key.
-    ///   - unsupported key type.
+    /// This method panics if [`Self::try_new`] fails. Use it for keys from user input.
     ///
     /// # Example
     ///
@@ -81,18 +128,49 @@ impl IssuerKeys {
         I: IntoIterator,
         I::Item: AsRef<str>,
     {
-        let signing_key_jwks = signing_key_jwks.into_iter().collect::<Vec<_>>();
-        assert!(!signing_key_jwks.is_empty(), "empty signing keys");
+        Self::try_new(signing_key_jwks).unwrap_or_else(|e| panic!("{}", e))
+    }
 
+    /// Create a new `IssuerKeys` instance from given signing keys, without panicking.
+    ///
+    /// # Errors
+    ///
+    /// - [`IssuerKeysError::Empty`] if `signing_key_jwks` is empty.
+    /// - [`IssuerKeysError::InvalidKey`] if any signing key is:
+    ///   - not a valid JWK, or has corrupt key material (e.g. a missing `x` or a `d` not matching it).
+    ///   - not a signing (private) key.
+    ///   - of an unsupported key type or curve.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use vc_issuer_mock_core::issuer_keys::{IssuerKeys, IssuerKeysError, KeyError};
+    ///
+    /// let err = IssuerKeys::try_new([r#"{"kty":"OKP","crv":"Ed25519","x":"11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo"}"#])
+    ///     .unwrap_err();
+    /// assert!(matches!(
+    ///     err,
+    ///     IssuerKeysError::InvalidKey { index: 0, source: KeyError::NotPrivateKey(_) }
+    /// ));
+    /// ```
+    pub fn try_new<I>(signing_key_jwks: I) -> Result<Self, IssuerKeysError>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
         let signing_keys = signing_key_jwks
-            .iter()
-            .map(|jwk| {
+            .into_iter()
+            .enumerate()
+            .map(|(index, jwk)| {
                 SigningKey::new(jwk.as_ref())
-                    .unwrap_or_else(|_| panic!("invalid JWK: {}", jwk.as_ref()))
+                    .map_err(|source| IssuerKeysError::InvalidKey { index, source })
             })
-            .collect();
+            .collect::<Result<Vec<_>, _>>()?;
+        if signing_keys.is_empty() {
+            return Err(IssuerKeysError::Empty);
+        }
 
-        Self(signing_keys)
+        Ok(Self(signing_keys))
     }
 
     /// Get signing keys specified by `kid` parameter in JWK.
@@ -146,6 +224,22 @@ impl IssuerKeys {
         })
     }
 
+    /// The key pair used when the issuer chooses its own key (e.g. status list credentials, access tokens).
+    /// An Ed25519 key is preferred for `eddsa-rdfc-2022`.
+    pub(crate) fn preferred_key_pair(&self) -> (SigningKey, VerificationKey) {
+        let mut key_pairs = self.key_pairs();
+        let index = key_pairs
+            .iter()
+            .position(|(_, vk)| RequestedCryptosuite::EddsaRdfc2022.supports_key(&JWK::from(vk)))
+            .unwrap_or(0);
+        key_pairs.swap_remove(index)
+    }
+
+    /// `did:key` of [`Self::preferred_key_pair`].
+    pub(crate) fn preferred_did_key(&self) -> String {
+        self.preferred_key_pair().1.to_did_key()
+    }
+
     pub(crate) fn into_local_signer(self) -> LocalSigner<Self> {
         LocalSigner(self)
     }
@@ -156,10 +250,17 @@ impl Default for IssuerKeys {
         let jws_rsa = Jwk::generate_rsa_key(2048).unwrap();
         let jws_ec = Jwk::generate_ec_key(EcCurve::P384).unwrap();
         let jws_okp = Jwk::generate_ed_key(EdCurve::Ed25519).unwrap();
+        let jws_ec_p256 = Jwk::generate_ec_key(EcCurve::P256).unwrap();
+        let jws_ec_secp256k1 = Jwk::generate_ec_key(EcCurve::Secp256k1).unwrap();
+        // josekit does not know BLS12-381.
+        let bls12381g2 = JWK::generate_bls12381g2();
         Self::new(vec![
             &jws_rsa.to_string(),
             &jws_ec.to_string(),
             &jws_okp.to_string(),
+            &jws_ec_p256.to_string(),
+            &jws_ec_secp256k1.to_string(),
+            &bls12381g2.to_string(),
         ])
     }
 }
@@ -169,73 +270,112 @@ impl SigningKey {
     ///
     /// # Errors
     ///
-    /// - Invalid JWK
-    /// - Unsupported key type
+    /// - Invalid JWK, including corrupt key material
+    /// - Unsupported key type or curve
     /// - Not a private key
-    fn new(signing_key_jwk: &str) -> anyhow::Result<Self> {
-        let jwk = Jwk::from_bytes(signing_key_jwk)
-            .unwrap_or_else(|_| panic!("invalid JWK: {}", signing_key_jwk));
+    fn new(signing_key_jwk: &str) -> Result<Self, KeyError> {
+        let jwk = parse_jwk(signing_key_jwk)?;
 
         // validation
-        match jwk.key_type() {
-            "RSA" => {
-                if jwk.parameter("d").is_none() {
-                    bail!("(RSA) not a private key: {}", signing_key_jwk);
-                }
-            }
-            "EC" => {
-                if jwk.parameter("d").is_none() {
-                    bail!("(EC) not a private key: {}", signing_key_jwk);
-                }
-            }
-            "OKP" => {
-                if jwk.parameter("d").is_none() {
-                    bail!("(OKP) not a private key: {}", signing_key_jwk);
-                }
+        let kty = jwk.key_type();
+        if !matches!(kty, "RSA" | "EC" | "OKP") {
+            return Err(KeyError::UnsupportedKeyType(kty.to_string()));
+        }
+        if jwk.parameter("d").is_none() {
+            return Err(KeyError::NotPrivateKey(kty.to_string()));
+        }
+        if let Some((_, curves)) = SUPPORTED_CURVES.iter().find(|(ty, _)| *ty == kty) {
+            let crv = jwk.curve().unwrap_or_default();
+            if !curves.contains(&crv) {
+                return Err(KeyError::UnsupportedCurve {
+                    kty: kty.to_string(),
+                    crv: crv.to_string(),
+                });
             }
-            ty => bail!("unsupported key type: {}", ty),
-        };
+        }
 
-        Ok(Self(jwk))
+        let sk = Self(jwk);
+        sk.check_key_material()?;
+        Ok(sk)
+    }
+
+    /// Derive everything later conversions (e.g. [`VerificationKey::to_did_key`]) need, so that they never panic.
+    ///
+    /// Error messages never contain the key itself.
+    fn check_key_material(&self) -> Result<(), KeyError> {
+        let invalid = |part: &str| KeyError::InvalidJwk(format!("invalid {} key material", part));
+
+        JWK::from_str(&self.0.to_string()).map_err(|_| invalid("private"))?;
+        let vk = self
+            .0
+            .to_public_key()
+            .map(VerificationKey)
+            .map_err(|_| invalid("public"))?;
+        let ssi_vk = JWK::from_str(&vk.0.to_string()).map_err(|_| invalid("public"))?;
+        DIDKey::generate(&ssi_vk).map_err(|_| invalid("public"))?;
+        ssi_vk.thumbprint().map_err(|_| invalid("public"))?;
+
+        // The private and public parts must be a pair. BLS12-381 G2 keys cannot sign JWS.
+        if self.0.curve() != Some("BLS12381G2") {
+            let signer = self.to_jws_signer().map_err(|_| invalid("private"))?;
+            let signature = signer
+                .sign(b"issuer key check")
+                .map_err(|_| invalid("private"))?;
+            vk.to_jws_verifier()
+                .and_then(|verifier| Ok(verifier.verify(b"issuer key check", &signature)?))
+                .map_err(|_| invalid("public"))?;
+        }
+        Ok(())
     }
 
     /// Convert the signing key into a JWK string.
     pub fn to_private_jwk(&self) -> String {
         self.to_string()
     }
+
+    /// Create a JWS signer from the signing key.
+    ///
+    /// The algorithm is chosen from the key type:
+    ///
+    /// - RSA: `RS256`
+    /// - EC (P-256): `ES256`
+    /// - EC (P-384): `ES384`
+    /// - EC (secp256k1): `ES256K`
+    /// - OKP (Ed25519): `EdDSA`
+    pub(crate) fn to_jws_signer(&self) -> anyhow::Result<Box<dyn JwsSigner>> {
+        let signer: Box<dyn JwsSigner> = match (self.0.key_type(), self.0.curve()) {
+            ("RSA", _) => Box::new(RS256.signer_from_jwk(&self.0)?),
+            ("EC", Some("P-256")) => Box::new(ES256.signer_from_jwk(&self.0)?),
+            ("EC", Some("P-384")) => Box::new(ES384.signer_from_jwk(&self.0)?),
+            ("EC", Some("secp256k1")) => Box::new(ES256K.signer_from_jwk(&self.0)?),
+            ("OKP", Some("Ed25519")) => Box::new(EdDSA.signer_from_jwk(&self.0)?),
+            (kty, crv) => bail!("unsupported key for JWS: kty={}, crv={:?}", kty, crv),
+        };
+        Ok(signer)
+    }
 }
 
 impl VerificationKey {
     /// All keys created from user input must pass this function.
     ///
+    /// Any curve is accepted, since verification keys may come from others (e.g. holders).
+    ///
     /// # Errors
     ///
     /// - Invalid JWK
     /// - Unsupported key type
     /// - Not a public key
-    fn new(verification_key_jwk: &str) -> anyhow::Result<Self> {
-        let jwk = Jwk::from_bytes(verification_key_jwk)
-            .unwrap_or_else(|_| panic!("invalid JWK: {}", verification_key_jwk));
+    fn new(verification_key_jwk: &str) -> Result<Self, KeyError> {
+        let jwk = parse_jwk(verification_key_jwk)?;
 
         // validation
-        match jwk.key_type() {
-            "RSA" => {
-                if jwk.parameter("d").is_some() {
-                    bail!("(RSA) is a private key: {}", verification_key_jwk);
-                }
-            }
-            "EC" => {
-                if jwk.parameter("d").is_some() {
-                    bail!("(EC) is a private key: {}", verification_key_jwk);
-                }
-            }
-            "OKP" => {
-                if jwk.parameter("d").is_some() {
-                    bail!("(OKP) is a private key: {}", verification_key_jwk);
-                }
-            }
-            ty => bail!("unsupported key type: {}", ty),
-        };
+        let kty = jwk.key_type();
+        if !matches!(kty, "RSA" | "EC" | "OKP") {
+            return Err(KeyError::UnsupportedKeyType(kty.to_string()));
+        }
+        if jwk.parameter("d").is_some() {
+            return Err(KeyError::NotPublicKey(kty.to_string()));
+        }
 
         Ok(Self(jwk))
     }
@@ -246,6 +386,10 @@ impl VerificationKey {
     }
 
     /// Convert the verification key into a DID key string.
+    ///
+    /// The DID document of the `did:key` expresses the key as a
+    /// [Multikey](https://www.w3.org/TR/controller-document/#multikey)
+    /// (e.g. `did:key:zUC7...` for a BLS12-381 G2 key).
     pub fn to_did_key(&self) -> String {
         let ssi_jwk = JWK::from(self);
         let did_key = DIDKey::generate(&ssi_jwk)
@@ -253,9 +397,50 @@ impl VerificationKey {
         did_key.to_string()
     }
 
+    /// Convert the verification key into a [did:jwk](https://github.com/quartzjer/did-jwk/blob/main/spec.md)
+    /// string. Its only verification method is `{did:jwk}#0`.
+    pub fn to_did_jwk(&self) -> String {
+        format!("did:jwk:{}", URL_SAFE_NO_PAD.encode(self.to_public_jwk()))
+    }
+
+    /// Parse a did:jwk, with or without the `#0` fragment.
+    ///
+    /// Returns `None` if it is not a did:jwk of a supported public key.
+    pub(crate) fn from_did_jwk(did_jwk: &str) -> Option<Self> {
+        let did_jwk = did_jwk.strip_suffix("#0").unwrap_or(did_jwk);
+        let encoded = did_jwk.strip_prefix("did:jwk:")?;
+        let jwk = URL_SAFE_NO_PAD.decode(encoded).ok()?;
+        Self::new(std::str::from_utf8(&jwk).ok()?).ok()
+    }
+
+    /// [JWK Thumbprint](https://datatracker.ietf.org/doc/html/rfc7638), used as `kid` in JWK Sets.
+    pub(crate) fn thumbprint(&self) -> String {
+        JWK::from(self)
+            .thumbprint()
+            .unwrap_or_else(|e| panic!("Failed to compute JWK thumbprint: {}", e))
+    }
+
+    /// Create a JWS verifier from the verification key. The algorithm is chosen in the same way as
+    /// [`SigningKey::to_jws_signer`].
+    pub(crate) fn to_jws_verifier(&self) -> anyhow::Result<Box<dyn JwsVerifier>> {
+        let verifier: Box<dyn JwsVerifier> = match (self.0.key_type(), self.0.curve()) {
+            ("RSA", _) => Box::new(RS256.verifier_from_jwk(&self.0)?),
+            ("EC", Some("P-256")) => Box::new(ES256.verifier_from_jwk(&self.0)?),
+            ("EC", Some("P-384")) => Box::new(ES384.verifier_from_jwk(&self.0)?),
+            ("EC", Some("secp256k1")) => Box::new(ES256K.verifier_from_jwk(&self.0)?),
+            ("OKP", Some("Ed25519")) => Box::new(EdDSA.verifier_from_jwk(&self.0)?),
+            (kty, crv) => bail!("unsupported key for JWS: kty={}, crv={:?}", kty, crv),
+        };
+        Ok(verifier)
+    }
+
+    /// Whether the key is picked for [JsonWebSignature2020](https://w3c.github.io/vc-jws-2020/)
+    /// when the issuer is not a DID.
     pub(crate) fn is_for_jwk2020(&self) -> bool {
-        // FIXME <https://w3c.github.io/vc-jws-2020/>
-        matches!(self.0.key_type(), "EC")
+        matches!(
+            (self.0.key_type(), self.0.curve()),
+            ("EC", Some("P-256" | "P-384" | "secp256k1"))
+        )
     }
 }
 
@@ -288,7 +473,7 @@ impl TryFrom<&JWK> for VerificationKey {
 
     fn try_from(jwk: &JWK) -> Result<Self, Self::Error> {
         let vk_str = jwk.to_string();
-        VerificationKey::new(&vk_str)
+        Ok(VerificationKey::new(&vk_str)?)
     }
 }
 
@@ -298,7 +483,7 @@ impl TryFrom<&JWK> for SigningKey {
 
     fn try_from(jwk: &JWK) -> Result<Self, Self::Error> {
         let sk_str = jwk.to_string();
-        SigningKey::new(&sk_str)
+        Ok(SigningKey::new(&sk_str)?)
     }
 }
 
@@ -326,6 +511,10 @@ impl<M: MaybeJwkVerificationMethod> Signer<M> for IssuerKeys {
     }
 }
 
+fn parse_jwk(jwk: &str) -> Result<Jwk, KeyError> {
+    Jwk::from_bytes(jwk).map_err(|e| KeyError::InvalidJwk(e.to_string()))
+}
+
 fn josekit_to_ssi(jwk: &Jwk) -> JWK {
     let json = jwk.to_string();
     JWK::from_str(&json).unwrap_or_else(|_| panic!("invalid JWK: {}", json))
@@ -337,8 +526,9 @@ mod tests {
 
     use crate::{
         test_jwks::{
-            JWK_EC_P384_PRIV, JWK_EC_P384_PUB, JWK_OKP_ED25519_PRIV, JWK_OKP_ED25519_PUB,
-            JWK_RSA_PRIV, JWK_RSA_PUB,
+            ISSMOCK_PRIV_EC_P256, ISSMOCK_PRIV_EC_P256_DIDKEY, ISSMOCK_PRIV_EC_SECP256K1,
+            ISSMOCK_PRIV_EC_SECP256K1_DIDKEY, JWK_EC_P384_PRIV, JWK_EC_P384_PUB,
+            JWK_OKP_ED25519_PRIV, JWK_OKP_ED25519_PUB, JWK_RSA_PRIV, JWK_RSA_PUB,
         },
         test_tracing::init_tracing,
     };
@@ -350,7 +540,7 @@ mod tests {
         init_tracing();
         let issuer_keys = IssuerKeys::default();
         debug!("IssuerKeys::defauls(): {:#?}", issuer_keys);
-        assert_eq!(issuer_keys.key_pairs().len(), 3);
+        assert_eq!(issuer_keys.key_pairs().len(), 6);
 
         // assert random keys are generated
         let issuer_keys2 = IssuerKeys::default();
@@ -365,6 +555,177 @@ mod tests {
         assert_eq!(issuer_keys.key_pairs().len(), 3);
     }
 
+    #[test]
+    fn test_signing_key_to_jws_signer_success() {
+        let issuer_keys =
+            IssuerKeys::new(vec![JWK_RSA_PRIV, JWK_EC_P384_PRIV, JWK_OKP_ED25519_PRIV]);
+        let algs = issuer_keys
+            .key_pairs()
+            .iter()
+            .map(|(sk, _)| sk.to_jws_signer().unwrap().algorithm().name().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(algs, vec!["RS256", "ES384", "EdDSA"]);
+    }
+
+    #[test]
+    fn test_signing_key_to_jws_signer_success_ec_curves() {
+        let issuer_keys = IssuerKeys::new(vec![ISSMOCK_PRIV_EC_P256, ISSMOCK_PRIV_EC_SECP256K1]);
+        for (sk, vk) in issuer_keys.key_pairs() {
+            let signer = sk.to_jws_signer().unwrap();
+            let signature = signer.sign(b"message").unwrap();
+            let verifier = vk.to_jws_verifier().unwrap();
+            assert_eq!(signer.algorithm().name(), verifier.algorithm().name());
+            verifier.verify(b"message", &signature).unwrap();
+            assert!(vk.is_for_jwk2020());
+        }
+    }
+
+    #[test]
+    fn test_verification_key_to_did_key_success_ec_curves() {
+        let issuer_keys = IssuerKeys::new(vec![ISSMOCK_PRIV_EC_P256, ISSMOCK_PRIV_EC_SECP256K1]);
+        let did_keys = issuer_keys
+            .key_pairs()
+            .iter()
+            .map(|(_, vk)| vk.to_did_key())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            did_keys,
+            vec![
+                ISSMOCK_PRIV_EC_P256_DIDKEY,
+                ISSMOCK_PRIV_EC_SECP256K1_DIDKEY
+            ]
+        );
+    }
+
+    #[test]
+    fn test_issuer_keys_new_success_bls12381g2() {
+        let bls12381g2 = JWK::generate_bls12381g2().to_string();
+        let issuer_keys = IssuerKeys::new(vec![bls12381g2]);
+        let (sk, vk) = &issuer_keys.key_pairs()[0];
+
+        assert!(RequestedCryptosuite::Bbs2023.supports_key(&JWK::from(vk)));
+        // bls12_381-g2-pub multicodec (0xeb)
+        assert!(vk.to_did_key().starts_with("did:key:zUC7"));
+        assert_eq!(issuer_keys.find_signing_key_from(vk).as_ref(), Some(sk));
+        assert!(!vk.is_for_jwk2020());
+        assert!(sk.to_jws_signer().is_err());
+    }
+
+    #[test]
+    fn test_verification_key_to_did_jwk_success() {
+        let issuer_keys = IssuerKeys::new(vec![JWK_OKP_ED25519_PRIV]);
+        let (_, vk) = &issuer_keys.key_pairs()[0];
+
+        let did_jwk = vk.to_did_jwk();
+        assert!(did_jwk.starts_with("did:jwk:ey"));
+        assert_eq!(VerificationKey::from_did_jwk(&did_jwk).as_ref(), Some(vk));
+        assert_eq!(
+            VerificationKey::from_did_jwk(&format!("{}#0", did_jwk)).as_ref(),
+            Some(vk)
+        );
+        assert_eq!(VerificationKey::from_did_jwk(&vk.to_did_key()), None);
+        // a private key must not be encoded in a DID
+        let sk_did_jwk = format!("did:jwk:{}", URL_SAFE_NO_PAD.encode(JWK_OKP_ED25519_PRIV));
+        assert_eq!(VerificationKey::from_did_jwk(&sk_did_jwk), None);
+    }
+
+    #[test]
+    fn test_issuer_keys_try_new_success() {
+        let jwks = vec![JWK_RSA_PRIV, JWK_EC_P384_PRIV, JWK_OKP_ED25519_PRIV];
+        let issuer_keys = IssuerKeys::try_new(jwks).unwrap();
+        assert_eq!(issuer_keys.key_pairs().len(), 3);
+    }
+
+    #[test]
+    fn test_issuer_keys_try_new_error_empty_jwks() {
+        let err = IssuerKeys::try_new(Vec::<&str>::new()).unwrap_err();
+        assert!(matches!(err, IssuerKeysError::Empty));
+    }
+
+    #[test]
+    fn test_issuer_keys_try_new_error_invalid_jwk() {
+        let err = IssuerKeys::try_new(vec![JWK_OKP_ED25519_PRIV, "INVALID-AS-JSON"]).unwrap_err();
+        assert!(matches!(
+            err,
+            IssuerKeysError::InvalidKey {
+                index: 1,
+                source: KeyError::InvalidJwk(_)
+            }
+        ));
+    }
+
+    #[test]
+    fn test_issuer_keys_try_new_error_corrupt_key_material() {
+        let mut wrong_x = serde_json::from_str::<serde_json::Value>(JWK_OKP_ED25519_PRIV).unwrap();
+        wrong_x["x"] = serde_json::Value::String("AAAA".to_string());
+        for jwk in [
+            r#"{"kty":"EC","crv":"P-384","d":"!!"}"#.to_string(),
+            r#"{"kty":"OKP","crv":"Ed25519","d":"nWGxne_9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyuf2A"}"#
+                .to_string(),
+            wrong_x.to_string(),
+        ] {
+            let err = IssuerKeys::try_new(vec![jwk]).unwrap_err();
+            assert!(
+                matches!(
+                    err,
+                    IssuerKeysError::InvalidKey {
+                        index: 0,
+                        source: KeyError::InvalidJwk(_)
+                    }
+                ),
+                "unexpected error: {}",
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn test_issuer_keys_try_new_error_pubkey() {
+        for (jwk, kty) in [
+            (JWK_RSA_PUB, "RSA"),
+            (JWK_EC_P384_PUB, "EC"),
+            (JWK_OKP_ED25519_PUB, "OKP"),
+        ] {
+            let err = IssuerKeys::try_new(vec![jwk]).unwrap_err();
+            let IssuerKeysError::InvalidKey { index, source } = err else {
+                panic!("should be InvalidKey");
+            };
+            assert_eq!(index, 0);
+            assert_eq!(source, KeyError::NotPrivateKey(kty.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_issuer_keys_try_new_error_unsupported_kty() {
+        let err = IssuerKeys::try_new(vec![r#"{"kty":"oct","k":"AyM1SysPpbyDfgZld3umj1qzKObwVMkoqQ-EstJQLr_T-1qS0gZH75aKtMN3Yj0iPS4hcgUuTwjAzZr1Z9CAow"}"#])
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            IssuerKeysError::InvalidKey {
+                source: KeyError::UnsupportedKeyType(_),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_issuer_keys_try_new_error_unsupported_curve() {
+        let p521 = Jwk::generate_ec_key(EcCurve::P521).unwrap().to_string();
+        let err = IssuerKeys::try_new(vec![p521]).unwrap_err();
+        let IssuerKeysError::InvalidKey { source, .. } = err else {
+            panic!("unexpected error: {}", err);
+        };
+        assert_eq!(
+            source,
+            KeyError::UnsupportedCurve {
+                kty: "EC".to_string(),
+                crv: "P-521".to_string()
+            }
+        );
+        // the key is not leaked in the message
+        assert_eq!(source.to_string(), "unsupported curve for EC: P-521");
+    }
+
     #[test]
     #[should_panic]
     fn test_issuer_keys_new_panic_empty_jwks() {
@@ -0,0 +1,406 @@
+//! Named issuer profiles, so that a single mock plays several issuers (e.g. a university, a government and a bank).
+//!
+//! Each profile has its own [`IssuerKeys`] and is served under `/issuers/{name}/...`.
+//! A profile signs only with its own keys, never with the keys of other profiles or the default issuer keys.
+//!
+//! If the `issuer` of a profile is `did:web:{host}:dids:{name}`, its DID document at `/dids/{name}/did.json`
+//! lists the keys of the profile.
+//!
+//! A profile with [`StatusLists`] has its own status lists, whose credentials are signed with the keys of the profile.
+
+use std::{collections::BTreeMap, str::FromStr, sync::Arc};
+
+use anyhow::{anyhow, bail, Context};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use ssi::JWK;
+
+use crate::{cryptosuite::RequestedCryptosuite, status_list::StatusLists, IssuerKeys};
+
+/// A named issuer identity with its own keys and defaults.
+#[derive(Clone, Debug)]
+pub struct IssuerProfile {
+    name: String,
+    issuer_keys: IssuerKeys,
+    /// `issuer` set to credentials issued without one.
+    issuer: Option<String>,
+    /// `cryptosuite` option used when neither `type` nor `cryptosuite` is requested.
+    cryptosuite: Option<String>,
+    default_properties: DefaultVcProperties,
+    /// Status lists referred to from the credentials issued by the profile.
+    status_lists: Option<StatusLists>,
+}
+
+/// Properties set to credentials issued without them (e.g. `{"name": "Example University"}`).
+///
+/// `POST /credentials/issue` uses them if added as an [`Extension`](axum::Extension).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DefaultVcProperties(Map<String, Value>);
+
+/// An [`IssuerProfile`] as written in configurations.
+///
+/// ```json
+/// {
+///   "name": "university",
+///   "issuer": "did:web:localhost%3A40080:dids:university",
+///   "keys": [{"kty": "OKP", "crv": "Ed25519", "d": "...", "x": "..."}],
+///   "cryptosuite": "eddsa-rdfc-2022",
+///   "defaultProperties": {"name": "Example University"}
+/// }
+/// ```
+///
+/// Random keys are generated if `keys` is omitted.
+///
+/// Keys are inline JWKs by default. Configurations may write them otherwise (e.g. as paths to JWK files)
+/// with another `K`, and load them before converting into an [`IssuerProfile`].
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct IssuerProfileConfig<K = Map<String, Value>> {
+    pub name: String,
+    pub issuer: Option<String>,
+    #[serde(default)]
+    pub keys: Vec<K>,
+    pub cryptosuite: Option<String>,
+    #[serde(default)]
+    pub default_properties: Map<String, Value>,
+}
+
+/// Registry of [`IssuerProfile`]s by name.
+#[derive(Clone, Debug, Default)]
+pub struct IssuerProfiles(Arc<BTreeMap<String, IssuerProfile>>);
+
+impl IssuerProfile {
+    /// Create a profile signing with `issuer_keys`, served under `/issuers/{name}/...`.
+    ///
+    /// # Errors
+    ///
+    /// If `name` is not a single non-empty path segment.
+    pub fn new(name: impl Into<String>, issuer_keys: IssuerKeys) -> anyhow::Result<Self> {
+        let name = name.into();
+        if name.is_empty() || name.contains(['/', '?', '#', ':']) {
+            bail!("invalid issuer profile name: `{}`", name);
+        }
+
+        Ok(Self {
+            name,
+            issuer_keys,
+            issuer: None,
+            cryptosuite: None,
+            default_properties: DefaultVcProperties::default(),
+            status_lists: None,
+        })
+    }
+
+    /// Set `issuer` (URL or DID) to credentials issued without one.
+    pub fn with_issuer(mut self, issuer: Option<String>) -> Self {
+        self.issuer = issuer;
+        self
+    }
+
+    /// Sign Data Integrity proofs with `cryptosuite` unless another suite is requested.
+    ///
+    /// # Errors
+    ///
+    /// If `cryptosuite` is unknown, or none of the keys of the profile can be used with it.
+    pub fn with_cryptosuite(mut self, cryptosuite: Option<String>) -> anyhow::Result<Self> {
+        if let Some(cryptosuite) = &cryptosuite {
+            let requested = RequestedCryptosuite::from_name(cryptosuite)
+                .map_err(|_| anyhow!("unknown cryptosuite: `{}`", cryptosuite))?;
+            let supported = self.issuer_keys.key_pairs().iter().any(|(_, vk)| {
+                JWK::from_str(&vk.to_public_jwk()).is_ok_and(|jwk| requested.supports_key(&jwk))
+            });
+            if !supported {
+                bail!(
+                    "no key of issuer profile `{}` can be used with the cryptosuite `{}`",
+                    self.name,
+                    cryptosuite
+                );
+            }
+        }
+        self.cryptosuite = cryptosuite;
+        Ok(self)
+    }
+
+    /// Set `default_properties` to credentials issued without them.
+    pub fn with_default_properties(mut self, default_properties: Map<String, Value>) -> Self {
+        self.default_properties = DefaultVcProperties::new(default_properties);
+        self
+    }
+
+    /// Add `BitstringStatusListEntry`s of `status_lists` to credentials issued by the profile.
+    ///
+    /// The status lists must be only for this profile, and issued as its `issuer` (see [`StatusLists::with_issuer`]).
+    pub fn with_status_lists(mut self, status_lists: Option<StatusLists>) -> Self {
+        self.status_lists = status_lists;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn issuer(&self) -> Option<&str> {
+        self.issuer.as_deref()
+    }
+
+    pub fn issuer_keys(&self) -> &IssuerKeys {
+        &self.issuer_keys
+    }
+
+    pub fn status_lists(&self) -> Option<&StatusLists> {
+        self.status_lists.as_ref()
+    }
+
+    /// Fill the defaults of the profile into a `POST /credentials/issue` request body.
+    ///
+    /// Only properties and options missing in the request are filled.
+    pub(crate) fn apply_defaults(&self, req: &mut Value) {
+        if let (Some(issuer), Some(Value::Object(credential))) =
+            (&self.issuer, req.get_mut("credential"))
+        {
+            credential
+                .entry("issuer")
+                .or_insert_with(|| Value::String(issuer.clone()));
+        }
+        self.default_properties.apply(req);
+
+        let Some(cryptosuite) = &self.cryptosuite else {
+            return;
+        };
+        let Value::Object(req) = req else {
+            return;
+        };
+        let options = req
+            .entry("options")
+            .or_insert_with(|| Value::Object(Map::new()));
+        if let Value::Object(options) = options {
+            // A suite for selective disclosure is picked from the key when `mandatoryPointers` are given.
+            let is_data_integrity = options
+                .get("securingMechanism")
+                .map_or(true, |mechanism| mechanism == "data-integrity");
+            if is_data_integrity
+                && !options.contains_key("type")
+                && !options.contains_key("cryptosuite")
+                && !options.contains_key("mandatoryPointers")
+            {
+                options.insert(
+                    "cryptosuite".to_string(),
+                    Value::String(cryptosuite.clone()),
+                );
+            }
+        }
+    }
+}
+
+impl DefaultVcProperties {
+    pub fn new(properties: Map<String, Value>) -> Self {
+        Self(properties)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Fill the properties missing in the `credential` of a `POST /credentials/issue` request body.
+    pub(crate) fn apply(&self, req: &mut Value) {
+        if let Some(Value::Object(credential)) = req.get_mut("credential") {
+            for (key, value) in &self.0 {
+                credential
+                    .entry(key.as_str())
+                    .or_insert_with(|| value.clone());
+            }
+        }
+    }
+}
+
+impl TryFrom<IssuerProfileConfig> for IssuerProfile {
+    type Error = anyhow::Error;
+
+    fn try_from(config: IssuerProfileConfig) -> Result<Self, Self::Error> {
+        let issuer_keys = if config.keys.is_empty() {
+            IssuerKeys::default()
+        } else {
+            let sk_jwks = config
+                .keys
+                .iter()
+                .map(|jwk| Value::Object(jwk.clone()).to_string())
+                .collect::<Vec<_>>();
+            IssuerKeys::try_new(&sk_jwks)
+                .with_context(|| format!("issuer profile `{}`", config.name))?
+        };
+
+        let profile = Self::new(config.name.clone(), issuer_keys)?
+            .with_issuer(config.issuer)
+            .with_default_properties(config.default_properties)
+            .with_cryptosuite(config.cryptosuite)
+            .with_context(|| format!("issuer profile `{}`", config.name))?;
+        Ok(profile)
+    }
+}
+
+impl IssuerProfiles {
+    /// # Errors
+    ///
+    /// If names of the profiles are not unique.
+    pub fn new(profiles: impl IntoIterator<Item = IssuerProfile>) -> anyhow::Result<Self> {
+        let mut map = BTreeMap::new();
+        for profile in profiles {
+            if map.contains_key(profile.name()) {
+                bail!("duplicate issuer profile name: `{}`", profile.name());
+            }
+            map.insert(profile.name().to_string(), profile);
+        }
+        Ok(Self(Arc::new(map)))
+    }
+
+    /// Load profiles from a JSON array of [`IssuerProfileConfig`]s.
+    ///
+    /// # Errors
+    ///
+    /// If the JSON is invalid, or any profile is rejected by [`IssuerProfile::try_from`] or [`Self::new`].
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        let configs: Vec<IssuerProfileConfig> =
+            serde_json::from_str(json).context("invalid issuer profiles")?;
+        let profiles = configs
+            .into_iter()
+            .map(IssuerProfile::try_from)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Self::new(profiles)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&IssuerProfile> {
+        self.0.get(name)
+    }
+
+    /// Profiles in the order of their names.
+    pub fn iter(&self) -> impl Iterator<Item = &IssuerProfile> {
+        self.0.values()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::test_jwks::{ISSMOCK_PRIV_EC_P384, ISSMOCK_PRIV_OKP_ED25519};
+
+    use super::*;
+
+    fn university() -> IssuerProfile {
+        IssuerProfile::new(
+            "university",
+            IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519]),
+        )
+        .unwrap()
+        .with_issuer(Some("https://university.example".to_string()))
+        .with_cryptosuite(Some("eddsa-jcs-2022".to_string()))
+        .unwrap()
+        .with_default_properties(
+            json!({ "name": "Example University" })
+                .as_object()
+                .unwrap()
+                .clone(),
+        )
+    }
+
+    #[test]
+    fn test_issuer_profile_new_error() {
+        for name in ["", "a/b", "a:b"] {
+            assert!(IssuerProfile::new(name, IssuerKeys::default()).is_err());
+        }
+        assert!(IssuerProfile::new("bank", IssuerKeys::default())
+            .unwrap()
+            .with_cryptosuite(Some("unknown-2099".to_string()))
+            .is_err());
+        // no P-256 key for ecdsa-sd-2023
+        assert!(IssuerProfile::new(
+            "bank",
+            IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519, ISSMOCK_PRIV_EC_P384])
+        )
+        .unwrap()
+        .with_cryptosuite(Some("ecdsa-sd-2023".to_string()))
+        .is_err());
+    }
+
+    #[test]
+    fn test_apply_defaults_success_missing() {
+        let mut req = json!({ "credential": { "type": ["VerifiableCredential"] } });
+        university().apply_defaults(&mut req);
+
+        assert_eq!(req["credential"]["issuer"], "https://university.example");
+        assert_eq!(req["credential"]["name"], "Example University");
+        assert_eq!(req["options"]["cryptosuite"], "eddsa-jcs-2022");
+    }
+
+    #[test]
+    fn test_apply_defaults_success_given() {
+        let mut req = json!({
+            "credential": { "issuer": "did:example:issuer", "name": "Other" },
+            "options": { "securingMechanism": "jose" },
+        });
+        university().apply_defaults(&mut req);
+
+        assert_eq!(req["credential"]["issuer"], "did:example:issuer");
+        assert_eq!(req["credential"]["name"], "Other");
+        assert!(req["options"].get("cryptosuite").is_none());
+
+        let mut req = json!({
+            "credential": {},
+            "options": { "mandatoryPointers": ["/issuer"] },
+        });
+        university().apply_defaults(&mut req);
+        assert!(req["options"].get("cryptosuite").is_none());
+    }
+
+    #[test]
+    fn test_default_vc_properties_apply() {
+        let default_properties = DefaultVcProperties::new(
+            json!({ "name": "Example", "description": "Issued by the mock" })
+                .as_object()
+                .unwrap()
+                .clone(),
+        );
+        let mut req = json!({ "credential": { "name": "Given" } });
+        default_properties.apply(&mut req);
+
+        assert_eq!(req["credential"]["name"], "Given");
+        assert_eq!(req["credential"]["description"], "Issued by the mock");
+    }
+
+    #[test]
+    fn test_issuer_profiles_from_json_success() {
+        let json = json!([
+            { "name": "government", "keys": [serde_json::from_str::<Value>(ISSMOCK_PRIV_EC_P384).unwrap()] },
+            { "name": "bank" },
+        ]);
+        let profiles = IssuerProfiles::from_json(&json.to_string()).unwrap();
+
+        let names = profiles.iter().map(|p| p.name()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["bank", "government"]);
+        assert_eq!(
+            profiles
+                .get("government")
+                .unwrap()
+                .issuer_keys()
+                .key_pairs()
+                .len(),
+            1
+        );
+        assert!(profiles.get("university").is_none());
+    }
+
+    #[test]
+    fn test_issuer_profiles_from_json_error() {
+        for json in [
+            json!([{ "name": "bank" }, { "name": "bank" }]),
+            json!([{ "name": "bank", "keys": [{ "kty": "OKP" }] }]),
+            json!([{ "name": "bank", "unknown": true }]),
+        ] {
+            assert!(IssuerProfiles::from_json(&json.to_string()).is_err());
+        }
+    }
+}
@@ -3,8 +3,19 @@
 pub mod issuer_keys;
 pub use issuer_keys::IssuerKeys;
 
+pub mod credential_id;
+pub mod credential_store;
+pub mod did_web;
 pub mod endpoints;
+pub mod issuer_profile;
+pub mod oid4vci;
+#[cfg(feature = "sqlite")]
+pub mod sqlite_store;
+pub mod status_list;
 
+pub(crate) mod cryptosuite;
+pub(crate) mod sd_jwt;
+pub(crate) mod vc_jose_cose;
 pub(crate) mod vcdm_v2;
 pub(crate) mod verification_method;
 
@@ -75,6 +86,42 @@ mod tests {
         serde_json::from_str(&body_str).unwrap()
     }
 
+    #[tokio::test]
+    async fn test_issue_cose_binary_success() {
+        init_tracing();
+
+        let app = Router::new()
+            .route(
+                "/credentials/issue",
+                post(endpoints::vc_api::credentials::issue),
+            )
+            .layer(Extension(IssuerKeys::default()));
+
+        let mut req_body: Value = serde_json::from_str(CREDENTIAL_OK).unwrap();
+        req_body["options"] = serde_json::json!({ "securingMechanism": "cose" });
+
+        let req = Request::builder()
+            .method("POST")
+            .uri("/credentials/issue")
+            .header("content-type", "application/json")
+            .header("accept", "application/vc+cose")
+            .body(Body::from(req_body.to_string()))
+            .unwrap();
+
+        let res = app.oneshot(req).await.unwrap();
+        assert_eq!(res.status(), 201);
+        assert_eq!(
+            res.headers().get("content-type").unwrap(),
+            "application/vc+cose"
+        );
+
+        let body = to_bytes(res.into_response().into_body(), usize::MAX)
+            .await
+            .unwrap();
+        // COSE_Sign1 tag (18)
+        assert_eq!(body[0], 0xd2);
+    }
+
     #[tokio::test]
     async fn test_issue_success() {
         let json = issue(CREDENTIAL_OK).await;
@@ -0,0 +1,229 @@
+//! [JWT access tokens](https://datatracker.ietf.org/doc/html/rfc9068) issued by the built-in authorization server.
+//!
+//! Access tokens are signed with an issuer key, which is published in the JWK Set of the authorization server.
+//! The credential configurations the token is valid for are carried in `authorization_details`.
+
+use std::time::{Duration, SystemTime};
+
+use anyhow::{anyhow, Context as _};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use josekit::{
+    jws::JwsHeader,
+    jwt::{self, JwtPayload},
+    util::random_bytes,
+    JoseHeader as _,
+};
+use serde_json::{json, Value};
+
+use crate::IssuerKeys;
+
+/// How long an access token is valid.
+pub const ACCESS_TOKEN_LIFETIME: Duration = Duration::from_secs(3600);
+
+/// `typ` header of access tokens.
+const ACCESS_TOKEN_TYP: &str = "at+jwt";
+
+/// `type` of [authorization details](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0.html#name-using-authorization-details).
+pub(crate) const OPENID_CREDENTIAL: &str = "openid_credential";
+
+/// Authorization given by a verified access token.
+#[derive(Clone, Debug)]
+pub(crate) struct AccessToken {
+    pub(crate) credential_configuration_ids: Vec<String>,
+}
+
+/// Sign an access token for the credential configurations.
+///
+/// `client_id` is `None` in the pre-authorized code flow with anonymous access.
+pub(crate) fn sign_access_token(
+    issuer_keys: &IssuerKeys,
+    credential_issuer: &str,
+    client_id: Option<&str>,
+    credential_configuration_ids: &[String],
+) -> anyhow::Result<String> {
+    let (sk, vk) = issuer_keys.preferred_key_pair();
+    let signer = sk.to_jws_signer()?;
+
+    let mut header = JwsHeader::new();
+    header.set_token_type(ACCESS_TOKEN_TYP);
+    header.set_key_id(vk.thumbprint());
+
+    let now = SystemTime::now();
+    let jti = URL_SAFE_NO_PAD.encode(random_bytes(16));
+    let authorization_details = credential_configuration_ids
+        .iter()
+        .map(|id| json!({ "type": OPENID_CREDENTIAL, "credential_configuration_id": id }))
+        .collect::<Vec<_>>();
+
+    let mut payload = JwtPayload::new();
+    payload.set_issuer(credential_issuer);
+    payload.set_audience(vec![credential_issuer]);
+    payload.set_subject(client_id.unwrap_or(&jti));
+    payload.set_issued_at(&now);
+    payload.set_expires_at(&(now + ACCESS_TOKEN_LIFETIME));
+    payload.set_jwt_id(&jti);
+    if let Some(client_id) = client_id {
+        payload.set_claim("client_id", Some(Value::from(client_id)))?;
+    }
+    payload.set_claim(
+        "authorization_details",
+        Some(Value::Array(authorization_details)),
+    )?;
+
+    Ok(jwt::encode_with_signer(&payload, &header, signer.as_ref())?)
+}
+
+/// Verify an access token issued by [`sign_access_token`].
+///
+/// The token must be issued by, and audienced to, `credential_issuer`.
+pub(crate) fn verify_access_token(
+    issuer_keys: &IssuerKeys,
+    credential_issuer: &str,
+    token: &str,
+) -> anyhow::Result<AccessToken> {
+    let header = jwt::decode_header(token)?;
+    if header.claim("typ").and_then(Value::as_str) != Some(ACCESS_TOKEN_TYP) {
+        return Err(anyhow!("not an access token"));
+    }
+    let kid = header
+        .claim("kid")
+        .and_then(Value::as_str)
+        .context("`kid` header is missing")?;
+    let (_, vk) = issuer_keys
+        .key_pairs()
+        .into_iter()
+        .find(|(_, vk)| vk.thumbprint() == kid)
+        .context("unknown `kid`")?;
+
+    let verifier = vk.to_jws_verifier()?;
+    let (payload, _) = jwt::decode_with_verifier(token, verifier.as_ref())?;
+
+    if payload.issuer() != Some(credential_issuer) {
+        return Err(anyhow!("unexpected `iss`"));
+    }
+    if !payload
+        .audience()
+        .is_some_and(|aud| aud.contains(&credential_issuer))
+    {
+        return Err(anyhow!("unexpected `aud`"));
+    }
+    if !payload
+        .expires_at()
+        .is_some_and(|exp| exp > SystemTime::now())
+    {
+        return Err(anyhow!("expired"));
+    }
+
+    let credential_configuration_ids = payload
+        .claim("authorization_details")
+        .and_then(Value::as_array)
+        .context("`authorization_details` is missing")?
+        .iter()
+        .filter(|detail| detail["type"] == OPENID_CREDENTIAL)
+        .filter_map(|detail| detail["credential_configuration_id"].as_str())
+        .map(|id| id.to_string())
+        .collect();
+
+    Ok(AccessToken {
+        credential_configuration_ids,
+    })
+}
+
+/// [JWK Set](https://datatracker.ietf.org/doc/html/rfc7517#section-5) of the keys signing access tokens.
+pub(crate) fn jwks(issuer_keys: &IssuerKeys) -> Value {
+    let keys = issuer_keys
+        .key_pairs()
+        .iter()
+        .map(|(_, vk)| {
+            let mut jwk: Value = serde_json::from_str(&vk.to_public_jwk())
+                .expect("verification key should be a JSON object");
+            jwk["kid"] = Value::String(vk.thumbprint());
+            jwk["use"] = Value::String("sig".to_string());
+            jwk
+        })
+        .collect::<Vec<_>>();
+    json!({ "keys": keys })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_jwks::{ISSMOCK_PRIV_EC_P384, ISSMOCK_PRIV_OKP_ED25519};
+
+    use super::*;
+
+    const CREDENTIAL_ISSUER: &str = "http://localhost:40080";
+
+    fn issuer_keys() -> IssuerKeys {
+        IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519, ISSMOCK_PRIV_EC_P384])
+    }
+
+    #[test]
+    fn test_verify_access_token_success() {
+        let issuer_keys = issuer_keys();
+        let token = sign_access_token(
+            &issuer_keys,
+            CREDENTIAL_ISSUER,
+            Some("wallet"),
+            &["ExampleCredential_ldp_vc".to_string()],
+        )
+        .unwrap();
+
+        let access_token = verify_access_token(&issuer_keys, CREDENTIAL_ISSUER, &token).unwrap();
+        assert_eq!(
+            access_token.credential_configuration_ids,
+            vec!["ExampleCredential_ldp_vc"]
+        );
+    }
+
+    #[test]
+    fn test_verify_access_token_error_other_issuer() {
+        let issuer_keys = issuer_keys();
+        let token = sign_access_token(&issuer_keys, "https://other.example", None, &[]).unwrap();
+
+        assert!(verify_access_token(&issuer_keys, CREDENTIAL_ISSUER, &token).is_err());
+    }
+
+    #[test]
+    fn test_verify_access_token_error_other_audience() {
+        let issuer_keys = issuer_keys();
+        let (sk, vk) = issuer_keys.preferred_key_pair();
+
+        let mut header = JwsHeader::new();
+        header.set_token_type(ACCESS_TOKEN_TYP);
+        header.set_key_id(vk.thumbprint());
+        let mut payload = JwtPayload::new();
+        payload.set_issuer(CREDENTIAL_ISSUER);
+        payload.set_audience(vec!["https://other.example"]);
+        payload.set_expires_at(&(SystemTime::now() + ACCESS_TOKEN_LIFETIME));
+        payload
+            .set_claim("authorization_details", Some(json!([])))
+            .unwrap();
+        let token =
+            jwt::encode_with_signer(&payload, &header, sk.to_jws_signer().unwrap().as_ref())
+                .unwrap();
+
+        let e = verify_access_token(&issuer_keys, CREDENTIAL_ISSUER, &token).unwrap_err();
+        assert_eq!(e.to_string(), "unexpected `aud`");
+    }
+
+    #[test]
+    fn test_verify_access_token_error_unknown_key() {
+        let token =
+            sign_access_token(&IssuerKeys::default(), CREDENTIAL_ISSUER, None, &[]).unwrap();
+
+        assert!(verify_access_token(&issuer_keys(), CREDENTIAL_ISSUER, &token).is_err());
+    }
+
+    #[test]
+    fn test_jwks_success() {
+        let issuer_keys = issuer_keys();
+        let jwks = jwks(&issuer_keys);
+
+        let keys = jwks["keys"].as_array().unwrap();
+        assert_eq!(keys.len(), 2);
+        for key in keys {
+            assert!(key.get("d").is_none());
+            assert!(key["kid"].is_string());
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! Authorization requests to the built-in authorization server
+//! ([RFC 6749](https://datatracker.ietf.org/doc/html/rfc6749#section-4.1.1) with
+//! [PKCE](https://datatracker.ietf.org/doc/html/rfc7636)).
+//!
+//! Credential configurations are requested either by `scope` (the credential configuration id) or by
+//! [`authorization_details`](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0.html#name-using-authorization-details).
+//!
+//! No user is authenticated. Requests are approved or denied following [`ConsentDecision`]s scripted
+//! in advance, and approved when there is no scripted decision.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    endpoints::oid4vci::res::oid4vci_error::{Oid4vciError, Oid4vciErrorCode},
+    oid4vci::{access_token::OPENID_CREDENTIAL, grants::AuthorizationCode, Oid4vciIssuer},
+};
+
+/// Parameters of an authorization request or a pushed authorization request.
+///
+/// All of them are optional here, since an authorization request referring to a pushed one has only
+/// `client_id` and `request_uri`.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct AuthorizationRequest {
+    pub response_type: Option<String>,
+    pub client_id: Option<String>,
+    pub redirect_uri: Option<String>,
+    pub state: Option<String>,
+    pub scope: Option<String>,
+    /// JSON array of authorization details objects.
+    pub authorization_details: Option<String>,
+    pub code_challenge: Option<String>,
+    pub code_challenge_method: Option<String>,
+    /// `issuer_state` from a credential offer. Accepted, but not used.
+    pub issuer_state: Option<String>,
+    /// `request_uri` of a pushed authorization request.
+    pub request_uri: Option<String>,
+}
+
+/// How the mock user answers an authorization request.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsentDecision {
+    Approve,
+    Deny,
+}
+
+impl AuthorizationRequest {
+    /// Validate the request and turn it into an (unissued) authorization code.
+    pub(crate) fn validate(
+        &self,
+        issuer: &Oid4vciIssuer,
+    ) -> Result<(AuthorizationCode, Option<String>), Oid4vciError> {
+        if self.response_type.as_deref() != Some("code") {
+            return Err(invalid_request("`response_type` must be `code`"));
+        }
+        let client_id = required("client_id", &self.client_id)?;
+        let redirect_uri = required("redirect_uri", &self.redirect_uri)?;
+        if !redirect_uri.contains("://") {
+            return Err(invalid_request("`redirect_uri` must be an absolute URI"));
+        }
+
+        let code_challenge = required("code_challenge", &self.code_challenge)?;
+        if self.code_challenge_method.as_deref() != Some("S256") {
+            return Err(invalid_request("`code_challenge_method` must be `S256`"));
+        }
+
+        let credential_configuration_ids = self.credential_configuration_ids()?;
+        if credential_configuration_ids.is_empty() {
+            return Err(invalid_request(
+                "no credential configuration is requested in `scope` or `authorization_details`",
+            ));
+        }
+        if let Some(id) = credential_configuration_ids
+            .iter()
+            .find(|id| issuer.configuration(id).is_none())
+        {
+            return Err(Oid4vciError::bad_request(
+                Oid4vciErrorCode::UnknownCredentialConfiguration,
+                format!("unknown credential configuration: {}", id),
+            ));
+        }
+
+        Ok((
+            AuthorizationCode {
+                client_id: client_id.to_string(),
+                redirect_uri: redirect_uri.to_string(),
+                code_challenge: code_challenge.to_string(),
+                credential_configuration_ids,
+            },
+            self.state.clone(),
+        ))
+    }
+
+    fn credential_configuration_ids(&self) -> Result<Vec<String>, Oid4vciError> {
+        let mut ids = self
+            .scope
+            .iter()
+            .flat_map(|scope| scope.split(' '))
+            .filter(|scope| !scope.is_empty())
+            .map(|scope| scope.to_string())
+            .collect::<Vec<_>>();
+
+        if let Some(authorization_details) = &self.authorization_details {
+            let details: Vec<Value> = serde_json::from_str(authorization_details).map_err(|e| {
+                invalid_request(format!(
+                    "`authorization_details` is not a JSON array: {}",
+                    e
+                ))
+            })?;
+            for detail in details {
+                match (
+                    detail["type"].as_str(),
+                    detail["credential_configuration_id"].as_str(),
+                ) {
+                    (Some(OPENID_CREDENTIAL), Some(id)) => ids.push(id.to_string()),
+                    _ => {
+                        return Err(invalid_request(format!(
+                            "unsupported authorization details: {}",
+                            detail
+                        )))
+                    }
+                }
+            }
+        }
+
+        ids.sort();
+        ids.dedup();
+        Ok(ids)
+    }
+}
+
+/// Check a PKCE `code_verifier` against the `S256` `code_challenge`.
+pub(crate) fn verify_code_verifier(code_verifier: &str, code_challenge: &str) -> bool {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes())) == code_challenge
+}
+
+/// Append query parameters to `redirect_uri`.
+pub(crate) fn redirect_uri_with(redirect_uri: &str, params: &[(&str, &str)]) -> String {
+    let mut uri = redirect_uri.to_string();
+    for (i, (name, value)) in params.iter().enumerate() {
+        let separator = match (i, redirect_uri.contains('?')) {
+            (0, false) => '?',
+            _ => '&',
+        };
+        uri.push(separator);
+        uri.push_str(name);
+        uri.push('=');
+        uri.push_str(&utf8_percent_encode(value, NON_ALPHANUMERIC).to_string());
+    }
+    uri
+}
+
+fn required<'a>(name: &str, value: &'a Option<String>) -> Result<&'a str, Oid4vciError> {
+    value
+        .as_deref()
+        .ok_or_else(|| invalid_request(format!("`{}` is required", name)))
+}
+
+fn invalid_request(error_description: impl Into<String>) -> Oid4vciError {
+    Oid4vciError::bad_request(Oid4vciErrorCode::InvalidRequest, error_description)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// <https://datatracker.ietf.org/doc/html/rfc7636#appendix-B>
+    pub(crate) const CODE_VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+    pub(crate) const CODE_CHALLENGE: &str = "E9Melhoa2OwvFrEMTJguCINvNxN4Rh5yYqlJIhq4xSU";
+
+    fn request() -> AuthorizationRequest {
+        AuthorizationRequest {
+            response_type: Some("code".to_string()),
+            client_id: Some("wallet".to_string()),
+            redirect_uri: Some("http://localhost/cb".to_string()),
+            state: Some("xyz".to_string()),
+            code_challenge: Some(CODE_CHALLENGE.to_string()),
+            code_challenge_method: Some("S256".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_validate_success_scope() {
+        let issuer = Oid4vciIssuer::new("http://localhost:40080");
+        let req = AuthorizationRequest {
+            scope: Some("ExampleCredential_ldp_vc".to_string()),
+            ..request()
+        };
+
+        let (code, state) = req.validate(&issuer).unwrap();
+        assert_eq!(
+            code.credential_configuration_ids,
+            vec!["ExampleCredential_ldp_vc"]
+        );
+        assert_eq!(state.unwrap(), "xyz");
+    }
+
+    #[test]
+    fn test_validate_success_authorization_details() {
+        let issuer = Oid4vciIssuer::new("http://localhost:40080");
+        let req = AuthorizationRequest {
+            authorization_details: Some(
+                r#"[{"type":"openid_credential","credential_configuration_id":"ExampleCredential_dc+sd-jwt"}]"#
+                    .to_string(),
+            ),
+            ..request()
+        };
+
+        let (code, _) = req.validate(&issuer).unwrap();
+        assert_eq!(
+            code.credential_configuration_ids,
+            vec!["ExampleCredential_dc+sd-jwt"]
+        );
+    }
+
+    #[test]
+    fn test_validate_error_plain_pkce() {
+        let issuer = Oid4vciIssuer::new("http://localhost:40080");
+        let req = AuthorizationRequest {
+            scope: Some("ExampleCredential_ldp_vc".to_string()),
+            code_challenge_method: Some("plain".to_string()),
+            ..request()
+        };
+
+        let err = req.validate(&issuer).unwrap_err();
+        assert_eq!(err.error, Oid4vciErrorCode::InvalidRequest);
+    }
+
+    #[test]
+    fn test_validate_error_unknown_scope() {
+        let issuer = Oid4vciIssuer::new("http://localhost:40080");
+        let req = AuthorizationRequest {
+            scope: Some("openid".to_string()),
+            ..request()
+        };
+
+        let err = req.validate(&issuer).unwrap_err();
+        assert_eq!(err.error, Oid4vciErrorCode::UnknownCredentialConfiguration);
+    }
+
+    #[test]
+    fn test_verify_code_verifier_success() {
+        assert!(verify_code_verifier(CODE_VERIFIER, CODE_CHALLENGE));
+        assert!(!verify_code_verifier("wrong", CODE_CHALLENGE));
+    }
+
+    #[test]
+    fn test_redirect_uri_with_success() {
+        assert_eq!(
+            redirect_uri_with("http://localhost/cb", &[("code", "a b"), ("state", "s")]),
+            "http://localhost/cb?code=a%20b&state=s"
+        );
+        assert_eq!(
+            redirect_uri_with("http://localhost/cb?x=1", &[("code", "c")]),
+            "http://localhost/cb?x=1&code=c"
+        );
+    }
+}
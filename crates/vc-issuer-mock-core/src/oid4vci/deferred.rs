@@ -0,0 +1,303 @@
+//! [Deferred credential issuance](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0.html#name-deferred-credential-endpoin).
+//!
+//! Credentials are issued when the credential request is received (so proofs and nonces are checked then),
+//! but kept back and delivered at the deferred credential endpoint once the transaction is ready.
+//! When a transaction is ready is decided by the [`Deferral`] rule in effect at the credential request.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime},
+};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use josekit::util::random_bytes;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// `interval` told to the wallet when the readiness does not depend on time.
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Whether and how long credential issuance is deferred.
+///
+/// Serialized as e.g. `{"mode": "delay", "seconds": 10}`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum Deferral {
+    /// Credentials are returned from the credential endpoint.
+    #[default]
+    Immediate,
+    /// Credentials are ready `seconds` after the credential request.
+    Delay { seconds: u64 },
+    /// Credentials are ready at the `polls`-th request to the deferred credential endpoint.
+    /// `{"mode": "polls", "polls": 1}` makes them ready at the first poll.
+    Polls { polls: u32 },
+    /// Credentials are ready only after the transaction is marked ready
+    /// (`POST /deferred_credential/{transaction_id}/ready`).
+    Manual,
+}
+
+/// Credentials returned from the credential endpoint, or the deferred credential endpoint.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CredentialDelivery {
+    Issued(Vec<Value>),
+    Deferred {
+        transaction_id: String,
+        /// Seconds the wallet should wait before polling.
+        interval: u64,
+    },
+}
+
+/// Why [`DeferredTransactions::poll`] could not deliver.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum DeferredPollError {
+    /// The transaction is unknown or already delivered.
+    InvalidTransactionId,
+    /// The transaction is for a credential configuration the access token is not authorized for.
+    NotAuthorized,
+}
+
+/// Pending deferred transactions.
+#[derive(Debug, Default)]
+pub(crate) struct DeferredTransactions {
+    /// `transaction_id` -> transaction.
+    transactions: HashMap<String, DeferredTransaction>,
+}
+
+#[derive(Debug)]
+struct DeferredTransaction {
+    credential_configuration_id: String,
+    credentials: Vec<Value>,
+    readiness: Readiness,
+}
+
+#[derive(Debug)]
+enum Readiness {
+    At(SystemTime),
+    /// Polls left until ready, including the one that delivers.
+    Polls(u32),
+    Manual {
+        ready: bool,
+    },
+}
+
+impl DeferredTransactions {
+    /// Keep `credentials` back following `deferral`.
+    ///
+    /// Returns the credentials as is for [`Deferral::Immediate`].
+    pub(crate) fn defer(
+        &mut self,
+        deferral: Deferral,
+        credential_configuration_id: &str,
+        credentials: Vec<Value>,
+    ) -> CredentialDelivery {
+        let readiness = match deferral {
+            Deferral::Immediate => return CredentialDelivery::Issued(credentials),
+            Deferral::Delay { seconds } => {
+                Readiness::At(SystemTime::now() + Duration::from_secs(seconds))
+            }
+            Deferral::Polls { polls } => Readiness::Polls(polls),
+            Deferral::Manual => Readiness::Manual { ready: false },
+        };
+
+        let transaction_id = URL_SAFE_NO_PAD.encode(random_bytes(16));
+        let interval = readiness.interval();
+        self.transactions.insert(
+            transaction_id.clone(),
+            DeferredTransaction {
+                credential_configuration_id: credential_configuration_id.to_string(),
+                credentials,
+                readiness,
+            },
+        );
+        CredentialDelivery::Deferred {
+            transaction_id,
+            interval,
+        }
+    }
+
+    /// Deliver the credentials of the transaction if ready.
+    ///
+    /// `is_authorized` tells whether the access token is authorized for a credential configuration.
+    pub(crate) fn poll(
+        &mut self,
+        transaction_id: &str,
+        is_authorized: impl Fn(&str) -> bool,
+    ) -> Result<CredentialDelivery, DeferredPollError> {
+        let transaction = self
+            .transactions
+            .get_mut(transaction_id)
+            .ok_or(DeferredPollError::InvalidTransactionId)?;
+        if !is_authorized(&transaction.credential_configuration_id) {
+            return Err(DeferredPollError::NotAuthorized);
+        }
+
+        if let Readiness::Polls(polls) = &mut transaction.readiness {
+            *polls = polls.saturating_sub(1);
+        }
+        if !transaction.readiness.is_ready() {
+            return Ok(CredentialDelivery::Deferred {
+                transaction_id: transaction_id.to_string(),
+                interval: transaction.readiness.interval(),
+            });
+        }
+
+        let transaction = self
+            .transactions
+            .remove(transaction_id)
+            .expect("transaction should exist");
+        Ok(CredentialDelivery::Issued(transaction.credentials))
+    }
+
+    /// Mark the transaction ready regardless of its readiness rule.
+    ///
+    /// Returns `false` if the transaction is unknown or already delivered.
+    pub(crate) fn mark_ready(&mut self, transaction_id: &str) -> bool {
+        match self.transactions.get_mut(transaction_id) {
+            Some(transaction) => {
+                transaction.readiness = Readiness::Manual { ready: true };
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Readiness {
+    fn is_ready(&self) -> bool {
+        match self {
+            Self::At(at) => *at <= SystemTime::now(),
+            Self::Polls(polls) => *polls == 0,
+            Self::Manual { ready } => *ready,
+        }
+    }
+
+    fn interval(&self) -> u64 {
+        match self {
+            Self::At(at) => at
+                .duration_since(SystemTime::now())
+                .map(|remaining| remaining.as_secs().max(1))
+                .unwrap_or(1),
+            Self::Polls(_) | Self::Manual { .. } => DEFAULT_INTERVAL.as_secs(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const CONFIGURATION_ID: &str = "ExampleCredential_ldp_vc";
+
+    fn transaction_id(delivery: CredentialDelivery) -> String {
+        match delivery {
+            CredentialDelivery::Deferred { transaction_id, .. } => transaction_id,
+            CredentialDelivery::Issued(_) => panic!("should be deferred"),
+        }
+    }
+
+    #[test]
+    fn test_defer_success_immediate() {
+        let mut transactions = DeferredTransactions::default();
+        let delivery = transactions.defer(Deferral::Immediate, CONFIGURATION_ID, vec![json!("vc")]);
+        assert_eq!(delivery, CredentialDelivery::Issued(vec![json!("vc")]));
+    }
+
+    #[test]
+    fn test_poll_success_polls() {
+        let mut transactions = DeferredTransactions::default();
+        let id = transaction_id(transactions.defer(
+            Deferral::Polls { polls: 2 },
+            CONFIGURATION_ID,
+            vec![json!("vc")],
+        ));
+
+        let delivery = transactions.poll(&id, |_| true).unwrap();
+        assert!(matches!(delivery, CredentialDelivery::Deferred { .. }));
+        let delivery = transactions.poll(&id, |_| true).unwrap();
+        assert_eq!(delivery, CredentialDelivery::Issued(vec![json!("vc")]));
+
+        // delivered only once
+        assert_eq!(
+            transactions.poll(&id, |_| true).unwrap_err(),
+            DeferredPollError::InvalidTransactionId
+        );
+    }
+
+    #[test]
+    fn test_poll_success_delay() {
+        let mut transactions = DeferredTransactions::default();
+        let delivery = transactions.defer(
+            Deferral::Delay { seconds: 30 },
+            CONFIGURATION_ID,
+            vec![json!("vc")],
+        );
+        let CredentialDelivery::Deferred {
+            transaction_id,
+            interval,
+        } = delivery
+        else {
+            panic!("should be deferred");
+        };
+        assert!((1..=30).contains(&interval));
+
+        let delivery = transactions.poll(&transaction_id, |_| true).unwrap();
+        assert!(matches!(delivery, CredentialDelivery::Deferred { .. }));
+    }
+
+    #[test]
+    fn test_poll_success_delay_elapsed() {
+        let mut transactions = DeferredTransactions::default();
+        let id = transaction_id(transactions.defer(
+            Deferral::Delay { seconds: 0 },
+            CONFIGURATION_ID,
+            vec![json!("vc")],
+        ));
+
+        let delivery = transactions.poll(&id, |_| true).unwrap();
+        assert_eq!(delivery, CredentialDelivery::Issued(vec![json!("vc")]));
+    }
+
+    #[test]
+    fn test_poll_success_manual() {
+        let mut transactions = DeferredTransactions::default();
+        let id = transaction_id(transactions.defer(
+            Deferral::Manual,
+            CONFIGURATION_ID,
+            vec![json!("vc")],
+        ));
+
+        let delivery = transactions.poll(&id, |_| true).unwrap();
+        assert!(matches!(delivery, CredentialDelivery::Deferred { .. }));
+
+        assert!(transactions.mark_ready(&id));
+        let delivery = transactions.poll(&id, |_| true).unwrap();
+        assert_eq!(delivery, CredentialDelivery::Issued(vec![json!("vc")]));
+        assert!(!transactions.mark_ready(&id));
+    }
+
+    #[test]
+    fn test_poll_error_not_authorized() {
+        let mut transactions = DeferredTransactions::default();
+        let id = transaction_id(transactions.defer(
+            Deferral::Polls { polls: 1 },
+            CONFIGURATION_ID,
+            vec![json!("vc")],
+        ));
+
+        assert_eq!(
+            transactions.poll(&id, |_| false).unwrap_err(),
+            DeferredPollError::NotAuthorized
+        );
+    }
+
+    #[test]
+    fn test_deferral_deserialize_success() {
+        let deferral: Deferral =
+            serde_json::from_value(json!({ "mode": "delay", "seconds": 10 })).unwrap();
+        assert_eq!(deferral, Deferral::Delay { seconds: 10 });
+        let deferral: Deferral = serde_json::from_value(json!({ "mode": "manual" })).unwrap();
+        assert_eq!(deferral, Deferral::Manual);
+    }
+}
@@ -0,0 +1,335 @@
+//! Grants of the built-in authorization server.
+//!
+//! A [pre-authorized code](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0.html#name-pre-authorized-code-flow)
+//! is handed out in a credential offer, and exchanged for an access token at the token endpoint.
+//! The access token authorizes the credential configurations in the offer.
+//!
+//! In the [authorization code flow](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0.html#name-authorization-code-flow),
+//! an authorization code is issued at the authorization endpoint (optionally after a pushed authorization request),
+//! and exchanged for an access token together with the PKCE code verifier.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime},
+};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use josekit::util::random_bytes;
+use serde::{Deserialize, Serialize};
+
+use crate::oid4vci::{authorization::AuthorizationRequest, CredentialOffer};
+
+/// How long a pre-authorized code (and its credential offer) is valid.
+pub const PRE_AUTHORIZED_CODE_LIFETIME: Duration = Duration::from_secs(600);
+
+/// How long an authorization code is valid.
+pub const AUTHORIZATION_CODE_LIFETIME: Duration = Duration::from_secs(60);
+
+/// How long a `request_uri` of a pushed authorization request is valid.
+pub const PUSHED_AUTHORIZATION_REQUEST_LIFETIME: Duration = Duration::from_secs(60);
+
+/// `grant_type` of the pre-authorized code flow.
+pub const PRE_AUTHORIZED_CODE_GRANT_TYPE: &str =
+    "urn:ietf:params:oauth:grant-type:pre-authorized_code";
+
+/// `grant_type` of the authorization code flow.
+pub const AUTHORIZATION_CODE_GRANT_TYPE: &str = "authorization_code";
+
+/// Prefix of `request_uri` returned for [pushed authorization requests](https://datatracker.ietf.org/doc/html/rfc9126).
+const REQUEST_URI_PREFIX: &str = "urn:ietf:params:oauth:request_uri:";
+
+/// [Transaction Code](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0.html#name-credential-offer-parameters)
+/// the wallet must send together with the pre-authorized code.
+///
+/// The code itself is sent to the user out of band. Only how to input it is written in the credential offer.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct TxCode {
+    #[serde(default)]
+    pub input_mode: TxCodeInputMode,
+    /// Length of the code. 6 if not specified.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// `input_mode` of [`TxCode`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TxCodeInputMode {
+    #[default]
+    Numeric,
+    Text,
+}
+
+/// Credential offers, pre-authorized codes, pushed authorization requests and authorization codes issued so far.
+///
+/// Access tokens are self-contained JWTs and not kept here.
+#[derive(Debug, Default)]
+pub(crate) struct Grants {
+    /// Credential offers passed by reference, with expiry.
+    credential_offers: HashMap<String, (CredentialOffer, SystemTime)>,
+    pre_authorized_codes: HashMap<String, PreAuthorizedCode>,
+    /// `request_uri` -> pushed authorization request, with expiry.
+    pushed_authorization_requests: HashMap<String, (AuthorizationRequest, SystemTime)>,
+    /// Authorization codes, with expiry.
+    authorization_codes: HashMap<String, (AuthorizationCode, SystemTime)>,
+}
+
+#[derive(Clone, Debug)]
+struct PreAuthorizedCode {
+    credential_configuration_ids: Vec<String>,
+    tx_code: Option<String>,
+    expiry: SystemTime,
+}
+
+/// An authorization code and what the user has approved with it.
+#[derive(Clone, Debug)]
+pub(crate) struct AuthorizationCode {
+    pub(crate) client_id: String,
+    pub(crate) redirect_uri: String,
+    /// PKCE `code_challenge` (`S256`).
+    pub(crate) code_challenge: String,
+    pub(crate) credential_configuration_ids: Vec<String>,
+}
+
+/// Why a pre-authorized code was not exchanged for an access token.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum PreAuthorizedCodeError {
+    /// Unknown, already used or expired.
+    InvalidCode,
+    /// `tx_code` is required but missing, or does not match.
+    InvalidTxCode,
+}
+
+impl TxCode {
+    const DEFAULT_LENGTH: usize = 6;
+
+    /// Generate a transaction code following this input mode and length.
+    pub(crate) fn generate(&self) -> String {
+        const DIGITS: &[u8] = b"0123456789";
+        const ALPHANUMERICS: &[u8] =
+            b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+        let chars = match self.input_mode {
+            TxCodeInputMode::Numeric => DIGITS,
+            TxCodeInputMode::Text => ALPHANUMERICS,
+        };
+        random_bytes(self.length.unwrap_or(Self::DEFAULT_LENGTH))
+            .into_iter()
+            .map(|b| chars[b as usize % chars.len()] as char)
+            .collect()
+    }
+}
+
+impl Grants {
+    /// Keep a credential offer to be passed by reference. Returns its identifier.
+    pub(crate) fn put_credential_offer(&mut self, offer: CredentialOffer) -> String {
+        let id = random_token();
+        let now = SystemTime::now();
+
+        self.credential_offers
+            .retain(|_, (_, expiry)| *expiry > now);
+        self.credential_offers
+            .insert(id.clone(), (offer, now + PRE_AUTHORIZED_CODE_LIFETIME));
+        id
+    }
+
+    pub(crate) fn credential_offer(&self, id: &str) -> Option<CredentialOffer> {
+        self.credential_offers
+            .get(id)
+            .filter(|(_, expiry)| *expiry > SystemTime::now())
+            .map(|(offer, _)| offer.clone())
+    }
+
+    /// Issue a pre-authorized code for the credential configurations.
+    pub(crate) fn new_pre_authorized_code(
+        &mut self,
+        credential_configuration_ids: Vec<String>,
+        tx_code: Option<String>,
+    ) -> String {
+        let code = random_token();
+        let now = SystemTime::now();
+
+        self.pre_authorized_codes.retain(|_, c| c.expiry > now);
+        self.pre_authorized_codes.insert(
+            code.clone(),
+            PreAuthorizedCode {
+                credential_configuration_ids,
+                tx_code,
+                expiry: now + PRE_AUTHORIZED_CODE_LIFETIME,
+            },
+        );
+        code
+    }
+
+    /// Redeem a pre-authorized code, and return the credential configurations it authorizes.
+    /// Each pre-authorized code can be used only once.
+    ///
+    /// A wrong `tx_code` does not consume the code, so that the user can retry.
+    pub(crate) fn redeem_pre_authorized_code(
+        &mut self,
+        code: &str,
+        tx_code: Option<&str>,
+    ) -> Result<Vec<String>, PreAuthorizedCodeError> {
+        let pre_authorized_code = self
+            .pre_authorized_codes
+            .get(code)
+            .filter(|c| c.expiry > SystemTime::now())
+            .ok_or(PreAuthorizedCodeError::InvalidCode)?;
+        if pre_authorized_code.tx_code.as_deref() != tx_code {
+            return Err(PreAuthorizedCodeError::InvalidTxCode);
+        }
+
+        let pre_authorized_code = self
+            .pre_authorized_codes
+            .remove(code)
+            .expect("already checked to exist");
+        Ok(pre_authorized_code.credential_configuration_ids)
+    }
+
+    /// Keep a pushed authorization request. Returns its `request_uri`.
+    pub(crate) fn put_pushed_authorization_request(&mut self, req: AuthorizationRequest) -> String {
+        let request_uri = format!("{}{}", REQUEST_URI_PREFIX, random_token());
+        let now = SystemTime::now();
+
+        self.pushed_authorization_requests
+            .retain(|_, (_, expiry)| *expiry > now);
+        self.pushed_authorization_requests.insert(
+            request_uri.clone(),
+            (req, now + PUSHED_AUTHORIZATION_REQUEST_LIFETIME),
+        );
+        request_uri
+    }
+
+    /// Take a pushed authorization request. Each `request_uri` can be used only once.
+    pub(crate) fn take_pushed_authorization_request(
+        &mut self,
+        request_uri: &str,
+    ) -> Option<AuthorizationRequest> {
+        self.pushed_authorization_requests
+            .remove(request_uri)
+            .filter(|(_, expiry)| *expiry > SystemTime::now())
+            .map(|(req, _)| req)
+    }
+
+    /// Issue an authorization code.
+    pub(crate) fn new_authorization_code(
+        &mut self,
+        authorization_code: AuthorizationCode,
+    ) -> String {
+        let code = random_token();
+        let now = SystemTime::now();
+
+        self.authorization_codes
+            .retain(|_, (_, expiry)| *expiry > now);
+        self.authorization_codes.insert(
+            code.clone(),
+            (authorization_code, now + AUTHORIZATION_CODE_LIFETIME),
+        );
+        code
+    }
+
+    /// Take an authorization code. Each authorization code can be used only once, even if the token request fails.
+    pub(crate) fn take_authorization_code(&mut self, code: &str) -> Option<AuthorizationCode> {
+        self.authorization_codes
+            .remove(code)
+            .filter(|(_, expiry)| *expiry > SystemTime::now())
+            .map(|(authorization_code, _)| authorization_code)
+    }
+}
+
+fn random_token() -> String {
+    URL_SAFE_NO_PAD.encode(random_bytes(32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tx_code_generate_success() {
+        let tx_code = TxCode::default().generate();
+        assert_eq!(tx_code.len(), 6);
+        assert!(tx_code.chars().all(|c| c.is_ascii_digit()));
+
+        let tx_code = TxCode {
+            input_mode: TxCodeInputMode::Text,
+            length: Some(8),
+            description: None,
+        }
+        .generate();
+        assert_eq!(tx_code.len(), 8);
+        assert!(tx_code.chars().all(|c| c.is_ascii_alphanumeric()));
+    }
+
+    #[test]
+    fn test_redeem_pre_authorized_code_success() {
+        let mut grants = Grants::default();
+        let code =
+            grants.new_pre_authorized_code(vec!["ExampleCredential_ldp_vc".to_string()], None);
+
+        let ids = grants.redeem_pre_authorized_code(&code, None).unwrap();
+        assert_eq!(ids, vec!["ExampleCredential_ldp_vc"]);
+
+        // single use
+        assert_eq!(
+            grants.redeem_pre_authorized_code(&code, None),
+            Err(PreAuthorizedCodeError::InvalidCode)
+        );
+    }
+
+    #[test]
+    fn test_redeem_pre_authorized_code_error_tx_code() {
+        let mut grants = Grants::default();
+        let code = grants.new_pre_authorized_code(
+            vec!["ExampleCredential_ldp_vc".to_string()],
+            Some("123456".to_string()),
+        );
+
+        assert_eq!(
+            grants.redeem_pre_authorized_code(&code, None),
+            Err(PreAuthorizedCodeError::InvalidTxCode)
+        );
+        assert_eq!(
+            grants.redeem_pre_authorized_code(&code, Some("000000")),
+            Err(PreAuthorizedCodeError::InvalidTxCode)
+        );
+        // still usable with the right one
+        assert!(grants
+            .redeem_pre_authorized_code(&code, Some("123456"))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_take_authorization_code_success() {
+        let mut grants = Grants::default();
+        let code = grants.new_authorization_code(AuthorizationCode {
+            client_id: "wallet".to_string(),
+            redirect_uri: "http://localhost/cb".to_string(),
+            code_challenge: "challenge".to_string(),
+            credential_configuration_ids: vec!["ExampleCredential_ldp_vc".to_string()],
+        });
+
+        assert_eq!(
+            grants.take_authorization_code(&code).unwrap().client_id,
+            "wallet"
+        );
+        // single use
+        assert!(grants.take_authorization_code(&code).is_none());
+    }
+
+    #[test]
+    fn test_redeem_pre_authorized_code_error_expired() {
+        let mut grants = Grants::default();
+        let code =
+            grants.new_pre_authorized_code(vec!["ExampleCredential_ldp_vc".to_string()], None);
+        grants.pre_authorized_codes.get_mut(&code).unwrap().expiry =
+            SystemTime::now() - Duration::from_secs(1);
+
+        assert_eq!(
+            grants.redeem_pre_authorized_code(&code, None),
+            Err(PreAuthorizedCodeError::InvalidCode)
+        );
+    }
+}
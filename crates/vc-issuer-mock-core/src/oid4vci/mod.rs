@@ -0,0 +1,761 @@
+//! [OpenID for Verifiable Credential Issuance 1.0](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0.html) (OID4VCI).
+//!
+//! [`Oid4vciIssuer`] holds the Credential Issuer configuration and the `c_nonce`s handed out to wallets.
+//! It also acts as a built-in authorization server for both the pre-authorized code flow and
+//! the authorization code flow, so that no external authorization server is needed.
+//! Endpoints are implemented in [`crate::endpoints::oid4vci`].
+
+pub(crate) mod access_token;
+pub mod authorization;
+pub mod deferred;
+pub mod grants;
+pub(crate) mod proof;
+
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, SystemTime},
+};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use josekit::util::random_bytes;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    endpoints::oid4vci::res::oid4vci_error::{Oid4vciError, Oid4vciErrorCode},
+    oid4vci::{
+        access_token::{sign_access_token, verify_access_token, AccessToken},
+        authorization::{
+            redirect_uri_with, verify_code_verifier, AuthorizationRequest, ConsentDecision,
+        },
+        deferred::{CredentialDelivery, Deferral, DeferredPollError, DeferredTransactions},
+        grants::{
+            Grants, PreAuthorizedCodeError, TxCode, AUTHORIZATION_CODE_GRANT_TYPE,
+            PRE_AUTHORIZED_CODE_GRANT_TYPE, PUSHED_AUTHORIZATION_REQUEST_LIFETIME,
+        },
+    },
+    IssuerKeys,
+};
+
+/// How long a `c_nonce` is valid.
+pub const NONCE_LIFETIME: Duration = Duration::from_secs(300);
+
+/// Maximum number of credentials issued for one credential request, unless changed by
+/// [`Oid4vciIssuer::with_batch_size`].
+pub const DEFAULT_BATCH_SIZE: usize = 10;
+
+/// JWS algorithms accepted in [JWT proofs](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0.html#name-jwt-proof-type).
+pub const PROOF_SIGNING_ALG_VALUES: [&str; 5] = ["ES256", "ES384", "ES256K", "EdDSA", "RS256"];
+
+/// An OID4VCI Credential Issuer.
+///
+/// # Example
+///
+/// ```
+/// use vc_issuer_mock_core::oid4vci::{CredentialConfiguration, CredentialFormat, Oid4vciIssuer};
+///
+/// let issuer = Oid4vciIssuer::new("http://localhost:40080").with_configuration(
+///     "UniversityDegree_ldp_vc",
+///     CredentialConfiguration::new(
+///         CredentialFormat::LdpVc,
+///         "UniversityDegreeCredential",
+///         serde_json::json!({ "degree": "Bachelor of Science" }),
+///     ),
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct Oid4vciIssuer {
+    credential_issuer: String,
+    configurations: BTreeMap<String, CredentialConfiguration>,
+    /// `c_nonce` -> expiry.
+    nonces: Arc<Mutex<HashMap<String, SystemTime>>>,
+    grants: Arc<Mutex<Grants>>,
+    /// Decisions of the mock user for upcoming authorization requests.
+    consent_script: Arc<Mutex<VecDeque<ConsentDecision>>>,
+    /// Maximum number of proofs (and credentials) in a credential request.
+    batch_size: usize,
+    /// Deferral rule applied to upcoming credential requests.
+    deferral: Arc<Mutex<Deferral>>,
+    deferred: Arc<Mutex<DeferredTransactions>>,
+}
+
+/// A credential offered by the issuer (an entry of `credential_configurations_supported`).
+#[derive(Clone, Debug)]
+pub struct CredentialConfiguration {
+    pub format: CredentialFormat,
+    /// Type of the credential. Used as the last `type` for `ldp_vc`, and as `vct` for `dc+sd-jwt`.
+    pub credential_type: String,
+    /// Claims about the holder. The holder's DID is added as `credentialSubject.id` for `ldp_vc`.
+    pub claims: Map<String, Value>,
+}
+
+/// [Credential Format](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0.html#name-credential-format-profiles).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub enum CredentialFormat {
+    /// VCDM 2.0 credential secured with a Data Integrity proof.
+    #[serde(rename = "ldp_vc")]
+    LdpVc,
+    /// SD-JWT VC bound to the holder key.
+    #[serde(rename = "dc+sd-jwt")]
+    DcSdJwt,
+}
+
+/// [Credential Offer](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0.html#name-credential-offer-parameters).
+#[derive(Clone, Debug, Serialize)]
+pub struct CredentialOffer {
+    pub credential_issuer: String,
+    pub credential_configuration_ids: Vec<String>,
+    pub grants: CredentialOfferGrants,
+}
+
+/// `grants` of [`CredentialOffer`].
+#[derive(Clone, Debug, Serialize)]
+pub struct CredentialOfferGrants {
+    #[serde(rename = "urn:ietf:params:oauth:grant-type:pre-authorized_code")]
+    pub pre_authorized_code: PreAuthorizedCodeGrant,
+}
+
+/// Pre-authorized code grant in [`CredentialOfferGrants`].
+#[derive(Clone, Debug, Serialize)]
+pub struct PreAuthorizedCodeGrant {
+    #[serde(rename = "pre-authorized_code")]
+    pub pre_authorized_code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_code: Option<TxCode>,
+}
+
+/// A credential offer created by [`Oid4vciIssuer::create_credential_offer`].
+#[derive(Clone, Debug)]
+pub struct CreatedCredentialOffer {
+    pub credential_offer: CredentialOffer,
+    /// URL where the credential offer is passed by reference.
+    pub credential_offer_uri: String,
+    /// Transaction code to be sent to the user out of band.
+    pub tx_code: Option<String>,
+}
+
+impl Oid4vciIssuer {
+    /// Create a Credential Issuer identified by `credential_issuer` (the base URL of the endpoints).
+    ///
+    /// It offers `ExampleCredential` both in `ldp_vc` (`ExampleCredential_ldp_vc`) and in `dc+sd-jwt`
+    /// (`ExampleCredential_dc+sd-jwt`) formats by default.
+    pub fn new(credential_issuer: impl Into<String>) -> Self {
+        let claims = json!({ "given_name": "Taro", "family_name": "Yamada" });
+        Self {
+            credential_issuer: credential_issuer.into().trim_end_matches('/').to_string(),
+            configurations: BTreeMap::new(),
+            nonces: Arc::new(Mutex::new(HashMap::new())),
+            grants: Arc::new(Mutex::new(Grants::default())),
+            consent_script: Arc::new(Mutex::new(VecDeque::new())),
+            batch_size: DEFAULT_BATCH_SIZE,
+            deferral: Arc::new(Mutex::new(Deferral::default())),
+            deferred: Arc::new(Mutex::new(DeferredTransactions::default())),
+        }
+        .with_configuration(
+            "ExampleCredential_ldp_vc",
+            CredentialConfiguration::new(
+                CredentialFormat::LdpVc,
+                "ExampleCredential",
+                claims.clone(),
+            ),
+        )
+        .with_configuration(
+            "ExampleCredential_dc+sd-jwt",
+            CredentialConfiguration::new(CredentialFormat::DcSdJwt, "ExampleCredential", claims),
+        )
+    }
+
+    /// Add (or replace) a credential configuration.
+    pub fn with_configuration(
+        mut self,
+        id: impl Into<String>,
+        configuration: CredentialConfiguration,
+    ) -> Self {
+        self.configurations.insert(id.into(), configuration);
+        self
+    }
+
+    /// Set the maximum number of credentials issued for one credential request.
+    ///
+    /// # Panics
+    ///
+    /// If `batch_size` is 0.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        assert!(batch_size > 0, "batch_size must be positive");
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Set the deferral rule applied to credential requests.
+    pub fn with_deferral(self, deferral: Deferral) -> Self {
+        self.set_deferral(deferral);
+        self
+    }
+
+    /// Change the deferral rule applied to upcoming credential requests.
+    ///
+    /// Pending transactions keep the rule in effect when they were created.
+    pub fn set_deferral(&self, deferral: Deferral) {
+        *self.lock_deferral() = deferral;
+    }
+
+    /// Credential Issuer Identifier.
+    pub fn credential_issuer(&self) -> &str {
+        &self.credential_issuer
+    }
+
+    pub(crate) fn configuration(&self, id: &str) -> Option<&CredentialConfiguration> {
+        self.configurations.get(id)
+    }
+
+    pub(crate) fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    /// [Credential Issuer Metadata](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0.html#name-credential-issuer-metadata-p).
+    pub fn metadata(&self) -> Value {
+        let configurations = self
+            .configurations
+            .iter()
+            .map(|(id, configuration)| (id.clone(), configuration.metadata(id)))
+            .collect::<Map<_, _>>();
+
+        let mut metadata = json!({
+            "credential_issuer": self.credential_issuer,
+            "credential_endpoint": format!("{}/credential", self.credential_issuer),
+            "nonce_endpoint": format!("{}/nonce", self.credential_issuer),
+            "deferred_credential_endpoint": format!("{}/deferred_credential", self.credential_issuer),
+            "credential_configurations_supported": configurations,
+        });
+        // must be absent if batch issuance is not supported
+        if self.batch_size > 1 {
+            metadata["batch_credential_issuance"] = json!({ "batch_size": self.batch_size });
+        }
+        metadata
+    }
+
+    /// [OAuth 2.0 Authorization Server Metadata](https://datatracker.ietf.org/doc/html/rfc8414)
+    /// of the built-in authorization server.
+    pub fn authorization_server_metadata(&self) -> Value {
+        json!({
+            "issuer": self.credential_issuer,
+            "authorization_endpoint": format!("{}/authorize", self.credential_issuer),
+            "pushed_authorization_request_endpoint": format!("{}/par", self.credential_issuer),
+            "token_endpoint": format!("{}/token", self.credential_issuer),
+            "jwks_uri": format!("{}/jwks", self.credential_issuer),
+            "scopes_supported": self.configurations.keys().collect::<Vec<_>>(),
+            "response_types_supported": ["code"],
+            "grant_types_supported": [AUTHORIZATION_CODE_GRANT_TYPE, PRE_AUTHORIZED_CODE_GRANT_TYPE],
+            "code_challenge_methods_supported": ["S256"],
+            "token_endpoint_auth_methods_supported": ["none"],
+            "authorization_details_types_supported": [access_token::OPENID_CREDENTIAL],
+            "authorization_response_iss_parameter_supported": true,
+            "pre-authorized_grant_anonymous_access_supported": true,
+        })
+    }
+
+    /// Script how the mock user answers upcoming authorization requests, in order.
+    ///
+    /// Authorization requests are approved when no decision is left.
+    pub fn script_consent(&self, decisions: impl IntoIterator<Item = ConsentDecision>) {
+        self.lock_consent_script().extend(decisions);
+    }
+
+    /// Validate and keep a [pushed authorization request](https://datatracker.ietf.org/doc/html/rfc9126).
+    ///
+    /// Returns `request_uri` and its lifetime in seconds.
+    pub(crate) fn push_authorization_request(
+        &self,
+        req: AuthorizationRequest,
+    ) -> Result<(String, u64), Oid4vciError> {
+        if req.request_uri.is_some() {
+            return Err(Oid4vciError::bad_request(
+                Oid4vciErrorCode::InvalidRequest,
+                "`request_uri` must not be pushed",
+            ));
+        }
+        req.validate(self)?;
+
+        let request_uri = self.lock_grants().put_pushed_authorization_request(req);
+        Ok((request_uri, PUSHED_AUTHORIZATION_REQUEST_LIFETIME.as_secs()))
+    }
+
+    /// Handle an authorization request, and return the URL to redirect the user agent to.
+    ///
+    /// The redirect URL has either `code` or `error=access_denied` depending on the consent.
+    pub(crate) fn authorize(&self, req: AuthorizationRequest) -> Result<String, Oid4vciError> {
+        let req = match &req.request_uri {
+            Some(request_uri) => {
+                let pushed = self
+                    .lock_grants()
+                    .take_pushed_authorization_request(request_uri)
+                    .ok_or_else(|| {
+                        Oid4vciError::bad_request(
+                            Oid4vciErrorCode::InvalidRequest,
+                            "`request_uri` is unknown, already used or expired",
+                        )
+                    })?;
+                if req.client_id.is_some() && req.client_id != pushed.client_id {
+                    return Err(Oid4vciError::bad_request(
+                        Oid4vciErrorCode::InvalidRequest,
+                        "`client_id` does not match the pushed authorization request",
+                    ));
+                }
+                pushed
+            }
+            None => req,
+        };
+        let (authorization_code, state) = req.validate(self)?;
+
+        let redirect_uri = authorization_code.redirect_uri.clone();
+        let decision = self.lock_consent_script().pop_front();
+        let code = match decision.unwrap_or(ConsentDecision::Approve) {
+            ConsentDecision::Approve => Some(
+                self.lock_grants()
+                    .new_authorization_code(authorization_code),
+            ),
+            ConsentDecision::Deny => None,
+        };
+
+        let mut params = match &code {
+            Some(code) => vec![("code", code.as_str())],
+            None => vec![("error", "access_denied")],
+        };
+        if let Some(state) = &state {
+            params.push(("state", state));
+        }
+        // <https://datatracker.ietf.org/doc/html/rfc9207>
+        params.push(("iss", &self.credential_issuer));
+
+        Ok(redirect_uri_with(&redirect_uri, &params))
+    }
+
+    /// Exchange an authorization code for an access token.
+    pub(crate) fn exchange_authorization_code(
+        &self,
+        issuer_keys: &IssuerKeys,
+        code: &str,
+        client_id: Option<&str>,
+        redirect_uri: Option<&str>,
+        code_verifier: Option<&str>,
+    ) -> Result<String, Oid4vciError> {
+        let invalid_grant = |error_description: &str| {
+            Oid4vciError::bad_request(Oid4vciErrorCode::InvalidGrant, error_description)
+        };
+
+        let authorization_code = self
+            .lock_grants()
+            .take_authorization_code(code)
+            .ok_or_else(|| invalid_grant("`code` is unknown, already used or expired"))?;
+        if client_id != Some(authorization_code.client_id.as_str()) {
+            return Err(invalid_grant("`client_id` does not match"));
+        }
+        if redirect_uri != Some(authorization_code.redirect_uri.as_str()) {
+            return Err(invalid_grant("`redirect_uri` does not match"));
+        }
+        if !code_verifier.is_some_and(|code_verifier| {
+            verify_code_verifier(code_verifier, &authorization_code.code_challenge)
+        }) {
+            return Err(invalid_grant("`code_verifier` is missing or wrong"));
+        }
+
+        let token = sign_access_token(
+            issuer_keys,
+            &self.credential_issuer,
+            Some(&authorization_code.client_id),
+            &authorization_code.credential_configuration_ids,
+        )?;
+        Ok(token)
+    }
+
+    /// Create a credential offer of the credential configurations with a pre-authorized code.
+    ///
+    /// If `tx_code` is given, a transaction code is generated and required at the token endpoint.
+    pub fn create_credential_offer(
+        &self,
+        credential_configuration_ids: Vec<String>,
+        tx_code: Option<TxCode>,
+    ) -> Result<CreatedCredentialOffer, Oid4vciError> {
+        if credential_configuration_ids.is_empty() {
+            return Err(Oid4vciError::bad_request(
+                Oid4vciErrorCode::InvalidRequest,
+                "`credential_configuration_ids` must not be empty",
+            ));
+        }
+        if let Some(id) = credential_configuration_ids
+            .iter()
+            .find(|id| self.configuration(id).is_none())
+        {
+            return Err(Oid4vciError::bad_request(
+                Oid4vciErrorCode::UnknownCredentialConfiguration,
+                format!("unknown credential configuration: {}", id),
+            ));
+        }
+
+        let tx_code_value = tx_code.as_ref().map(TxCode::generate);
+
+        let mut grants = self.lock_grants();
+        let pre_authorized_code = grants
+            .new_pre_authorized_code(credential_configuration_ids.clone(), tx_code_value.clone());
+        let credential_offer = CredentialOffer {
+            credential_issuer: self.credential_issuer.clone(),
+            credential_configuration_ids,
+            grants: CredentialOfferGrants {
+                pre_authorized_code: PreAuthorizedCodeGrant {
+                    pre_authorized_code,
+                    tx_code,
+                },
+            },
+        };
+        let id = grants.put_credential_offer(credential_offer.clone());
+
+        Ok(CreatedCredentialOffer {
+            credential_offer,
+            credential_offer_uri: format!("{}/credential-offers/{}", self.credential_issuer, id),
+            tx_code: tx_code_value,
+        })
+    }
+
+    /// A credential offer passed by reference.
+    pub(crate) fn credential_offer(&self, id: &str) -> Option<CredentialOffer> {
+        self.lock_grants().credential_offer(id)
+    }
+
+    /// Exchange a pre-authorized code for an access token.
+    pub(crate) fn exchange_pre_authorized_code(
+        &self,
+        issuer_keys: &IssuerKeys,
+        code: &str,
+        tx_code: Option<&str>,
+    ) -> Result<String, Oid4vciError> {
+        let credential_configuration_ids = self
+            .lock_grants()
+            .redeem_pre_authorized_code(code, tx_code)
+            .map_err(|e| {
+                let error_description = match e {
+                    PreAuthorizedCodeError::InvalidCode => {
+                        "`pre-authorized_code` is unknown, already used or expired"
+                    }
+                    PreAuthorizedCodeError::InvalidTxCode => "`tx_code` is missing or wrong",
+                };
+                Oid4vciError::bad_request(Oid4vciErrorCode::InvalidGrant, error_description)
+            })?;
+
+        let token = sign_access_token(
+            issuer_keys,
+            &self.credential_issuer,
+            None,
+            &credential_configuration_ids,
+        )?;
+        Ok(token)
+    }
+
+    /// Verify an access token sent to the credential endpoint.
+    pub(crate) fn access_token(
+        &self,
+        issuer_keys: &IssuerKeys,
+        token: &str,
+    ) -> Result<AccessToken, Oid4vciError> {
+        verify_access_token(issuer_keys, &self.credential_issuer, token)
+            .map_err(|e| Oid4vciError::invalid_token(format!("invalid access token: {}", e)))
+    }
+
+    /// Deliver credentials issued for a credential request, or defer them following the deferral rule.
+    pub(crate) fn deliver(
+        &self,
+        credential_configuration_id: &str,
+        credentials: Vec<Value>,
+    ) -> CredentialDelivery {
+        let deferral = *self.lock_deferral();
+        self.lock_deferred()
+            .defer(deferral, credential_configuration_id, credentials)
+    }
+
+    /// Deliver deferred credentials if ready.
+    pub(crate) fn poll_deferred(
+        &self,
+        access_token: &AccessToken,
+        transaction_id: &str,
+    ) -> Result<CredentialDelivery, Oid4vciError> {
+        self.lock_deferred()
+            .poll(transaction_id, |id| {
+                access_token
+                    .credential_configuration_ids
+                    .iter()
+                    .any(|authorized| authorized == id)
+            })
+            .map_err(|e| match e {
+                DeferredPollError::InvalidTransactionId => Oid4vciError::bad_request(
+                    Oid4vciErrorCode::InvalidTransactionId,
+                    "`transaction_id` is unknown or already delivered",
+                ),
+                DeferredPollError::NotAuthorized => Oid4vciError::insufficient_scope(
+                    "access token is not authorized for the deferred credential",
+                ),
+            })
+    }
+
+    /// Make a deferred transaction ready, regardless of the deferral rule.
+    ///
+    /// Returns `false` if the transaction is unknown or already delivered.
+    pub fn mark_deferred_ready(&self, transaction_id: &str) -> bool {
+        self.lock_deferred().mark_ready(transaction_id)
+    }
+
+    /// Hand out a fresh `c_nonce`.
+    pub(crate) fn new_nonce(&self) -> String {
+        let nonce = URL_SAFE_NO_PAD.encode(random_bytes(16));
+
+        let now = SystemTime::now();
+        let mut nonces = self.lock_nonces();
+        nonces.retain(|_, expiry| *expiry > now);
+        nonces.insert(nonce.clone(), now + NONCE_LIFETIME);
+
+        nonce
+    }
+
+    /// Check that `nonce` has been handed out and not expired. Each `c_nonce` can be used only once.
+    pub(crate) fn consume_nonce(&self, nonce: &str) -> bool {
+        self.lock_nonces()
+            .remove(nonce)
+            .is_some_and(|expiry| expiry > SystemTime::now())
+    }
+
+    fn lock_grants(&self) -> MutexGuard<'_, Grants> {
+        self.grants.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_consent_script(&self) -> MutexGuard<'_, VecDeque<ConsentDecision>> {
+        self.consent_script
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_deferral(&self) -> MutexGuard<'_, Deferral> {
+        self.deferral.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_deferred(&self) -> MutexGuard<'_, DeferredTransactions> {
+        self.deferred.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_nonces(&self) -> MutexGuard<'_, HashMap<String, SystemTime>> {
+        self.nonces.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl CreatedCredentialOffer {
+    /// `openid-credential-offer://` URI passing the credential offer by value.
+    pub fn offer_by_value(&self) -> String {
+        let credential_offer = serde_json::to_string(&self.credential_offer)
+            .expect("CredentialOffer should be serialized into JSON");
+        format!(
+            "openid-credential-offer://?credential_offer={}",
+            utf8_percent_encode(&credential_offer, NON_ALPHANUMERIC)
+        )
+    }
+
+    /// `openid-credential-offer://` URI passing the credential offer by reference.
+    pub fn offer_by_reference(&self) -> String {
+        format!(
+            "openid-credential-offer://?credential_offer_uri={}",
+            utf8_percent_encode(&self.credential_offer_uri, NON_ALPHANUMERIC)
+        )
+    }
+}
+
+impl CredentialConfiguration {
+    /// # Panics
+    ///
+    /// If `claims` is not a JSON object.
+    pub fn new(
+        format: CredentialFormat,
+        credential_type: impl Into<String>,
+        claims: Value,
+    ) -> Self {
+        let Value::Object(claims) = claims else {
+            panic!("claims must be a JSON object: {}", claims);
+        };
+        Self {
+            format,
+            credential_type: credential_type.into(),
+            claims,
+        }
+    }
+
+    /// `@context` of `ldp_vc` credentials.
+    pub(crate) fn contexts(&self) -> Vec<&'static str> {
+        vec![
+            "https://www.w3.org/ns/credentials/v2",
+            "https://www.w3.org/ns/credentials/examples/v2",
+        ]
+    }
+
+    /// `type` of `ldp_vc` credentials.
+    pub(crate) fn types(&self) -> Vec<&str> {
+        vec!["VerifiableCredential", &self.credential_type]
+    }
+
+    fn metadata(&self, id: &str) -> Value {
+        let mut metadata = json!({
+            "format": self.format,
+            "scope": id,
+            "proof_types_supported": {
+                "jwt": { "proof_signing_alg_values_supported": PROOF_SIGNING_ALG_VALUES },
+            },
+        });
+
+        match self.format {
+            CredentialFormat::LdpVc => {
+                metadata["cryptographic_binding_methods_supported"] = json!(["did:key", "did:jwk"]);
+                metadata["credential_definition"] = json!({
+                    "@context": self.contexts(),
+                    "type": self.types(),
+                });
+            }
+            CredentialFormat::DcSdJwt => {
+                metadata["cryptographic_binding_methods_supported"] = json!(["jwk"]);
+                metadata["vct"] = json!(self.credential_type);
+            }
+        }
+
+        metadata
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metadata_success() {
+        let issuer = Oid4vciIssuer::new("http://localhost:40080/");
+        let metadata = issuer.metadata();
+
+        assert_eq!(metadata["credential_issuer"], "http://localhost:40080");
+        assert_eq!(
+            metadata["credential_endpoint"],
+            "http://localhost:40080/credential"
+        );
+        assert_eq!(metadata["nonce_endpoint"], "http://localhost:40080/nonce");
+        assert_eq!(
+            metadata["deferred_credential_endpoint"],
+            "http://localhost:40080/deferred_credential"
+        );
+        assert_eq!(
+            metadata["batch_credential_issuance"]["batch_size"],
+            DEFAULT_BATCH_SIZE
+        );
+
+        let ldp_vc = &metadata["credential_configurations_supported"]["ExampleCredential_ldp_vc"];
+        assert_eq!(ldp_vc["format"], "ldp_vc");
+        assert_eq!(
+            ldp_vc["credential_definition"]["type"],
+            json!(["VerifiableCredential", "ExampleCredential"])
+        );
+
+        let sd_jwt =
+            &metadata["credential_configurations_supported"]["ExampleCredential_dc+sd-jwt"];
+        assert_eq!(sd_jwt["format"], "dc+sd-jwt");
+        assert_eq!(sd_jwt["vct"], "ExampleCredential");
+    }
+
+    #[test]
+    fn test_metadata_success_no_batch() {
+        let issuer = Oid4vciIssuer::new("http://localhost:40080").with_batch_size(1);
+        let metadata = issuer.metadata();
+
+        assert!(metadata.get("batch_credential_issuance").is_none());
+    }
+
+    #[test]
+    fn test_create_credential_offer_success() {
+        let issuer = Oid4vciIssuer::new("http://localhost:40080");
+        let created = issuer
+            .create_credential_offer(
+                vec!["ExampleCredential_ldp_vc".to_string()],
+                Some(TxCode::default()),
+            )
+            .unwrap();
+
+        let offer = serde_json::to_value(&created.credential_offer).unwrap();
+        assert_eq!(offer["credential_issuer"], "http://localhost:40080");
+        assert_eq!(
+            offer["credential_configuration_ids"],
+            json!(["ExampleCredential_ldp_vc"])
+        );
+        let grant = &offer["grants"][PRE_AUTHORIZED_CODE_GRANT_TYPE];
+        assert!(grant["pre-authorized_code"].is_string());
+        assert_eq!(grant["tx_code"]["input_mode"], "numeric");
+        assert_eq!(created.tx_code.as_ref().unwrap().len(), 6);
+
+        assert!(created
+            .offer_by_value()
+            .starts_with("openid-credential-offer://?credential_offer=%7B%22credential_issuer%22"));
+        assert!(created.offer_by_reference().starts_with(
+            "openid-credential-offer://?credential_offer_uri=http%3A%2F%2Flocalhost%3A40080%2Fcredential%2Doffers%2F"
+        ));
+
+        let id = created.credential_offer_uri.rsplit('/').next().unwrap();
+        assert!(issuer.credential_offer(id).is_some());
+    }
+
+    #[test]
+    fn test_create_credential_offer_error_unknown_configuration() {
+        let issuer = Oid4vciIssuer::new("http://localhost:40080");
+        let err = issuer
+            .create_credential_offer(vec!["UnknownCredential".to_string()], None)
+            .unwrap_err();
+        assert_eq!(err.error, Oid4vciErrorCode::UnknownCredentialConfiguration);
+    }
+
+    #[test]
+    fn test_authorize_success_scripted_consent() {
+        let issuer = Oid4vciIssuer::new("http://localhost:40080");
+        issuer.script_consent([ConsentDecision::Deny]);
+        let req = || AuthorizationRequest {
+            response_type: Some("code".to_string()),
+            client_id: Some("wallet".to_string()),
+            redirect_uri: Some("http://localhost/cb".to_string()),
+            scope: Some("ExampleCredential_ldp_vc".to_string()),
+            code_challenge: Some(authorization::tests::CODE_CHALLENGE.to_string()),
+            code_challenge_method: Some("S256".to_string()),
+            ..Default::default()
+        };
+
+        // denied by the script
+        let location = issuer.authorize(req()).unwrap();
+        assert_eq!(
+            location,
+            "http://localhost/cb?error=access_denied&iss=http%3A%2F%2Flocalhost%3A40080"
+        );
+
+        // approved after the script runs out
+        let location = issuer.authorize(req()).unwrap();
+        assert!(location.starts_with("http://localhost/cb?code="));
+    }
+
+    #[test]
+    fn test_consume_nonce_success() {
+        let issuer = Oid4vciIssuer::new("http://localhost:40080");
+        let nonce = issuer.new_nonce();
+
+        assert!(issuer.consume_nonce(&nonce));
+        // single use
+        assert!(!issuer.consume_nonce(&nonce));
+        assert!(!issuer.consume_nonce("unknown"));
+    }
+
+    #[test]
+    fn test_consume_nonce_error_expired() {
+        let issuer = Oid4vciIssuer::new("http://localhost:40080");
+        let nonce = issuer.new_nonce();
+        issuer
+            .lock_nonces()
+            .insert(nonce.clone(), SystemTime::now() - Duration::from_secs(1));
+
+        assert!(!issuer.consume_nonce(&nonce));
+    }
+}
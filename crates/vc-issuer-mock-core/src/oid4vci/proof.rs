@@ -0,0 +1,402 @@
+//! Verifies [JWT proofs](https://openid.net/specs/openid-4-verifiable-credential-issuance-1_0.html#name-jwt-proof-type)
+//! of possession of the holder key.
+
+use std::time::{Duration, SystemTime};
+
+use josekit::{
+    jwk::Jwk,
+    jws::{EdDSA, JwsVerifier, ES256, ES256K, ES384, RS256},
+    jwt, JoseHeader as _,
+};
+use serde_json::{Map, Value};
+use ssi::{dids::DIDKey, json_ld::iref::Iri, JWK};
+
+use crate::{
+    endpoints::oid4vci::res::oid4vci_error::{Oid4vciError, Oid4vciErrorCode},
+    oid4vci::Oid4vciIssuer,
+    verification_method::CustomVerificationMethodResolver,
+    IssuerKeys,
+};
+
+/// `typ` header of JWT proofs.
+pub(crate) const PROOF_JWT_TYP: &str = "openid4vci-proof+jwt";
+
+/// Tolerated clock skew of `iat`.
+const MAX_CLOCK_SKEW: Duration = Duration::from_secs(60);
+
+/// The holder key proven by a JWT proof.
+#[derive(Clone, Debug)]
+pub(crate) struct HolderKey {
+    /// Public key as a JWK.
+    pub(crate) jwk: Map<String, Value>,
+    /// DID of the holder. `None` if the key cannot be expressed as a DID.
+    pub(crate) did: Option<String>,
+}
+
+/// Verify the JWT proofs of a credential request and return the holder keys.
+///
+/// The holder key is taken either from the `jwk` header or from the `kid` header as a DID URL.
+/// Each proof must be for this issuer (`aud`), and contain the same `c_nonce` handed out by the issuer.
+/// The `c_nonce` is consumed once for all the proofs, so that a batch can be requested with one `c_nonce`.
+pub(crate) async fn verify_jwt_proofs(
+    proof_jwts: &[&str],
+    issuer_keys: &IssuerKeys,
+    issuer: &Oid4vciIssuer,
+) -> Result<Vec<HolderKey>, Oid4vciError> {
+    let mut holder_keys = Vec::with_capacity(proof_jwts.len());
+    let mut nonces = Vec::with_capacity(proof_jwts.len());
+    for proof_jwt in proof_jwts {
+        let (holder_key, nonce) = verify_jwt_proof(proof_jwt, issuer_keys, issuer).await?;
+        holder_keys.push(holder_key);
+        nonces.push(nonce);
+    }
+
+    let nonce = match nonces.split_first() {
+        Some((Some(nonce), rest)) if rest.iter().all(|other| other.as_ref() == Some(nonce)) => {
+            Some(nonce.as_str())
+        }
+        Some((Some(_), _)) => {
+            return Err(Oid4vciError::bad_request(
+                Oid4vciErrorCode::InvalidNonce,
+                "all proof JWTs must have the same `nonce`",
+            ))
+        }
+        _ => None,
+    };
+    if !nonce.is_some_and(|nonce| issuer.consume_nonce(nonce)) {
+        return Err(Oid4vciError::bad_request(
+            Oid4vciErrorCode::InvalidNonce,
+            "`nonce` of proof JWT is missing, unknown or expired",
+        ));
+    }
+
+    Ok(holder_keys)
+}
+
+/// Verify a JWT proof except for its `nonce`, and return the holder key and the `nonce`.
+async fn verify_jwt_proof(
+    proof_jwt: &str,
+    issuer_keys: &IssuerKeys,
+    issuer: &Oid4vciIssuer,
+) -> Result<(HolderKey, Option<String>), Oid4vciError> {
+    let header = jwt::decode_header(proof_jwt)
+        .map_err(|e| invalid_proof(format!("malformed proof JWT: {}", e)))?;
+
+    if header.claim("typ").and_then(Value::as_str) != Some(PROOF_JWT_TYP) {
+        return Err(invalid_proof(format!(
+            "`typ` header of proof JWT must be `{}`",
+            PROOF_JWT_TYP
+        )));
+    }
+    let alg = header
+        .claim("alg")
+        .and_then(Value::as_str)
+        .ok_or_else(|| invalid_proof("`alg` header is missing in proof JWT"))?
+        .to_string();
+
+    let holder_key = match (header.claim("jwk"), header.claim("kid")) {
+        (Some(Value::Object(jwk)), None) => holder_key_from_jwk(jwk)?,
+        (None, Some(Value::String(kid))) => holder_key_from_kid(kid, issuer_keys).await?,
+        _ => {
+            return Err(invalid_proof(
+                "proof JWT must have exactly one of `jwk` or `kid` (DID URL) header",
+            ))
+        }
+    };
+
+    let verifier = jws_verifier(&alg, &holder_key.jwk)?;
+    let (payload, _) = jwt::decode_with_verifier(proof_jwt, verifier.as_ref())
+        .map_err(|e| invalid_proof(format!("invalid signature of proof JWT: {}", e)))?;
+
+    if !payload
+        .audience()
+        .is_some_and(|aud| aud.contains(&issuer.credential_issuer()))
+    {
+        return Err(invalid_proof(format!(
+            "`aud` of proof JWT must be `{}`",
+            issuer.credential_issuer()
+        )));
+    }
+    match payload.issued_at() {
+        Some(iat) if iat <= SystemTime::now() + MAX_CLOCK_SKEW => {}
+        Some(_) => return Err(invalid_proof("`iat` of proof JWT is in the future")),
+        None => return Err(invalid_proof("`iat` is missing in proof JWT")),
+    }
+
+    let nonce = payload
+        .claim("nonce")
+        .and_then(Value::as_str)
+        .map(|nonce| nonce.to_string());
+
+    Ok((holder_key, nonce))
+}
+
+fn holder_key_from_jwk(jwk: &Map<String, Value>) -> Result<HolderKey, Oid4vciError> {
+    if jwk.contains_key("d") {
+        return Err(invalid_proof("`jwk` header must not contain a private key"));
+    }
+    let ssi_jwk: JWK = serde_json::from_value(Value::Object(jwk.clone()))
+        .map_err(|e| invalid_proof(format!("invalid `jwk` header: {}", e)))?;
+    let did = DIDKey::generate(&ssi_jwk).ok().map(|did| did.to_string());
+
+    Ok(HolderKey {
+        jwk: jwk.clone(),
+        did,
+    })
+}
+
+async fn holder_key_from_kid(
+    kid: &str,
+    issuer_keys: &IssuerKeys,
+) -> Result<HolderKey, Oid4vciError> {
+    let (did, _) = kid
+        .split_once('#')
+        .filter(|(did, _)| did.starts_with("did:"))
+        .ok_or_else(|| invalid_proof("`kid` header of proof JWT must be a DID URL"))?;
+    let did_url = Iri::new(kid).map_err(|_| invalid_proof("`kid` header is not a valid IRI"))?;
+
+    let vm_resolver = CustomVerificationMethodResolver::new(issuer_keys.clone());
+    let jwk = vm_resolver
+        .resolve_did_url(did_url)
+        .await
+        .and_then(|vm| vm.try_to_jwk())
+        .map_err(|problem_details| {
+            invalid_proof(format!(
+                "failed to resolve `kid` header: {}",
+                problem_details.detail
+            ))
+        })?;
+    let Ok(Value::Object(jwk)) = serde_json::to_value(jwk) else {
+        unreachable!("JWK should be serialized into a JSON object");
+    };
+
+    Ok(HolderKey {
+        jwk,
+        did: Some(did.to_string()),
+    })
+}
+
+fn jws_verifier(alg: &str, jwk: &Map<String, Value>) -> Result<Box<dyn JwsVerifier>, Oid4vciError> {
+    let jwk = Jwk::from_map(jwk.clone())
+        .map_err(|e| invalid_proof(format!("invalid holder key: {}", e)))?;
+
+    let verifier: Result<Box<dyn JwsVerifier>, _> = match alg {
+        "ES256" => ES256.verifier_from_jwk(&jwk).map(|v| Box::new(v) as _),
+        "ES384" => ES384.verifier_from_jwk(&jwk).map(|v| Box::new(v) as _),
+        "ES256K" => ES256K.verifier_from_jwk(&jwk).map(|v| Box::new(v) as _),
+        "EdDSA" => EdDSA.verifier_from_jwk(&jwk).map(|v| Box::new(v) as _),
+        "RS256" => RS256.verifier_from_jwk(&jwk).map(|v| Box::new(v) as _),
+        // `none` and symmetric algorithms cannot prove possession of a holder key.
+        _ => {
+            return Err(invalid_proof(format!(
+                "unsupported `alg` of proof JWT: {}",
+                alg
+            )))
+        }
+    };
+    verifier.map_err(|e| invalid_proof(format!("holder key does not match `alg`: {}", e)))
+}
+
+fn invalid_proof(error_description: impl Into<String>) -> Oid4vciError {
+    Oid4vciError::bad_request(Oid4vciErrorCode::InvalidProof, error_description)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use josekit::{
+        jws::JwsHeader,
+        jwt::{encode_with_signer, JwtPayload},
+    };
+
+    use crate::test_jwks::{
+        ISSMOCK_PRIV_EC_P384, ISSMOCK_PRIV_OKP_ED25519, ISSMOCK_PRIV_OKP_ED25519_DIDKEY,
+        JWK_EC_P384_PRIV, JWK_EC_P384_PUB,
+    };
+
+    use super::*;
+
+    /// Sign a proof JWT for `issuer` with `holder_priv_jwk` (EC P-384 or Ed25519).
+    ///
+    /// The holder key is put in the `jwk` header if `kid` is `None`.
+    pub(crate) fn proof_jwt(
+        issuer: &Oid4vciIssuer,
+        holder_priv_jwk: &str,
+        kid: Option<&str>,
+        nonce: Option<&str>,
+    ) -> String {
+        let jwk = Jwk::from_bytes(holder_priv_jwk).unwrap();
+
+        let mut header = JwsHeader::new();
+        header.set_token_type(PROOF_JWT_TYP);
+        match kid {
+            Some(kid) => header.set_key_id(kid),
+            None => header.set_jwk(jwk.to_public_key().unwrap()),
+        }
+
+        let mut payload = JwtPayload::new();
+        payload.set_audience(vec![issuer.credential_issuer()]);
+        payload.set_issued_at(&SystemTime::now());
+        if let Some(nonce) = nonce {
+            payload
+                .set_claim("nonce", Some(Value::from(nonce)))
+                .unwrap();
+        }
+
+        let signer: Box<dyn josekit::jws::JwsSigner> = match jwk.key_type() {
+            "OKP" => Box::new(EdDSA.signer_from_jwk(&jwk).unwrap()),
+            _ => Box::new(ES384.signer_from_jwk(&jwk).unwrap()),
+        };
+        encode_with_signer(&payload, &header, signer.as_ref()).unwrap()
+    }
+
+    fn setup() -> (IssuerKeys, Oid4vciIssuer) {
+        (
+            IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519, ISSMOCK_PRIV_EC_P384]),
+            Oid4vciIssuer::new("http://localhost:40080"),
+        )
+    }
+
+    async fn verify_one(
+        jwt: &str,
+        issuer_keys: &IssuerKeys,
+        issuer: &Oid4vciIssuer,
+    ) -> Result<HolderKey, Oid4vciError> {
+        let mut holder_keys = verify_jwt_proofs(&[jwt], issuer_keys, issuer).await?;
+        Ok(holder_keys.remove(0))
+    }
+
+    #[tokio::test]
+    async fn test_verify_jwt_proof_success_jwk() {
+        let (issuer_keys, issuer) = setup();
+        let nonce = issuer.new_nonce();
+        let jwt = proof_jwt(&issuer, JWK_EC_P384_PRIV, None, Some(&nonce));
+
+        let holder_key = verify_one(&jwt, &issuer_keys, &issuer).await.unwrap();
+
+        let expected: Map<String, Value> = serde_json::from_str(JWK_EC_P384_PUB).unwrap();
+        assert_eq!(holder_key.jwk["x"], expected["x"]);
+        assert!(holder_key.did.unwrap().starts_with("did:key:"));
+    }
+
+    #[tokio::test]
+    async fn test_verify_jwt_proof_success_kid() {
+        let (issuer_keys, issuer) = setup();
+        let nonce = issuer.new_nonce();
+        let kid = format!(
+            "{}#{}",
+            ISSMOCK_PRIV_OKP_ED25519_DIDKEY,
+            ISSMOCK_PRIV_OKP_ED25519_DIDKEY.trim_start_matches("did:key:")
+        );
+        let jwt = proof_jwt(&issuer, ISSMOCK_PRIV_OKP_ED25519, Some(&kid), Some(&nonce));
+
+        let holder_key = verify_one(&jwt, &issuer_keys, &issuer).await.unwrap();
+        assert_eq!(holder_key.did.unwrap(), ISSMOCK_PRIV_OKP_ED25519_DIDKEY);
+    }
+
+    #[tokio::test]
+    async fn test_verify_jwt_proof_error_nonce_reused() {
+        let (issuer_keys, issuer) = setup();
+        let nonce = issuer.new_nonce();
+
+        let jwt = proof_jwt(&issuer, JWK_EC_P384_PRIV, None, Some(&nonce));
+        verify_one(&jwt, &issuer_keys, &issuer).await.unwrap();
+
+        let jwt = proof_jwt(&issuer, JWK_EC_P384_PRIV, None, Some(&nonce));
+        let err = verify_one(&jwt, &issuer_keys, &issuer).await.unwrap_err();
+        assert_eq!(err.error, Oid4vciErrorCode::InvalidNonce);
+    }
+
+    #[tokio::test]
+    async fn test_verify_jwt_proofs_success_batch() {
+        let (issuer_keys, issuer) = setup();
+        let nonce = issuer.new_nonce();
+        let jwts = [
+            proof_jwt(&issuer, JWK_EC_P384_PRIV, None, Some(&nonce)),
+            proof_jwt(&issuer, ISSMOCK_PRIV_OKP_ED25519, None, Some(&nonce)),
+        ];
+
+        let holder_keys =
+            verify_jwt_proofs(&[jwts[0].as_str(), jwts[1].as_str()], &issuer_keys, &issuer)
+                .await
+                .unwrap();
+        assert_eq!(holder_keys.len(), 2);
+        assert_ne!(holder_keys[0].jwk, holder_keys[1].jwk);
+    }
+
+    #[tokio::test]
+    async fn test_verify_jwt_proofs_error_different_nonces() {
+        let (issuer_keys, issuer) = setup();
+        let jwts = [
+            proof_jwt(&issuer, JWK_EC_P384_PRIV, None, Some(&issuer.new_nonce())),
+            proof_jwt(&issuer, JWK_EC_P384_PRIV, None, Some(&issuer.new_nonce())),
+        ];
+
+        let err = verify_jwt_proofs(&[jwts[0].as_str(), jwts[1].as_str()], &issuer_keys, &issuer)
+            .await
+            .unwrap_err();
+        assert_eq!(err.error, Oid4vciErrorCode::InvalidNonce);
+    }
+
+    #[tokio::test]
+    async fn test_verify_jwt_proof_error_no_nonce() {
+        let (issuer_keys, issuer) = setup();
+        let jwt = proof_jwt(&issuer, JWK_EC_P384_PRIV, None, None);
+
+        let err = verify_one(&jwt, &issuer_keys, &issuer).await.unwrap_err();
+        assert_eq!(err.error, Oid4vciErrorCode::InvalidNonce);
+    }
+
+    #[tokio::test]
+    async fn test_verify_jwt_proof_error_wrong_audience() {
+        let (issuer_keys, issuer) = setup();
+        let other_issuer = Oid4vciIssuer::new("https://other.example");
+        let nonce = issuer.new_nonce();
+        let jwt = proof_jwt(&other_issuer, JWK_EC_P384_PRIV, None, Some(&nonce));
+
+        let err = verify_one(&jwt, &issuer_keys, &issuer).await.unwrap_err();
+        assert_eq!(err.error, Oid4vciErrorCode::InvalidProof);
+    }
+
+    #[tokio::test]
+    async fn test_verify_jwt_proof_error_tampered() {
+        let (issuer_keys, issuer) = setup();
+        let nonce = issuer.new_nonce();
+        let jwt = proof_jwt(&issuer, JWK_EC_P384_PRIV, None, Some(&nonce));
+        // Replace the signature with the one of another proof.
+        let other = proof_jwt(&issuer, JWK_EC_P384_PRIV, None, Some("other"));
+        let jwt = format!(
+            "{}.{}",
+            jwt.rsplit_once('.').unwrap().0,
+            other.rsplit_once('.').unwrap().1
+        );
+
+        let err = verify_one(&jwt, &issuer_keys, &issuer).await.unwrap_err();
+        assert_eq!(err.error, Oid4vciErrorCode::InvalidProof);
+    }
+
+    #[tokio::test]
+    async fn test_verify_jwt_proof_error_private_jwk() {
+        let (issuer_keys, issuer) = setup();
+        let nonce = issuer.new_nonce();
+        let jwt = proof_jwt(&issuer, JWK_EC_P384_PRIV, None, Some(&nonce));
+
+        // Rebuild the header with the private key.
+        let (_, rest) = jwt.split_once('.').unwrap();
+        let header = serde_json::json!({
+            "alg": "ES384",
+            "typ": PROOF_JWT_TYP,
+            "jwk": serde_json::from_str::<Value>(JWK_EC_P384_PRIV).unwrap(),
+        });
+        let jwt = format!(
+            "{}.{}",
+            base64::Engine::encode(
+                &base64::engine::general_purpose::URL_SAFE_NO_PAD,
+                header.to_string()
+            ),
+            rest
+        );
+
+        let err = verify_one(&jwt, &issuer_keys, &issuer).await.unwrap_err();
+        assert_eq!(err.error, Oid4vciErrorCode::InvalidProof);
+    }
+}
@@ -0,0 +1,436 @@
+//! Implements [SD-JWT-based Verifiable Credentials (SD-JWT VC)](https://datatracker.ietf.org/doc/draft-ietf-oauth-sd-jwt-vc/).
+//!
+//! A credential in the issue request is mapped into SD-JWT VC claims as follows:
+//!
+//! - `id` -> `jti`
+//! - `issuer` -> `iss`
+//! - the last `type` -> `vct`
+//! - `validFrom` / `validUntil` (VCDM v2.0) or `issuanceDate` / `expirationDate` (VCDM v1.1) -> `nbf` / `exp`
+//! - `credentialStatus` -> `status`, as is
+//! - `credentialSubject.id` -> `sub`
+//! - other `credentialSubject` properties -> top-level claims, each of them selectively disclosable
+//! - other properties (except `@context`) -> top-level claims, always disclosed
+//!
+//! `mandatoryPointers` in the issue options are used as the always-disclosed claims.
+//! A pointer `/credentialSubject/<claim>` (or any pointer under it) makes `<claim>` always disclosed.
+//! Pointers outside `/credentialSubject/` are rejected, as the other claims are always disclosed anyway.
+//!
+//! Claims with the same name (e.g. `name` both in the credential and its subject) are rejected.
+
+use anyhow::anyhow;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::DateTime;
+use josekit::{
+    jws::{self, JwsHeader},
+    util::random_bytes,
+};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use ssi::{claims::data_integrity::JsonPointerBuf, JWK};
+
+use crate::{
+    endpoints::vc_api::res::{
+        vc_api_error::custom_problem_types::CustomProblemType, AnyVerifiableCredential,
+        SdJwtCredential, SdJwtDisclosure,
+    },
+    issuer_keys::{SigningKey, VerificationKey},
+    vcdm_v2::problem_details::{PredefinedProblemType, ProblemDetails},
+};
+
+/// Credential properties mapped into registered claims, or not carried into the SD-JWT VC.
+const MAPPED_PROPERTIES: [&str; 10] = [
+    "@context",
+    "id",
+    "type",
+    "issuer",
+    "validFrom",
+    "validUntil",
+    "issuanceDate",
+    "expirationDate",
+    "credentialStatus",
+    "credentialSubject",
+];
+
+/// Issue an SD-JWT VC from the credential.
+///
+/// If `holder_jwk` is given, it is bound to the SD-JWT as `cnf.jwk` so that the holder can present it with
+/// a Key Binding JWT.
+pub(crate) fn create_sd_jwt_vc(
+    credential: &AnyVerifiableCredential,
+    always_disclosed: &[JsonPointerBuf],
+    holder_jwk: Option<&Map<String, Value>>,
+    signing_key: &SigningKey,
+    kid: &str,
+) -> Result<SdJwtCredential, ProblemDetails> {
+    let (payload, disclosures) = to_sd_jwt_payload(credential, always_disclosed, holder_jwk)?;
+
+    let jwt = sign(&payload, signing_key, kid).map_err(|e| {
+        ProblemDetails::new(
+            CustomProblemType::SignatureError,
+            "signature error".to_string(),
+            "failed to sign SD-JWT VC".to_string(),
+            e,
+        )
+    })?;
+
+    // <https://datatracker.ietf.org/doc/html/draft-ietf-oauth-selective-disclosure-jwt#name-sd-jwt-and-sd-jwtkb-data-fo>
+    let mut sd_jwt = jwt;
+    for disclosure in &disclosures {
+        sd_jwt.push('~');
+        sd_jwt.push_str(&disclosure.disclosure);
+    }
+    sd_jwt.push('~');
+
+    Ok(SdJwtCredential {
+        sd_jwt,
+        disclosures,
+    })
+}
+
+fn to_sd_jwt_payload(
+    credential: &AnyVerifiableCredential,
+    always_disclosed: &[JsonPointerBuf],
+    holder_jwk: Option<&Map<String, Value>>,
+) -> Result<(Map<String, Value>, Vec<SdJwtDisclosure>), ProblemDetails> {
+    let always_disclosed = always_disclosed_claims(always_disclosed)?;
+    let credential = serde_json::to_value(credential)
+        .expect("AnyVerifiableCredential should be serialized into JSON");
+    let mut payload = Map::new();
+
+    if let Some(jti) = credential["id"].as_str() {
+        payload.insert("jti".to_string(), Value::String(jti.to_string()));
+    }
+
+    let iss = match &credential["issuer"] {
+        Value::String(iss) => Some(iss.clone()),
+        issuer => issuer["id"].as_str().map(|id| id.to_string()),
+    };
+    if let Some(iss) = iss {
+        payload.insert("iss".to_string(), Value::String(iss));
+    }
+
+    let vct = match &credential["type"] {
+        Value::String(r#type) => Some(r#type.clone()),
+        Value::Array(types) => types.last().and_then(|t| t.as_str()).map(|t| t.to_string()),
+        _ => None,
+    };
+    if let Some(vct) = vct {
+        payload.insert("vct".to_string(), Value::String(vct));
+    }
+
+    payload.insert(
+        "iat".to_string(),
+        Value::from(chrono::Utc::now().timestamp()),
+    );
+    for (property, claim) in [
+        ("validFrom", "nbf"),
+        ("validUntil", "exp"),
+        ("issuanceDate", "nbf"),
+        ("expirationDate", "exp"),
+    ] {
+        if let Some(date_time) = credential[property].as_str() {
+            let timestamp = DateTime::parse_from_rfc3339(date_time)
+                .map_err(|e| {
+                    malformed_value_error(format!("`{}` is not a valid date-time: {}", property, e))
+                })?
+                .timestamp();
+            payload.insert(claim.to_string(), Value::from(timestamp));
+        }
+    }
+
+    if let Some(status) = credential.get("credentialStatus") {
+        payload.insert("status".to_string(), status.clone());
+    }
+
+    if let Some(holder_jwk) = holder_jwk {
+        validate_holder_jwk(holder_jwk)?;
+        let mut cnf = Map::new();
+        cnf.insert("jwk".to_string(), Value::Object(holder_jwk.clone()));
+        payload.insert("cnf".to_string(), Value::Object(cnf));
+    }
+
+    for (name, value) in credential.as_object().into_iter().flatten() {
+        if !MAPPED_PROPERTIES.contains(&name.as_str()) {
+            insert_claim(&mut payload, name.clone(), value.clone())?;
+        }
+    }
+
+    let subject = match &credential["credentialSubject"] {
+        Value::Object(subject) => subject.clone(),
+        Value::Array(subjects) if subjects.len() == 1 => {
+            subjects[0].as_object().cloned().unwrap_or_default()
+        }
+        _ => {
+            return Err(malformed_value_error(
+                "SD-JWT VC supports exactly one `credentialSubject`".to_string(),
+            ))
+        }
+    };
+
+    let mut disclosures = vec![];
+    for (name, value) in subject {
+        if name == "id" {
+            payload.insert("sub".to_string(), value);
+        } else if always_disclosed.contains(&name) {
+            insert_claim(&mut payload, name, value)?;
+        } else if payload.contains_key(&name) {
+            return Err(conflicting_claim_error(&name));
+        } else {
+            disclosures.push(disclose(name, value));
+        }
+    }
+
+    if !disclosures.is_empty() {
+        // Sort digests not to reveal the original order of claims.
+        let mut digests = disclosures
+            .iter()
+            .map(|d| d.digest.clone())
+            .collect::<Vec<_>>();
+        digests.sort();
+
+        payload.insert(
+            "_sd".to_string(),
+            Value::Array(digests.into_iter().map(Value::String).collect()),
+        );
+        payload.insert("_sd_alg".to_string(), Value::String("sha-256".to_string()));
+    }
+
+    Ok((payload, disclosures))
+}
+
+/// Names of `credentialSubject` properties pointed by `/credentialSubject/<claim>[/...]`.
+fn always_disclosed_claims(pointers: &[JsonPointerBuf]) -> Result<Vec<String>, ProblemDetails> {
+    pointers
+        .iter()
+        .map(|ptr| {
+            let rest = ptr
+                .as_str()
+                .strip_prefix("/credentialSubject/")
+                .ok_or_else(|| {
+                    malformed_value_error(format!(
+                    "`mandatoryPointers` of SD-JWT VC must point under `/credentialSubject/`: `{}`",
+                    ptr
+                ))
+                })?;
+            let token = rest.split('/').next().unwrap_or_default();
+            // <https://datatracker.ietf.org/doc/html/rfc6901#section-4>
+            Ok(token.replace("~1", "/").replace("~0", "~"))
+        })
+        .collect()
+}
+
+/// Insert a claim, rejecting the one already in the payload.
+fn insert_claim(
+    payload: &mut Map<String, Value>,
+    name: String,
+    value: Value,
+) -> Result<(), ProblemDetails> {
+    if payload.contains_key(&name) {
+        return Err(conflicting_claim_error(&name));
+    }
+    payload.insert(name, value);
+    Ok(())
+}
+
+fn conflicting_claim_error(name: &str) -> ProblemDetails {
+    malformed_value_error(format!(
+        "`{}` conflicts with another claim of the SD-JWT VC",
+        name
+    ))
+}
+
+/// <https://datatracker.ietf.org/doc/html/draft-ietf-oauth-selective-disclosure-jwt#name-disclosures-for-object-prop>
+fn disclose(claim: String, value: Value) -> SdJwtDisclosure {
+    let salt = URL_SAFE_NO_PAD.encode(random_bytes(16));
+    let array = Value::Array(vec![
+        Value::String(salt),
+        Value::String(claim.clone()),
+        value,
+    ]);
+    let disclosure = URL_SAFE_NO_PAD.encode(array.to_string());
+    let digest = URL_SAFE_NO_PAD.encode(Sha256::digest(disclosure.as_bytes()));
+
+    SdJwtDisclosure {
+        claim,
+        disclosure,
+        digest,
+    }
+}
+
+fn validate_holder_jwk(holder_jwk: &Map<String, Value>) -> Result<(), ProblemDetails> {
+    serde_json::from_value::<JWK>(Value::Object(holder_jwk.clone()))
+        .map_err(|e| anyhow!(e))
+        .and_then(|jwk| VerificationKey::try_from(&jwk))
+        .map(|_| ())
+        .map_err(|e| malformed_value_error(format!("`holderJwk` is not a valid public JWK: {}", e)))
+}
+
+fn sign(
+    payload: &Map<String, Value>,
+    signing_key: &SigningKey,
+    kid: &str,
+) -> anyhow::Result<String> {
+    let signer = signing_key.to_jws_signer()?;
+
+    // <https://datatracker.ietf.org/doc/html/draft-ietf-oauth-sd-jwt-vc#name-jose-header>
+    let mut header = JwsHeader::new();
+    header.set_token_type("dc+sd-jwt");
+    header.set_key_id(kid);
+
+    let jwt = jws::serialize_compact(&serde_json::to_vec(payload)?, &header, signer.as_ref())?;
+    Ok(jwt)
+}
+
+fn malformed_value_error(detail: String) -> ProblemDetails {
+    ProblemDetails::new(
+        PredefinedProblemType::MalformedValueError,
+        "validation error (SD-JWT VC)".to_string(),
+        detail.clone(),
+        anyhow!(detail),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use serde_json::json;
+
+    use crate::{
+        endpoints::vc_api::req::IssueRequest,
+        test_jwks::{ISSMOCK_PUB_OKP_ED25519, JWK_OKP_ED25519_PRIV},
+        test_tracing::init_tracing,
+        test_vc_json::vc_data_model_2_0_test_suite::README_ALUMNI,
+        vcdm_v2::problem_details::ProblemType as _,
+    };
+
+    use super::*;
+
+    fn readme_alumni() -> AnyVerifiableCredential {
+        let req: IssueRequest = serde_json::from_str(README_ALUMNI).unwrap();
+        req.credential
+    }
+
+    fn decode_disclosure(disclosure: &str) -> Value {
+        let bytes = URL_SAFE_NO_PAD.decode(disclosure).unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[test]
+    fn test_to_sd_jwt_payload_selectively_disclosed() {
+        init_tracing();
+
+        let (payload, disclosures) = to_sd_jwt_payload(&readme_alumni(), &[], None).unwrap();
+        assert_eq!(payload["jti"], "http://university.example/credentials/1872");
+        assert_eq!(payload["iss"], "https://university.example/issuers/565049");
+        assert_eq!(payload["vct"], "ExampleAlumniCredential");
+        assert_eq!(payload["sub"], "did:example:ebfeb1f712ebc6f1c276e12ec21");
+        assert_eq!(payload["nbf"], 1688239404);
+        assert!(payload.get("alumniOf").is_none());
+        assert!(payload.get("cnf").is_none());
+
+        assert_eq!(disclosures.len(), 1);
+        let disclosure = &disclosures[0];
+        assert_eq!(disclosure.claim, "alumniOf");
+        assert_eq!(
+            decode_disclosure(&disclosure.disclosure)[2]["name"],
+            "Example University"
+        );
+        assert_eq!(
+            disclosure.digest,
+            URL_SAFE_NO_PAD.encode(Sha256::digest(disclosure.disclosure.as_bytes()))
+        );
+        assert_eq!(payload["_sd"][0], Value::String(disclosure.digest.clone()));
+        assert_eq!(payload["_sd_alg"], "sha-256");
+    }
+
+    #[test]
+    fn test_to_sd_jwt_payload_always_disclosed() {
+        init_tracing();
+
+        let pointers = vec![JsonPointerBuf::from_str("/credentialSubject/alumniOf/name").unwrap()];
+        let (payload, disclosures) = to_sd_jwt_payload(&readme_alumni(), &pointers, None).unwrap();
+        assert_eq!(payload["alumniOf"]["name"], "Example University");
+        assert!(payload.get("_sd").is_none());
+        assert!(disclosures.is_empty());
+    }
+
+    #[test]
+    fn test_to_sd_jwt_payload_other_properties() {
+        init_tracing();
+
+        let mut req: Value = serde_json::from_str(README_ALUMNI).unwrap();
+        let status = json!({
+            "id": "https://university.example/status-lists/revocation#94567",
+            "type": "BitstringStatusListEntry",
+            "statusPurpose": "revocation",
+            "statusListIndex": "94567",
+            "statusListCredential": "https://university.example/status-lists/revocation",
+        });
+        req["credential"]["credentialStatus"] = status.clone();
+        req["credential"]["name"] = json!("Alumni Credential");
+        let req: IssueRequest = serde_json::from_value(req).unwrap();
+
+        let (payload, disclosures) = to_sd_jwt_payload(&req.credential, &[], None).unwrap();
+        assert_eq!(payload["status"], status);
+        assert_eq!(payload["name"], "Alumni Credential");
+        for property in [
+            "@context",
+            "id",
+            "type",
+            "credentialStatus",
+            "credentialSubject",
+        ] {
+            assert!(payload.get(property).is_none(), "{}", property);
+        }
+        assert_eq!(disclosures.len(), 1);
+    }
+
+    #[test]
+    fn test_to_sd_jwt_payload_error_conflicting_claim() {
+        init_tracing();
+
+        let mut req: Value = serde_json::from_str(README_ALUMNI).unwrap();
+        req["credential"]["alumniOf"] = json!("Example University");
+        let req: IssueRequest = serde_json::from_value(req).unwrap();
+
+        let e = to_sd_jwt_payload(&req.credential, &[], None).unwrap_err();
+        assert_eq!(
+            e.code().unwrap(),
+            PredefinedProblemType::MalformedValueError.code()
+        );
+    }
+
+    #[test]
+    fn test_to_sd_jwt_payload_error_mandatory_pointer_outside_subject() {
+        init_tracing();
+
+        let pointers = vec![JsonPointerBuf::from_str("/issuer").unwrap()];
+        let e = to_sd_jwt_payload(&readme_alumni(), &pointers, None).unwrap_err();
+        assert_eq!(
+            e.code().unwrap(),
+            PredefinedProblemType::MalformedValueError.code()
+        );
+    }
+
+    #[test]
+    fn test_to_sd_jwt_payload_cnf() {
+        init_tracing();
+
+        let holder_jwk: Map<String, Value> = serde_json::from_str(ISSMOCK_PUB_OKP_ED25519).unwrap();
+        let (payload, _) = to_sd_jwt_payload(&readme_alumni(), &[], Some(&holder_jwk)).unwrap();
+        assert_eq!(payload["cnf"]["jwk"], Value::Object(holder_jwk));
+    }
+
+    #[test]
+    fn test_to_sd_jwt_payload_error_private_holder_jwk() {
+        init_tracing();
+
+        let holder_jwk: Map<String, Value> = serde_json::from_str(JWK_OKP_ED25519_PRIV).unwrap();
+        let e = to_sd_jwt_payload(&readme_alumni(), &[], Some(&holder_jwk)).unwrap_err();
+        assert_eq!(
+            e.code().unwrap(),
+            PredefinedProblemType::MalformedValueError.code()
+        );
+    }
+}
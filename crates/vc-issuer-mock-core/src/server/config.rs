@@ -0,0 +1,442 @@
+//! Configuration of the server.
+//!
+//! Read from the file given by `--config <path>` or `ISSMOCK_CONFIG`, in TOML (or JSON if the file
+//! name ends with `.json`). Without a file, the `ISSMOCK_*` environment variables are used.
+//!
+//! ```toml
+//! bind = "0.0.0.0:40080"
+//! base_url = "http://localhost:40080"
+//! log_level = "info"
+//! # "vc-api", "status-list", "oid4vci" and "did-web" are enabled by default.
+//! endpoints = ["vc-api", "status-list", "did-web"]
+//! status_list_path = "/status-lists"
+//! sqlite_path = "issmock.sqlite3"
+//! credential_id_prefix = "urn:uuid:"
+//!
+//! # Private keys (JWK), inline or as paths to JWK files relative to the config file.
+//! # Random keys are generated if omitted.
+//! keys = [
+//!   { kty = "OKP", crv = "Ed25519", d = "...", x = "..." },
+//!   "keys/p384.jwk",
+//! ]
+//!
+//! [default_properties]
+//! name = "Example Credential"
+//!
+//! # Written as `IssuerProfileConfig`s (in camelCase), but `keys` may also be paths to JWK files.
+//! [[issuer_profiles]]
+//! name = "university"
+//! issuer = "did:web:localhost%3A40080:dids:university"
+//! keys = ["keys/university.jwk"]
+//! cryptosuite = "eddsa-rdfc-2022"
+//! defaultProperties = { description = "Issued by Example University" }
+//! ```
+//!
+//! Every value is validated at startup, and the server exits with the error if any is invalid.
+
+use std::{
+    env, fs,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::{anyhow, bail, Context};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use vc_issuer_mock_core::{
+    issuer_profile::{DefaultVcProperties, IssuerProfile, IssuerProfileConfig, IssuerProfiles},
+    IssuerKeys,
+};
+
+/// Port used when `bind` is not configured.
+const DEFAULT_PORT: u16 = 40080;
+
+/// Validated configuration of the server.
+#[derive(Debug)]
+pub(crate) struct Config {
+    pub(crate) bind: SocketAddr,
+    pub(crate) base_url: String,
+    pub(crate) log_level: tracing::Level,
+    pub(crate) endpoints: Vec<EndpointGroup>,
+    pub(crate) status_list_path: String,
+    pub(crate) sqlite_path: Option<String>,
+    pub(crate) credential_id_prefix: Option<String>,
+    /// Static issuer keys. If `None`, persisted or random keys are used.
+    pub(crate) issuer_keys: Option<IssuerKeys>,
+    pub(crate) issuer_profiles: IssuerProfiles,
+    pub(crate) default_properties: DefaultVcProperties,
+}
+
+/// Groups of endpoints which can be enabled.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum EndpointGroup {
+    /// `/credentials/*` and `/issuers/{name}/credentials/*`.
+    VcApi,
+    /// Status list credentials.
+    StatusList,
+    /// OID4VCI credential issuer and authorization server.
+    Oid4vci,
+    /// DID documents of `did:web`s.
+    DidWeb,
+}
+
+/// Configuration file as written.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    bind: Option<SocketAddr>,
+    base_url: Option<String>,
+    log_level: Option<String>,
+    endpoints: Option<Vec<EndpointGroup>>,
+    status_list_path: Option<String>,
+    sqlite_path: Option<String>,
+    credential_id_prefix: Option<String>,
+    #[serde(default)]
+    keys: Vec<KeyConfig>,
+    #[serde(default)]
+    issuer_profiles: Vec<IssuerProfileConfig<KeyConfig>>,
+    #[serde(default)]
+    default_properties: Map<String, Value>,
+}
+
+/// A private key: an inline JWK, or a path to a JWK file.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum KeyConfig {
+    Path(PathBuf),
+    Jwk(Map<String, Value>),
+}
+
+impl Config {
+    /// Load the configuration from the file given by `--config <path>` or `ISSMOCK_CONFIG`,
+    /// or from the environment variables if neither is given.
+    pub(crate) fn load() -> anyhow::Result<Self> {
+        match config_path(env::args().skip(1))? {
+            Some(path) => Self::from_file(Path::new(&path))
+                .with_context(|| format!("invalid configuration file {}", path)),
+            None => Self::from_env(),
+        }
+    }
+
+    fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let file: ConfigFile = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => serde_json::from_str(&content)?,
+            _ => toml::from_str(&content)?,
+        };
+        let base_dir = path.parent().unwrap_or(Path::new("."));
+        Self::try_from_file(file, base_dir)
+    }
+
+    fn try_from_file(file: ConfigFile, base_dir: &Path) -> anyhow::Result<Self> {
+        let log_level = match file.log_level {
+            Some(log_level) => tracing::Level::from_str(&log_level)
+                .map_err(|_| anyhow!("unknown log_level: `{}`", log_level))?,
+            None => tracing::Level::DEBUG,
+        };
+
+        let issuer_keys = if file.keys.is_empty() {
+            None
+        } else {
+            let sk_jwks = load_keys(&file.keys, base_dir)?;
+            Some(IssuerKeys::try_new(&sk_jwks).context("invalid keys")?)
+        };
+
+        let endpoints = file.endpoints.unwrap_or_else(all_endpoints);
+
+        let issuer_profiles = file
+            .issuer_profiles
+            .into_iter()
+            .map(|profile| {
+                // The DID document of a did:web issuer is served only by the "did-web" endpoints.
+                if let Some(issuer) = profile.issuer.as_deref() {
+                    if issuer.starts_with("did:web:") && !endpoints.contains(&EndpointGroup::DidWeb)
+                    {
+                        bail!(
+                            "issuer profile `{}` has a did:web issuer, but `did-web` is not in `endpoints`",
+                            profile.name
+                        );
+                    }
+                }
+
+                let keys = load_keys(&profile.keys, base_dir)?
+                    .iter()
+                    .map(|jwk| serde_json::from_str(jwk))
+                    .collect::<Result<Vec<_>, _>>()
+                    .with_context(|| format!("issuer profile `{}`", profile.name))?;
+                IssuerProfile::try_from(IssuerProfileConfig {
+                    name: profile.name,
+                    issuer: profile.issuer,
+                    keys,
+                    cryptosuite: profile.cryptosuite,
+                    default_properties: profile.default_properties,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Self {
+            bind: file.bind.unwrap_or_else(default_bind),
+            base_url: file.base_url.unwrap_or_else(default_base_url),
+            log_level,
+            endpoints,
+            status_list_path: normalize_path(
+                file.status_list_path
+                    .as_deref()
+                    .unwrap_or(DEFAULT_STATUS_LIST_PATH),
+            ),
+            sqlite_path: file.sqlite_path,
+            credential_id_prefix: file.credential_id_prefix,
+            issuer_keys,
+            issuer_profiles: IssuerProfiles::new(issuer_profiles)?,
+            default_properties: DefaultVcProperties::new(file.default_properties),
+        })
+    }
+
+    /// Configuration from the `ISSMOCK_*` environment variables (see [`crate`]).
+    fn from_env() -> anyhow::Result<Self> {
+        let issuer_keys = [
+            env::var("ISSMOCK_PRIV_OKP_ED25519"),
+            env::var("ISSMOCK_PRIV_EC_P384"),
+        ]
+        .into_iter()
+        .collect::<Result<Vec<String>, _>>()
+        .ok()
+        .map(|mut sk_jwks| {
+            sk_jwks.extend(
+                [
+                    "ISSMOCK_PRIV_EC_P256",
+                    "ISSMOCK_PRIV_EC_SECP256K1",
+                    "ISSMOCK_PRIV_EC_BLS12381G2",
+                ]
+                .into_iter()
+                .filter_map(|name| env::var(name).ok()),
+            );
+            IssuerKeys::try_new(&sk_jwks).context("invalid issuer keys in ISSMOCK_PRIV_* env")
+        })
+        .transpose()?;
+
+        let issuer_profiles = match env::var("ISSMOCK_ISSUER_PROFILES") {
+            Ok(path) => fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|json| IssuerProfiles::from_json(&json))
+                .with_context(|| format!("invalid issuer profiles in {}", path))?,
+            Err(_) => IssuerProfiles::default(),
+        };
+
+        Ok(Self {
+            bind: default_bind(),
+            base_url: env::var("ISSMOCK_BASE_URL").unwrap_or_else(|_| default_base_url()),
+            log_level: tracing::Level::DEBUG,
+            endpoints: all_endpoints(),
+            status_list_path: normalize_path(
+                &env::var("ISSMOCK_STATUS_LIST_PATH")
+                    .unwrap_or(DEFAULT_STATUS_LIST_PATH.to_string()),
+            ),
+            sqlite_path: env::var("ISSMOCK_SQLITE_PATH").ok(),
+            credential_id_prefix: env::var("ISSMOCK_CREDENTIAL_ID_PREFIX").ok(),
+            issuer_keys,
+            issuer_profiles,
+            default_properties: DefaultVcProperties::default(),
+        })
+    }
+
+    pub(crate) fn is_enabled(&self, endpoints: EndpointGroup) -> bool {
+        self.endpoints.contains(&endpoints)
+    }
+}
+
+const DEFAULT_STATUS_LIST_PATH: &str = "/status-lists";
+
+fn default_bind() -> SocketAddr {
+    SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), DEFAULT_PORT)
+}
+
+fn default_base_url() -> String {
+    format!("http://localhost:{}", DEFAULT_PORT)
+}
+
+fn all_endpoints() -> Vec<EndpointGroup> {
+    vec![
+        EndpointGroup::VcApi,
+        EndpointGroup::StatusList,
+        EndpointGroup::Oid4vci,
+        EndpointGroup::DidWeb,
+    ]
+}
+
+fn normalize_path(path: &str) -> String {
+    format!("/{}", path.trim_matches('/'))
+}
+
+/// `--config <path>` or `--config=<path>` in `args`, or `ISSMOCK_CONFIG`.
+fn config_path(mut args: impl Iterator<Item = String>) -> anyhow::Result<Option<String>> {
+    while let Some(arg) = args.next() {
+        if arg == "--config" {
+            return args
+                .next()
+                .map(Some)
+                .ok_or_else(|| anyhow!("`--config` requires a path"));
+        }
+        if let Some(path) = arg.strip_prefix("--config=") {
+            return Ok(Some(path.to_string()));
+        }
+        bail!("unknown argument: `{}`", arg);
+    }
+    Ok(env::var("ISSMOCK_CONFIG").ok())
+}
+
+/// Load keys as JWK strings. Paths are relative to `base_dir`.
+fn load_keys(keys: &[KeyConfig], base_dir: &Path) -> anyhow::Result<Vec<String>> {
+    keys.iter()
+        .map(|key| match key {
+            KeyConfig::Jwk(jwk) => Ok(Value::Object(jwk.clone()).to_string()),
+            KeyConfig::Path(path) => {
+                let path = base_dir.join(path);
+                fs::read_to_string(&path)
+                    .with_context(|| format!("failed to read key file {}", path.display()))
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ED25519: &str = r#"{"kty":"OKP","crv":"Ed25519","d":"uACpBLNoNFWd70B2I-Dq41LS6YfBsaMN609VQcynLbc","x":"RP2fVkXQcK7ZARnqeLMyJAU5Nje03RT0pd7Eqwn6_f8"}"#;
+
+    fn parse_toml(toml: &str) -> anyhow::Result<Config> {
+        Config::try_from_file(toml::from_str(toml)?, Path::new("."))
+    }
+
+    #[test]
+    fn test_try_from_file_success_default() {
+        let config = parse_toml("").unwrap();
+        assert_eq!(config.bind, default_bind());
+        assert_eq!(config.base_url, "http://localhost:40080");
+        assert_eq!(config.log_level, tracing::Level::DEBUG);
+        assert_eq!(config.endpoints, all_endpoints());
+        assert_eq!(config.status_list_path, "/status-lists");
+        assert!(config.issuer_keys.is_none());
+        assert!(config.issuer_profiles.is_empty());
+        assert!(config.default_properties.is_empty());
+    }
+
+    #[test]
+    fn test_try_from_file_success() {
+        let config = parse_toml(&format!(
+            r#"
+bind = "127.0.0.1:8080"
+base_url = "https://issuer.example"
+log_level = "info"
+endpoints = ["vc-api", "did-web"]
+status_list_path = "lists/"
+keys = [{ed25519}]
+
+[default_properties]
+name = "Example Credential"
+
+[[issuer_profiles]]
+name = "university"
+keys = [{ed25519}]
+cryptosuite = "eddsa-rdfc-2022"
+defaultProperties = {{ description = "Issued by Example University" }}
+
+[[issuer_profiles]]
+name = "bank"
+"#,
+            ed25519 = toml_inline_table(ED25519),
+        ))
+        .unwrap();
+
+        assert_eq!(config.bind, "127.0.0.1:8080".parse().unwrap());
+        assert_eq!(config.log_level, tracing::Level::INFO);
+        assert!(config.is_enabled(EndpointGroup::DidWeb));
+        assert!(!config.is_enabled(EndpointGroup::Oid4vci));
+        assert_eq!(config.status_list_path, "/lists");
+        assert_eq!(config.issuer_keys.unwrap().key_pairs().len(), 1);
+        assert_eq!(
+            config
+                .issuer_profiles
+                .iter()
+                .map(|profile| profile.name())
+                .collect::<Vec<_>>(),
+            vec!["bank", "university"]
+        );
+        assert!(!config.default_properties.is_empty());
+    }
+
+    #[test]
+    fn test_try_from_file_success_key_file() {
+        let dir = env::temp_dir().join(format!("issmock-config-{}", std::process::id()));
+        fs::create_dir_all(dir.join("keys")).unwrap();
+        fs::write(dir.join("keys/ed25519.jwk"), ED25519).unwrap();
+        fs::write(
+            dir.join("config.json"),
+            r#"{"keys": ["keys/ed25519.jwk"], "issuer_profiles": [{"name": "bank", "keys": ["keys/ed25519.jwk"], "defaultProperties": {"name": "Bank Credential"}}]}"#,
+        )
+        .unwrap();
+
+        let config = Config::from_file(&dir.join("config.json"));
+        fs::remove_dir_all(&dir).unwrap();
+
+        let config = config.unwrap();
+        assert_eq!(config.issuer_keys.unwrap().key_pairs().len(), 1);
+        assert!(config.issuer_profiles.get("bank").is_some());
+    }
+
+    #[test]
+    fn test_try_from_file_error() {
+        for toml in [
+            r#"unknown = true"#,
+            r#"bind = "localhost""#,
+            r#"log_level = "verbose""#,
+            r#"endpoints = ["unknown"]"#,
+            r#"keys = [{ kty = "OKP" }]"#,
+            r#"keys = ["no/such/file.jwk"]"#,
+            r#"[[issuer_profiles]]
+name = "a/b""#,
+            r#"[[issuer_profiles]]
+name = "bank"
+cryptosuite = "unknown-2099""#,
+            r#"[[issuer_profiles]]
+name = "bank"
+default_properties = { name = "Bank Credential" }"#,
+            r#"endpoints = ["vc-api"]
+
+[[issuer_profiles]]
+name = "bank"
+issuer = "did:web:localhost%3A40080:dids:bank""#,
+        ] {
+            assert!(parse_toml(toml).is_err(), "{}", toml);
+        }
+    }
+
+    #[test]
+    fn test_config_path() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            config_path(args(&["--config", "issmock.toml"]).into_iter()).unwrap(),
+            Some("issmock.toml".to_string())
+        );
+        assert_eq!(
+            config_path(args(&["--config=issmock.json"]).into_iter()).unwrap(),
+            Some("issmock.json".to_string())
+        );
+        assert!(config_path(args(&["--config"]).into_iter()).is_err());
+        assert!(config_path(args(&["--port", "8080"]).into_iter()).is_err());
+    }
+
+    /// A JSON object as a TOML inline table.
+    fn toml_inline_table(json: &str) -> String {
+        let map: Map<String, Value> = serde_json::from_str(json).unwrap();
+        let fields = map
+            .iter()
+            .map(|(key, value)| format!("{} = {}", key, value))
+            .collect::<Vec<_>>();
+        format!("{{ {} }}", fields.join(", "))
+    }
+}
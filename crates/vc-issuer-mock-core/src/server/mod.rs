@@ -2,6 +2,12 @@
 //!
 //! Tested from W3C test suites.
 //!
+//! # Configuration file
+//!
+//! `--config <path>` or `ISSMOCK_CONFIG` gives a TOML (or JSON) configuration file, covering everything
+//! below and more (bind address, log level, enabled endpoints, default VC properties).
+//! See [`config`] for its format. The environment variables below are ignored when it is given.
+//!
 //! # Environment variables
 //!
 //! - `ISSMOCK_PRIV_OKP_ED25519`: Static private key (JWK) for Ed25519 (OKP).
@@ -9,65 +15,350 @@
 //!
 //! If all of the above variables are set, the service will use them to issue VCs.
 //! Otherwise, it will randomly generate key-pairs at startup.
+//!
+//! - `ISSMOCK_PRIV_EC_P256`: Optional static private key (JWK) for P-256 (EC).
+//! - `ISSMOCK_PRIV_EC_SECP256K1`: Optional static private key (JWK) for secp256k1 (EC).
+//! - `ISSMOCK_PRIV_EC_BLS12381G2`: Optional static private key (JWK) for BLS12-381 G2 (EC), used
+//!   for `bbs-2023`.
+//!
+//! These are added to the static keys above when set.
+//!
+//! - `ISSMOCK_BASE_URL`: Public URL of the service, used in `statusListCredential` of issued VCs
+//!   and as the OID4VCI Credential Issuer Identifier (default: `http://localhost:40080`).
+//!   Its host also makes the `did:web` of the issuer, whose DID document is served at
+//!   `/.well-known/did.json` (and `/dids/{name}/did.json` for `did:web:{host}:dids:{name}`).
+//! - `ISSMOCK_STATUS_LIST_PATH`: Path where status list credentials are served
+//!   (default: `/status-lists`). Each status purpose is served at `{path}/{statusPurpose}`.
+//! - `ISSMOCK_SQLITE_PATH`: If set, issued credentials, status lists and issuer keys are persisted
+//!   in the SQLite database file at this path. Otherwise, everything is kept in memory.
+//!   Issuer keys from ISSMOCK_PRIV_* envs take precedence over the persisted ones.
+//!   Issuer keys are stored as private JWKs in plaintext, so protect the file as you would the keys.
+//! - `ISSMOCK_CREDENTIAL_ID_PREFIX`: If set, an `id` (`{prefix}{UUID v4}`) is minted for credentials
+//!   issued without one. `urn:uuid:` or a URL prefix like `https://issuer.example/credentials/`.
+//! - `ISSMOCK_ISSUER_PROFILES`: If set, path to a JSON file with an array of issuer profiles
+//!   (see [`IssuerProfileConfig`](vc_issuer_mock_core::issuer_profile::IssuerProfileConfig)).
+//!   Each profile issues and verifies with its own keys at `/issuers/{name}/credentials/issue`
+//!   and `/issuers/{name}/credentials/verify`. It also has its own status lists, updated at
+//!   `/issuers/{name}/credentials/status` and served at `/issuers/{name}{path}/{statusPurpose}`.
+//!   Its issued credentials are served and deleted only at `/issuers/{name}/credentials/{id}`.
+//!
+//! By default, the server listens on port 40080 and logs at the DEBUG level. Both are configurable
+//! by `bind` and `log_level` in the configuration file.
 
+mod config;
 #[cfg(feature = "server")]
 pub mod log_req_res_body;
 
-use std::{
-    env,
-    net::{IpAddr, Ipv4Addr, SocketAddr},
-};
+use std::{fmt, process, sync::Arc};
 
-use axum::{middleware, routing::post, Extension, Router};
+use axum::{
+    middleware,
+    routing::{get, post, put},
+    Extension, Router,
+};
+use config::{Config, EndpointGroup};
 use log_req_res_body::log_req_res_body;
 use tokio::net::TcpListener;
-use tracing::info;
-use vc_issuer_mock_core::{endpoints::vc_api, IssuerKeys};
+use tracing::{error, info};
+use vc_issuer_mock_core::{
+    credential_id::CredentialIdMinter,
+    credential_store::{DynCredentialStore, InMemoryCredentialStore},
+    did_web::DidWeb,
+    endpoints::{did_web, oid4vci, status_list, vc_api},
+    issuer_profile::IssuerProfiles,
+    oid4vci::Oid4vciIssuer,
+    sqlite_store::SqliteStore,
+    status_list::StatusLists,
+    IssuerKeys,
+};
 
 #[tokio::main]
 async fn main() {
+    // Logging is configured by the configuration itself, so its errors are printed as is.
+    let mut config = Config::load().unwrap_or_else(|e| {
+        eprintln!("Invalid configuration: {:#}", e);
+        process::exit(1);
+    });
+
     tracing_subscriber::fmt()
-        .with_max_level(tracing::Level::DEBUG)
+        .with_max_level(config.log_level)
         .init();
 
-    let issuer_keys = issuer_keys();
-    let app = Router::new()
-        .route("/credentials/issue", post(vc_api::credentials::issue))
+    let did_web = DidWeb::from_base_url(&config.base_url).unwrap_or_else(|e| {
+        error!("Invalid base URL: {}", e);
+        process::exit(1);
+    });
+    info!("Issuer did:web is {}", did_web.did());
+
+    let sqlite_store = sqlite_store(config.sqlite_path.as_deref());
+    let issuer_keys = issuer_keys(config.issuer_keys.take(), sqlite_store.as_deref());
+    let status_lists = status_lists(
+        &config.base_url,
+        &config.status_list_path,
+        sqlite_store.clone(),
+    );
+
+    // Each profile publishes its own status lists, signed with its own keys.
+    let issuer_profiles = if config.is_enabled(EndpointGroup::StatusList) {
+        issuer_profiles_with_status_lists(
+            &config.issuer_profiles,
+            &config.base_url,
+            &config.status_list_path,
+            sqlite_store.as_ref(),
+        )
+    } else {
+        config.issuer_profiles.clone()
+    };
+    log_issuer_profiles(&issuer_profiles);
+    let did_web = did_web.with_issuer_profiles(issuer_profiles.clone());
+    let credential_store: DynCredentialStore = match sqlite_store {
+        Some(sqlite_store) => sqlite_store,
+        None => Arc::new(InMemoryCredentialStore::default()),
+    };
+
+    let mut app = Router::new();
+    if config.is_enabled(EndpointGroup::VcApi) {
+        app = app
+            .route("/credentials/issue", post(vc_api::credentials::issue))
+            .route("/credentials/verify", post(vc_api::credentials::verify))
+            .route("/credentials/derive", post(vc_api::credentials::derive))
+            .route(
+                "/credentials/:id",
+                get(vc_api::credentials::get_credential)
+                    .delete(vc_api::credentials::delete_credential),
+            )
+            .route(
+                "/issuers/:name/credentials/issue",
+                post(vc_api::issuers::issue),
+            )
+            .route(
+                "/issuers/:name/credentials/verify",
+                post(vc_api::issuers::verify),
+            )
+            .route(
+                "/issuers/:name/credentials/:id",
+                get(vc_api::issuers::get_credential).delete(vc_api::issuers::delete_credential),
+            );
+    }
+    if config.is_enabled(EndpointGroup::StatusList) {
+        if config.is_enabled(EndpointGroup::VcApi) {
+            app = app
+                .route(
+                    "/credentials/status",
+                    post(vc_api::credentials::update_status),
+                )
+                .route(
+                    "/issuers/:name/credentials/status",
+                    post(vc_api::issuers::update_status),
+                );
+        }
+        app = app
+            .route(
+                &format!("{}/:purpose", config.status_list_path),
+                get(status_list::status_list_credential),
+            )
+            .route(
+                &format!("/issuers/:name{}/:purpose", config.status_list_path),
+                get(vc_api::issuers::status_list_credential),
+            );
+    }
+    if config.is_enabled(EndpointGroup::Oid4vci) {
+        app = app
+            .route(
+                "/.well-known/openid-credential-issuer",
+                get(oid4vci::credential_issuer::credential_issuer_metadata),
+            )
+            .route("/nonce", post(oid4vci::credential_issuer::nonce))
+            .route("/credential", post(oid4vci::credential_issuer::credential))
+            .route(
+                "/deferred_credential",
+                post(oid4vci::deferred::deferred_credential),
+            )
+            .route(
+                "/deferred_credential/:transaction_id/ready",
+                post(oid4vci::deferred::mark_ready),
+            )
+            .route("/deferral", put(oid4vci::deferred::set_deferral))
+            .route(
+                "/credential-offers",
+                post(oid4vci::credential_offer::create_credential_offer),
+            )
+            .route(
+                "/credential-offers/:id",
+                get(oid4vci::credential_offer::credential_offer),
+            )
+            .route(
+                "/.well-known/oauth-authorization-server",
+                get(oid4vci::token::authorization_server_metadata),
+            )
+            .route("/token", post(oid4vci::token::token))
+            .route("/authorize", get(oid4vci::authorization::authorize))
+            .route(
+                "/authorize/consent",
+                post(oid4vci::authorization::script_consent),
+            )
+            .route(
+                "/par",
+                post(oid4vci::authorization::pushed_authorization_request),
+            )
+            .route("/jwks", get(oid4vci::authorization::jwks))
+            .layer(Extension(Oid4vciIssuer::new(config.base_url.clone())));
+    }
+    if config.is_enabled(EndpointGroup::DidWeb) {
+        app = app
+            .route("/.well-known/did.json", get(did_web::did_document))
+            .route(
+                "/dids/:name/did.json",
+                get(did_web::path_based_did_document),
+            );
+    }
+
+    // Credentials refer to status lists, and did:web issuers are resolved locally, only when they are served.
+    if config.is_enabled(EndpointGroup::StatusList) {
+        app = app.layer(Extension(status_lists));
+    }
+    if config.is_enabled(EndpointGroup::DidWeb) {
+        app = app.layer(Extension(did_web));
+    }
+    app = app
         .layer(Extension(issuer_keys))
-        // log req/res body
-        .layer(middleware::from_fn(log_req_res_body));
+        .layer(Extension(issuer_profiles))
+        .layer(Extension(credential_store));
+    if let Some(prefix) = config.credential_id_prefix {
+        info!("Minting credential ids as {}<UUID>", prefix);
+        app = app.layer(Extension(CredentialIdMinter::new(prefix)));
+    }
+    if !config.default_properties.is_empty() {
+        app = app.layer(Extension(config.default_properties));
+    }
+    // log req/res body
+    let app = app.layer(middleware::from_fn(log_req_res_body));
 
-    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 40080);
-    let listener = TcpListener::bind(&addr)
-        .await
-        .expect("Could not bind listener");
-    info!("listening on {}", addr);
+    let listener = or_exit(
+        TcpListener::bind(&config.bind).await,
+        "Could not bind listener",
+    );
+    info!("listening on {}", config.bind);
     axum::serve(listener, app.into_make_service())
         .await
         .expect("failed to start server");
 }
 
-fn issuer_keys() -> IssuerKeys {
-    let sk_jwks = vec![
-        env::var("ISSMOCK_PRIV_OKP_ED25519"),
-        env::var("ISSMOCK_PRIV_EC_P384"),
-    ]
-    .into_iter()
-    .collect::<Result<Vec<String>, _>>();
-
-    let issuer_keys = sk_jwks
-        .map(|sk_jwks| {
-            info!("Using static issuer keys from ISSMOCK_PRIV_* env:");
-            IssuerKeys::new(&sk_jwks)
-        })
-        .unwrap_or_else(|_| {
-            info!("Using random issuer keys (not all ISSMOCK_PRIV_* envs are set):");
-            IssuerKeys::default()
-        });
+fn sqlite_store(path: Option<&str>) -> Option<Arc<SqliteStore>> {
+    let path = path?;
+    info!("Persisting states in SQLite database {}", path);
+    let store = or_exit(SqliteStore::open(path), "Failed to open SQLite database");
+    Some(Arc::new(store))
+}
+
+fn issuer_keys(static_keys: Option<IssuerKeys>, sqlite_store: Option<&SqliteStore>) -> IssuerKeys {
+    let stored_issuer_keys = sqlite_store.and_then(|store| {
+        or_exit(
+            store.load_issuer_keys(),
+            "Failed to load issuer keys from SQLite database",
+        )
+    });
+
+    let issuer_keys = match (static_keys, stored_issuer_keys) {
+        (Some(issuer_keys), _) => {
+            info!("Using static issuer keys from the configuration:");
+            issuer_keys
+        }
+        (None, Some(issuer_keys)) => {
+            info!("Using issuer keys from SQLite database:");
+            issuer_keys
+        }
+        (None, None) => {
+            info!("Using random issuer keys (no static keys are configured):");
+            let issuer_keys = IssuerKeys::default();
+            if let Some(store) = sqlite_store {
+                or_exit(
+                    store.save_issuer_keys(&issuer_keys),
+                    "Failed to save issuer keys to SQLite database",
+                );
+            }
+            issuer_keys
+        }
+    };
 
     for (_, vk) in issuer_keys.key_pairs() {
         info!("  {}", vk.to_did_key());
+        info!("  {}", vk.to_did_jwk());
     }
 
     issuer_keys
 }
+
+fn log_issuer_profiles(issuer_profiles: &IssuerProfiles) {
+    if issuer_profiles.is_empty() {
+        return;
+    }
+
+    info!("Issuer profiles:");
+    for profile in issuer_profiles.iter() {
+        info!(
+            "  /issuers/{} (issuer: {})",
+            profile.name(),
+            profile.issuer().unwrap_or("not set")
+        );
+        for (_, vk) in profile.issuer_keys().key_pairs() {
+            info!("    {}", vk.to_did_key());
+        }
+    }
+}
+
+fn issuer_profiles_with_status_lists(
+    issuer_profiles: &IssuerProfiles,
+    base_url: &str,
+    path: &str,
+    sqlite_store: Option<&Arc<SqliteStore>>,
+) -> IssuerProfiles {
+    let profiles = issuer_profiles.iter().map(|profile| {
+        let status_lists_url = format!(
+            "{}/issuers/{}{}",
+            base_url.trim_end_matches('/'),
+            profile.name(),
+            path
+        );
+        let status_lists = match sqlite_store {
+            Some(store) => or_exit(
+                StatusLists::with_store(
+                    status_lists_url,
+                    Arc::new(store.issuer_status_list_store(profile.name())),
+                ),
+                "Failed to load status lists of issuer profiles from SQLite database",
+            ),
+            None => StatusLists::new(status_lists_url),
+        }
+        .with_issuer(profile.issuer().map(ToString::to_string));
+        profile.clone().with_status_lists(Some(status_lists))
+    });
+    or_exit(
+        IssuerProfiles::new(profiles),
+        "Failed to register issuer profiles with status lists",
+    )
+}
+
+fn status_lists(base_url: &str, path: &str, sqlite_store: Option<Arc<SqliteStore>>) -> StatusLists {
+    let status_lists_url = format!("{}{}", base_url.trim_end_matches('/'), path);
+    info!(
+        "Status list credentials are published under {}",
+        status_lists_url
+    );
+
+    match sqlite_store {
+        Some(store) => or_exit(
+            StatusLists::with_store(status_lists_url, store),
+            "Failed to load status lists from SQLite database",
+        ),
+        None => StatusLists::new(status_lists_url),
+    }
+}
+
+/// Unwrap the result, or exit with the error since the server cannot start without it.
+fn or_exit<T, E: fmt::Display>(result: Result<T, E>, message: &str) -> T {
+    result.unwrap_or_else(|e| {
+        error!("{}: {:#}", message, e);
+        process::exit(1);
+    })
+}
@@ -0,0 +1,411 @@
+//! A file-backed storage with [SQLite](https://www.sqlite.org/).
+//!
+//! [`SqliteStore`] persists the following across restarts:
+//!
+//! - issued credentials ([`CredentialStore`])
+//! - status list bitstrings ([`StatusListStore`])
+//! - issuer keys
+//!
+//! Issuer keys are stored as private JWKs in plaintext. Do not use the database file with keys that need protection.
+
+use std::{
+    collections::HashMap,
+    path::Path,
+    str::FromStr,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use anyhow::Context;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::Value;
+
+use crate::{
+    credential_store::CredentialStore,
+    status_list::{StatusListStore, StatusListsState, StatusPurpose},
+    IssuerKeys,
+};
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS credentials (
+    id TEXT PRIMARY KEY,
+    credential TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS status_lists (
+    scope TEXT NOT NULL,
+    status_purpose TEXT NOT NULL,
+    bitstring BLOB NOT NULL,
+    PRIMARY KEY (scope, status_purpose)
+);
+CREATE TABLE IF NOT EXISTS status_list_indices (
+    scope TEXT NOT NULL,
+    credential_id TEXT NOT NULL,
+    status_list_index INTEGER NOT NULL,
+    PRIMARY KEY (scope, credential_id)
+);
+CREATE TABLE IF NOT EXISTS status_list_allocation (
+    scope TEXT PRIMARY KEY,
+    next_index INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS signing_keys (
+    position INTEGER PRIMARY KEY,
+    jwk TEXT NOT NULL
+);
+"#;
+
+/// A storage in an SQLite database file.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use vc_issuer_mock_core::{
+///     credential_store::DynCredentialStore, sqlite_store::SqliteStore, status_list::StatusLists,
+/// };
+///
+/// let store = Arc::new(SqliteStore::open("vc-issuer-mock.sqlite3").unwrap());
+/// let credential_store: DynCredentialStore = store.clone();
+/// let status_lists = StatusLists::with_store("http://localhost:40080/status-lists", store).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct SqliteStore {
+    conn: Mutex<Connection>,
+}
+
+/// [`StatusListStore`] for the status lists of an [`IssuerProfile`](crate::issuer_profile::IssuerProfile),
+/// in the database of a [`SqliteStore`].
+///
+/// [`SqliteStore`] itself stores the status lists of the default issuer.
+#[derive(Debug)]
+pub struct SqliteIssuerStatusListStore {
+    store: Arc<SqliteStore>,
+    scope: String,
+}
+
+/// Scope of the status lists of the default issuer.
+const DEFAULT_SCOPE: &str = "";
+
+impl SqliteStore {
+    /// Open (or create) the database file at `path`.
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open SQLite database {}", path.display()))?;
+        Self::init(conn)
+    }
+
+    /// Open an in-memory database. Mainly for testing.
+    pub fn open_in_memory() -> anyhow::Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> anyhow::Result<Self> {
+        conn.execute_batch(SCHEMA)
+            .context("Failed to create SQLite tables")?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Load the issuer keys saved by [`Self::save_issuer_keys`], if any.
+    pub fn load_issuer_keys(&self) -> anyhow::Result<Option<IssuerKeys>> {
+        let conn = self.lock();
+        let mut stmt = conn.prepare("SELECT jwk FROM signing_keys ORDER BY position")?;
+        let jwks = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if jwks.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(IssuerKeys::try_new(jwks)?))
+    }
+
+    /// Save the signing keys of `issuer_keys`, replacing the previous ones.
+    ///
+    /// They are saved as private JWKs in plaintext.
+    pub fn save_issuer_keys(&self, issuer_keys: &IssuerKeys) -> anyhow::Result<()> {
+        let mut conn = self.lock();
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM signing_keys", [])?;
+        for (position, (sk, _)) in issuer_keys.key_pairs().iter().enumerate() {
+            tx.execute(
+                "INSERT INTO signing_keys (position, jwk) VALUES (?1, ?2)",
+                params![position as i64, sk.to_private_jwk()],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// [`StatusListStore`] for the status lists of the issuer profile `name`.
+    pub fn issuer_status_list_store(self: &Arc<Self>, name: &str) -> SqliteIssuerStatusListStore {
+        SqliteIssuerStatusListStore {
+            store: self.clone(),
+            scope: format!("issuers/{}", name),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn load_status_lists(&self, scope: &str) -> anyhow::Result<Option<StatusListsState>> {
+        let conn = self.lock();
+
+        let Some(next_index) = conn
+            .query_row(
+                "SELECT next_index FROM status_list_allocation WHERE scope = ?1",
+                params![scope],
+                |row| row.get::<_, i64>(0),
+            )
+            .optional()?
+        else {
+            return Ok(None);
+        };
+
+        let mut stmt =
+            conn.prepare("SELECT status_purpose, bitstring FROM status_lists WHERE scope = ?1")?;
+        let bitstrings = stmt
+            .query_map(params![scope], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?))
+            })?
+            .map(|row| {
+                let (purpose, bitstring) = row?;
+                let purpose = StatusPurpose::from_str(&purpose)?;
+                Ok((purpose, bitstring))
+            })
+            .collect::<anyhow::Result<HashMap<_, _>>>()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT credential_id, status_list_index FROM status_list_indices WHERE scope = ?1",
+        )?;
+        let indices = stmt
+            .query_map(params![scope], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
+            })?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+
+        Ok(Some(StatusListsState {
+            next_index: next_index as usize,
+            bitstrings,
+            indices,
+        }))
+    }
+
+    fn save_status_list_allocation(
+        &self,
+        scope: &str,
+        credential_id: &str,
+        index: usize,
+        next_index: usize,
+    ) -> anyhow::Result<()> {
+        let mut conn = self.lock();
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            "INSERT INTO status_list_indices (scope, credential_id, status_list_index) VALUES (?1, ?2, ?3)",
+            params![scope, credential_id, index as i64],
+        )?;
+        tx.execute(
+            "INSERT OR REPLACE INTO status_list_allocation (scope, next_index) VALUES (?1, ?2)",
+            params![scope, next_index as i64],
+        )?;
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn save_status_list_bitstring(
+        &self,
+        scope: &str,
+        purpose: StatusPurpose,
+        bitstring: &[u8],
+    ) -> anyhow::Result<()> {
+        self.lock().execute(
+            "INSERT OR REPLACE INTO status_lists (scope, status_purpose, bitstring) VALUES (?1, ?2, ?3)",
+            params![scope, purpose.to_string(), bitstring],
+        )?;
+        Ok(())
+    }
+}
+
+impl CredentialStore for SqliteStore {
+    fn put(&self, id: &str, credential: Value) -> anyhow::Result<()> {
+        self.lock().execute(
+            "INSERT OR REPLACE INTO credentials (id, credential) VALUES (?1, ?2)",
+            params![id, credential.to_string()],
+        )?;
+        Ok(())
+    }
+
+    fn get(&self, id: &str) -> anyhow::Result<Option<Value>> {
+        let credential = self
+            .lock()
+            .query_row(
+                "SELECT credential FROM credentials WHERE id = ?1",
+                params![id],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?;
+
+        credential
+            .map(|credential| serde_json::from_str(&credential))
+            .transpose()
+            .context("Stored credential is not a valid JSON")
+    }
+
+    fn delete(&self, id: &str) -> anyhow::Result<bool> {
+        let deleted = self
+            .lock()
+            .execute("DELETE FROM credentials WHERE id = ?1", params![id])?;
+        Ok(deleted > 0)
+    }
+}
+
+impl StatusListStore for SqliteStore {
+    fn load(&self) -> anyhow::Result<Option<StatusListsState>> {
+        self.load_status_lists(DEFAULT_SCOPE)
+    }
+
+    fn save_allocation(
+        &self,
+        credential_id: &str,
+        index: usize,
+        next_index: usize,
+    ) -> anyhow::Result<()> {
+        self.save_status_list_allocation(DEFAULT_SCOPE, credential_id, index, next_index)
+    }
+
+    fn save_bitstring(&self, purpose: StatusPurpose, bitstring: &[u8]) -> anyhow::Result<()> {
+        self.save_status_list_bitstring(DEFAULT_SCOPE, purpose, bitstring)
+    }
+}
+
+impl StatusListStore for SqliteIssuerStatusListStore {
+    fn load(&self) -> anyhow::Result<Option<StatusListsState>> {
+        self.store.load_status_lists(&self.scope)
+    }
+
+    fn save_allocation(
+        &self,
+        credential_id: &str,
+        index: usize,
+        next_index: usize,
+    ) -> anyhow::Result<()> {
+        self.store
+            .save_status_list_allocation(&self.scope, credential_id, index, next_index)
+    }
+
+    fn save_bitstring(&self, purpose: StatusPurpose, bitstring: &[u8]) -> anyhow::Result<()> {
+        self.store
+            .save_status_list_bitstring(&self.scope, purpose, bitstring)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::test_jwks::{ISSMOCK_PRIV_EC_P384, ISSMOCK_PRIV_OKP_ED25519};
+
+    use super::*;
+
+    #[test]
+    fn test_credential_store_success() -> anyhow::Result<()> {
+        let store = SqliteStore::open_in_memory()?;
+        assert_eq!(store.get("urn:uuid:1")?, None);
+
+        store.put("urn:uuid:1", json!({ "id": "urn:uuid:1" }))?;
+        assert_eq!(
+            store.get("urn:uuid:1")?,
+            Some(json!({ "id": "urn:uuid:1" }))
+        );
+
+        assert!(store.delete("urn:uuid:1")?);
+        assert!(!store.delete("urn:uuid:1")?);
+        assert_eq!(store.get("urn:uuid:1")?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_status_list_store_success() -> anyhow::Result<()> {
+        let store = SqliteStore::open_in_memory()?;
+        assert_eq!(store.load()?, None);
+
+        store.save_allocation("urn:uuid:1", 0, 1)?;
+        store.save_allocation("urn:uuid:2", 1, 2)?;
+        store.save_bitstring(StatusPurpose::Revocation, &[0b1000_0000, 0])?;
+        store.save_bitstring(StatusPurpose::Suspension, &[0, 0b0000_0001])?;
+        store.save_bitstring(StatusPurpose::Suspension, &[0, 0b0000_0011])?;
+
+        let state = StatusListsState {
+            next_index: 2,
+            bitstrings: HashMap::from([
+                (StatusPurpose::Revocation, vec![0b1000_0000, 0]),
+                (StatusPurpose::Suspension, vec![0, 0b0000_0011]),
+            ]),
+            indices: HashMap::from([("urn:uuid:1".to_string(), 0), ("urn:uuid:2".to_string(), 1)]),
+        };
+        assert_eq!(store.load()?, Some(state));
+
+        // an index is never allocated twice for a credential
+        assert!(store.save_allocation("urn:uuid:1", 2, 3).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_issuer_status_list_store_success() -> anyhow::Result<()> {
+        let store = Arc::new(SqliteStore::open_in_memory()?);
+        let bank = store.issuer_status_list_store("bank");
+        assert_eq!(bank.load()?, None);
+
+        // the same credential id in different scopes
+        store.save_allocation("urn:uuid:1", 0, 1)?;
+        bank.save_allocation("urn:uuid:1", 0, 1)?;
+        bank.save_bitstring(StatusPurpose::Revocation, &[0b1000_0000])?;
+
+        let state = bank.load()?.expect("should be saved");
+        assert_eq!(state.bitstrings.len(), 1);
+        assert_eq!(state.indices["urn:uuid:1"], 0);
+        assert!(store
+            .load()?
+            .expect("should be saved")
+            .bitstrings
+            .is_empty());
+        assert_eq!(store.issuer_status_list_store("government").load()?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_issuer_keys_success() -> anyhow::Result<()> {
+        let store = SqliteStore::open_in_memory()?;
+        assert!(store.load_issuer_keys()?.is_none());
+
+        let issuer_keys = IssuerKeys::new(vec![ISSMOCK_PRIV_OKP_ED25519, ISSMOCK_PRIV_EC_P384]);
+        store.save_issuer_keys(&issuer_keys)?;
+
+        let loaded = store.load_issuer_keys()?.expect("should be saved");
+        assert_eq!(loaded.key_pairs(), issuer_keys.key_pairs());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_open_success_persisted() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(format!(
+            "vc-issuer-mock-test-{}.sqlite3",
+            uuid::Uuid::new_v4()
+        ));
+
+        SqliteStore::open(&path)?.put("urn:uuid:1", json!({ "id": "urn:uuid:1" }))?;
+        let credential = SqliteStore::open(&path)?.get("urn:uuid:1")?;
+        std::fs::remove_file(&path)?;
+
+        assert_eq!(credential, Some(json!({ "id": "urn:uuid:1" })));
+        Ok(())
+    }
+}
@@ -0,0 +1,846 @@
+//! [Bitstring Status List](https://www.w3.org/TR/vc-bitstring-status-list/) held by the issuer.
+//!
+//! Every VCDM v2.0 credential issued while [`StatusLists`] is available gets an index in the status lists,
+//! which is referenced from the `BitstringStatusListEntry`s injected into its `credentialStatus`.
+//! The same index is used for all of the status purposes.
+//!
+//! SD-JWT VCs and VCDM v1.1 credentials do not get `credentialStatus`.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    io::Write,
+    str::FromStr,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use anyhow::{anyhow, bail};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{SecondsFormat, Utc};
+use derive_more::Display;
+use flate2::{write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    did_web::DidWeb,
+    endpoints::vc_api::{
+        credentials::create_vc_with_data_integrity,
+        req::IssueRequestOptions,
+        res::{
+            vc_api_error::custom_problem_types::CustomProblemType, VerifiableCredentialV2,
+            VerifiableCredentialV2DataIntegrity,
+        },
+    },
+    vcdm_v2::problem_details::{PredefinedProblemType, ProblemDetails},
+    verification_method::CustomVerificationMethodResolver,
+    IssuerKeys,
+};
+
+/// Number of entries in a status list.
+///
+/// <https://www.w3.org/TR/vc-bitstring-status-list/#bitstring-generation-algorithm>
+/// > The bitstring MUST be a minimum of 16KB in size.
+pub const STATUS_LIST_LENGTH: usize = 16 * 1024 * 8;
+
+/// [`statusPurpose`](https://www.w3.org/TR/vc-bitstring-status-list/#bitstringstatuslistentry) supported by the issuer.
+#[derive(Clone, Copy, Debug, Display, Eq, PartialEq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StatusPurpose {
+    /// Permanently invalidates the credential.
+    #[display(fmt = "revocation")]
+    Revocation,
+    /// Temporarily invalidates the credential.
+    #[display(fmt = "suspension")]
+    Suspension,
+}
+
+impl StatusPurpose {
+    /// All of the status purposes. Each issued credential has an entry for each of them.
+    pub const ALL: [StatusPurpose; 2] = [StatusPurpose::Revocation, StatusPurpose::Suspension];
+}
+
+impl FromStr for StatusPurpose {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "revocation" => Ok(StatusPurpose::Revocation),
+            "suspension" => Ok(StatusPurpose::Suspension),
+            _ => bail!("Unknown status purpose: {}", s),
+        }
+    }
+}
+
+/// Status lists shared among the endpoints.
+///
+/// Status list credentials are published at `{base_url}/{statusPurpose}`
+/// (e.g. `http://localhost:40080/status-lists/revocation`).
+/// They are issued by [`Self::with_issuer`], or the `did:key` of an issuer key by default.
+///
+/// # Example
+///
+/// ```
+/// use vc_issuer_mock_core::status_list::StatusLists;
+///
+/// let status_lists = StatusLists::new("http://localhost:40080/status-lists");
+/// ```
+#[derive(Clone, Debug)]
+pub struct StatusLists {
+    base_url: String,
+    /// `issuer` of the status list credentials.
+    issuer: Option<String>,
+    inner: Arc<Mutex<StatusListsInner>>,
+    store: Option<Arc<dyn StatusListStore>>,
+}
+
+/// A storage which persists [`StatusLists`] across restarts.
+///
+/// Changes are saved one by one, so that the cost of saving does not grow with the number of issued credentials.
+pub trait StatusListStore: fmt::Debug + Send + Sync {
+    /// Load the saved state, if any.
+    fn load(&self) -> anyhow::Result<Option<StatusListsState>>;
+
+    /// Save `index` allocated for `credential_id`, and `next_index` to be allocated next.
+    fn save_allocation(
+        &self,
+        credential_id: &str,
+        index: usize,
+        next_index: usize,
+    ) -> anyhow::Result<()>;
+
+    /// Save the bitstring (not encoded) of `purpose`, replacing the previous one.
+    fn save_bitstring(&self, purpose: StatusPurpose, bitstring: &[u8]) -> anyhow::Result<()>;
+}
+
+/// State of [`StatusLists`] to be persisted by [`StatusListStore`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StatusListsState {
+    /// Index to be allocated next.
+    pub next_index: usize,
+    /// Bitstring (not encoded) of each status list.
+    pub bitstrings: HashMap<StatusPurpose, Vec<u8>>,
+    /// Credential identifier -> allocated index.
+    pub indices: HashMap<String, usize>,
+}
+
+#[derive(Debug)]
+struct StatusListsInner {
+    next_index: usize,
+    lists: HashMap<StatusPurpose, StatusList>,
+    /// Credential `id` -> allocated index.
+    indices: HashMap<String, usize>,
+}
+
+#[derive(Debug)]
+struct StatusList {
+    bitstring: Bitstring,
+    /// Incremented on every change of `bitstring`.
+    version: u64,
+    /// Signed status list credential of `version`.
+    credential: Option<VerifiableCredentialV2DataIntegrity>,
+}
+
+impl StatusLists {
+    /// Create empty status lists published under `base_url`.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        let lists = StatusPurpose::ALL
+            .into_iter()
+            .map(|purpose| {
+                let list = StatusList {
+                    bitstring: Bitstring::new(),
+                    version: 0,
+                    credential: None,
+                };
+                (purpose, list)
+            })
+            .collect();
+
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            issuer: None,
+            inner: Arc::new(Mutex::new(StatusListsInner {
+                next_index: 0,
+                lists,
+                indices: HashMap::new(),
+            })),
+            store: None,
+        }
+    }
+
+    /// Create status lists persisted in `store`. The state saved last time is restored, if any.
+    pub fn with_store(
+        base_url: impl Into<String>,
+        store: Arc<dyn StatusListStore>,
+    ) -> anyhow::Result<Self> {
+        let mut status_lists = Self::new(base_url);
+
+        if let Some(state) = store.load()? {
+            let mut inner = status_lists.lock();
+            inner.next_index = state.next_index;
+            inner.indices = state.indices;
+            for (purpose, bitstring) in state.bitstrings {
+                if bitstring.len() != STATUS_LIST_LENGTH / 8 {
+                    bail!(
+                        "Stored {} status list has {} bytes, expected {}",
+                        purpose,
+                        bitstring.len(),
+                        STATUS_LIST_LENGTH / 8
+                    );
+                }
+                inner
+                    .lists
+                    .get_mut(&purpose)
+                    .expect("all status purposes have a list")
+                    .bitstring = Bitstring(bitstring);
+            }
+        }
+
+        status_lists.store = Some(store);
+        Ok(status_lists)
+    }
+
+    /// Issue the status list credentials as `issuer` (e.g. the issuer of an
+    /// [`IssuerProfile`](crate::issuer_profile::IssuerProfile)), instead of the `did:key` of an issuer key.
+    ///
+    /// `issuer` must be resolved to a key given to [`Self::sign`].
+    pub fn with_issuer(mut self, issuer: Option<String>) -> Self {
+        self.issuer = issuer;
+        self
+    }
+
+    /// URL of the status list credential for `purpose`.
+    pub fn status_list_credential_url(&self, purpose: StatusPurpose) -> String {
+        format!("{}/{}", self.base_url, purpose)
+    }
+
+    /// Whether `credential` already has `credentialStatus` (e.g. specified by the client).
+    pub(crate) fn has_credential_status(
+        credential: &VerifiableCredentialV2,
+    ) -> Result<bool, ProblemDetails> {
+        let value = serde_json::to_value(credential).map_err(unknown_error)?;
+        Ok(value.get("credentialStatus").is_some())
+    }
+
+    /// Allocate an index for `credential` and add `BitstringStatusListEntry`s to its `credentialStatus`.
+    ///
+    /// The index is looked up by `credential_id` in [`Self::update_status`], so it must not be used by another
+    /// credential. `credentialStatus` specified by the client is kept as is.
+    pub(crate) fn add_credential_status(
+        &self,
+        credential: &VerifiableCredentialV2,
+        credential_id: &str,
+    ) -> Result<VerifiableCredentialV2, ProblemDetails> {
+        let mut value = serde_json::to_value(credential).map_err(unknown_error)?;
+        if value.get("credentialStatus").is_some() {
+            return Ok(credential.clone());
+        }
+
+        let index = self.allocate(credential_id)?;
+        let entries = StatusPurpose::ALL
+            .into_iter()
+            .map(|purpose| self.status_list_entry(purpose, index))
+            .collect::<Vec<_>>();
+        value["credentialStatus"] = Value::Array(entries);
+
+        serde_json::from_value(value).map_err(unknown_error)
+    }
+
+    /// Set the status of the credential identified by `credential_id`.
+    ///
+    /// The status list credential for `purpose` needs to be signed again with [`Self::sign`] after this.
+    pub(crate) fn update_status(
+        &self,
+        credential_id: &str,
+        purpose: StatusPurpose,
+        status: bool,
+    ) -> Result<(), ProblemDetails> {
+        self.update_statuses(credential_id, &[(purpose, status)])
+    }
+
+    /// Set the statuses of the credential identified by `credential_id`, in order.
+    ///
+    /// All updates are validated before any is applied, so nothing changes if one of them is rejected.
+    /// The status list credentials for the updated purposes need to be signed again with [`Self::sign`] after this.
+    pub(crate) fn update_statuses(
+        &self,
+        credential_id: &str,
+        updates: &[(StatusPurpose, bool)],
+    ) -> Result<(), ProblemDetails> {
+        let mut inner = self.lock();
+
+        let index = *inner.indices.get(credential_id).ok_or_else(|| {
+            ProblemDetails::new(
+                CustomProblemType::CredentialNotFoundError,
+                "credential not found".to_string(),
+                format!("No status entry found for credential `{}`.", credential_id),
+                anyhow!("No status entry found for credential `{}`", credential_id),
+            )
+        })?;
+
+        let mut new_statuses = HashMap::new();
+        for &(purpose, status) in updates {
+            let current = new_statuses
+                .get(&purpose)
+                .copied()
+                .unwrap_or_else(|| inner.lists[&purpose].bitstring.get(index));
+
+            // <https://www.w3.org/TR/vc-bitstring-status-list/#bitstringstatuslistentry>
+            // > revocation: Used to cancel the validity of a verifiable credential. This status is not reversible.
+            if purpose == StatusPurpose::Revocation && current && !status {
+                return Err(ProblemDetails::new(
+                    PredefinedProblemType::MalformedValueError,
+                    "revocation is not reversible".to_string(),
+                    format!("Credential `{}` has already been revoked.", credential_id),
+                    anyhow!("Credential `{}` has already been revoked", credential_id),
+                ));
+            }
+            new_statuses.insert(purpose, status);
+        }
+
+        for (purpose, status) in new_statuses {
+            let list = inner
+                .lists
+                .get_mut(&purpose)
+                .expect("all status purposes have a list");
+            if list.bitstring.get(index) != status {
+                list.bitstring.set(index, status);
+                list.version += 1;
+                list.credential = None;
+                let bitstring = &list.bitstring.0;
+                self.persist(|store| store.save_bitstring(purpose, bitstring))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The signed status list credential for `purpose`, if it is up to date.
+    pub fn signed_credential(
+        &self,
+        purpose: StatusPurpose,
+    ) -> Option<VerifiableCredentialV2DataIntegrity> {
+        self.lock().lists[&purpose].credential.clone()
+    }
+
+    /// Sign the current status list for `purpose` and keep it as the published status list credential.
+    ///
+    /// `did_web` resolves the issuer if it is a `did:web` hosted by the mock.
+    pub async fn sign(
+        &self,
+        issuer_keys: &IssuerKeys,
+        did_web: Option<&DidWeb>,
+        purpose: StatusPurpose,
+    ) -> Result<VerifiableCredentialV2DataIntegrity, ProblemDetails> {
+        let (version, encoded_list) = {
+            let inner = self.lock();
+            let list = &inner.lists[&purpose];
+            (list.version, list.bitstring.encode())
+        };
+
+        let url = self.status_list_credential_url(purpose);
+        let issuer = match &self.issuer {
+            Some(issuer) => issuer.clone(),
+            None => issuer_keys.preferred_did_key(),
+        };
+        let credential: VerifiableCredentialV2 = serde_json::from_value(json!({
+            "@context": ["https://www.w3.org/ns/credentials/v2"],
+            "id": url,
+            "type": ["VerifiableCredential", "BitstringStatusListCredential"],
+            "issuer": issuer,
+            "validFrom": Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            "credentialSubject": {
+                "id": format!("{}#list", url),
+                "type": "BitstringStatusList",
+                "statusPurpose": purpose,
+                "encodedList": encoded_list,
+            },
+        }))
+        .map_err(unknown_error)?;
+
+        let vm_resolver = CustomVerificationMethodResolver::new(issuer_keys.clone())
+            .with_did_web(did_web.cloned());
+        let vm = vm_resolver.resolve(&credential.issuer).await?;
+        let vc = create_vc_with_data_integrity(
+            credential,
+            &IssueRequestOptions::default(),
+            issuer_keys.clone(),
+            &vm,
+            &vm_resolver,
+        )
+        .await?;
+
+        // The list might have been updated while signing. Then the newer one will be signed later.
+        let mut inner = self.lock();
+        let list = inner
+            .lists
+            .get_mut(&purpose)
+            .expect("all status purposes have a list");
+        if list.version == version {
+            list.credential = Some(vc.clone());
+        }
+
+        Ok(vc)
+    }
+
+    fn allocate(&self, credential_id: &str) -> Result<usize, ProblemDetails> {
+        let mut inner = self.lock();
+
+        if inner.indices.contains_key(credential_id) {
+            return Err(ProblemDetails::new(
+                PredefinedProblemType::MalformedValueError,
+                "duplicate credential id".to_string(),
+                format!(
+                    "Credential `{}` already has a status entry. Use another id.",
+                    credential_id
+                ),
+                anyhow!("Credential `{}` already has a status entry", credential_id),
+            ));
+        }
+
+        let index = inner.next_index;
+        if index >= STATUS_LIST_LENGTH {
+            return Err(ProblemDetails::new(
+                PredefinedProblemType::RangeError,
+                "status list is full".to_string(),
+                format!(
+                    "All of the {} entries in the status list are already allocated.",
+                    STATUS_LIST_LENGTH
+                ),
+                anyhow!("Status list is full"),
+            ));
+        }
+        // Saved first, so that the index is never reused after a restart.
+        self.persist(|store| store.save_allocation(credential_id, index, index + 1))?;
+        inner.next_index += 1;
+        inner.indices.insert(credential_id.to_string(), index);
+        Ok(index)
+    }
+
+    fn persist(
+        &self,
+        save: impl FnOnce(&dyn StatusListStore) -> anyhow::Result<()>,
+    ) -> Result<(), ProblemDetails> {
+        let Some(store) = &self.store else {
+            return Ok(());
+        };
+
+        save(store.as_ref()).map_err(|e| {
+            ProblemDetails::new(
+                CustomProblemType::UnknownError,
+                "status list error".to_string(),
+                e.to_string(),
+                e,
+            )
+        })
+    }
+
+    fn status_list_entry(&self, purpose: StatusPurpose, index: usize) -> Value {
+        let url = self.status_list_credential_url(purpose);
+        json!({
+            "id": format!("{}#{}", url, index),
+            "type": "BitstringStatusListEntry",
+            "statusPurpose": purpose,
+            "statusListIndex": index.to_string(),
+            "statusListCredential": url,
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, StatusListsInner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn unknown_error(e: serde_json::Error) -> ProblemDetails {
+    ProblemDetails::new(
+        CustomProblemType::UnknownError,
+        "status list error".to_string(),
+        e.to_string(),
+        anyhow!(e),
+    )
+}
+
+/// A bitstring whose leftmost bit is at index 0.
+#[derive(Clone, Debug)]
+struct Bitstring(Vec<u8>);
+
+impl Bitstring {
+    fn new() -> Self {
+        Self(vec![0; STATUS_LIST_LENGTH / 8])
+    }
+
+    fn get(&self, index: usize) -> bool {
+        self.0[index / 8] & (0x80 >> (index % 8)) != 0
+    }
+
+    fn set(&mut self, index: usize, value: bool) {
+        if value {
+            self.0[index / 8] |= 0x80 >> (index % 8);
+        } else {
+            self.0[index / 8] &= !(0x80 >> (index % 8));
+        }
+    }
+
+    /// `encodedList`: GZIP-compressed, multibase-encoded (base64url with no padding) bitstring.
+    ///
+    /// <https://www.w3.org/TR/vc-bitstring-status-list/#bitstring-generation-algorithm>
+    fn encode(&self) -> String {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(&self.0)
+            .expect("writing into Vec never fails");
+        let compressed = encoder.finish().expect("writing into Vec never fails");
+        format!("u{}", URL_SAFE_NO_PAD.encode(compressed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use flate2::read::GzDecoder;
+    use ssi::JWK;
+
+    use crate::{
+        endpoints::vc_api::req::IssueRequest,
+        issuer_keys::VerificationKey,
+        test_jwks::{ISSMOCK_PRIV_EC_P384, ISSMOCK_PRIV_OKP_ED25519, ISSMOCK_PUB_OKP_ED25519},
+        test_tracing::init_tracing,
+        test_vc_json::vc_data_model_2_0_test_suite::README_ALUMNI,
+        vcdm_v2::problem_details::ProblemType as _,
+    };
+
+    use super::*;
+
+    const BASE_URL: &str = "http://localhost:40080/status-lists";
+
+    fn decode(encoded_list: &str) -> Vec<u8> {
+        let compressed = URL_SAFE_NO_PAD
+            .decode(encoded_list.strip_prefix('u').unwrap())
+            .unwrap();
+        let mut bitstring = Vec::new();
+        GzDecoder::new(compressed.as_slice())
+            .read_to_end(&mut bitstring)
+            .unwrap();
+        bitstring
+    }
+
+    #[test]
+    fn test_bitstring_set_success() {
+        let mut bitstring = Bitstring::new();
+        bitstring.set(0, true);
+        bitstring.set(9, true);
+        assert!(bitstring.get(0));
+        assert!(!bitstring.get(1));
+        assert!(bitstring.get(9));
+        assert_eq!(&bitstring.0[..2], &[0b1000_0000, 0b0100_0000]);
+
+        bitstring.set(9, false);
+        assert!(!bitstring.get(9));
+    }
+
+    #[test]
+    fn test_bitstring_encode_success() {
+        let mut bitstring = Bitstring::new();
+        bitstring.set(STATUS_LIST_LENGTH - 1, true);
+
+        let decoded = decode(&bitstring.encode());
+        assert_eq!(decoded.len(), STATUS_LIST_LENGTH / 8);
+        assert_eq!(decoded.last(), Some(&0b0000_0001));
+    }
+
+    #[test]
+    fn test_add_credential_status_success() -> anyhow::Result<()> {
+        init_tracing();
+
+        let req: IssueRequest = serde_json::from_str(README_ALUMNI)?;
+        let credential = req.credential.as_v2().unwrap();
+
+        let status_lists = StatusLists::new(format!("{}/", BASE_URL));
+        let _ = status_lists.add_credential_status(credential, "urn:uuid:other")?;
+        let credential =
+            status_lists.add_credential_status(credential, req.credential_id().unwrap())?;
+
+        let json = serde_json::to_value(&credential)?;
+        let entries = json["credentialStatus"].as_array().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[0],
+            json!({
+                "id": format!("{}/revocation#1", BASE_URL),
+                "type": "BitstringStatusListEntry",
+                "statusPurpose": "revocation",
+                "statusListIndex": "1",
+                "statusListCredential": format!("{}/revocation", BASE_URL),
+            })
+        );
+        assert_eq!(entries[1]["statusPurpose"], "suspension");
+        assert_eq!(
+            status_lists.lock().indices["http://university.example/credentials/1872"],
+            1
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_credential_status_error_full() -> anyhow::Result<()> {
+        init_tracing();
+
+        let req: IssueRequest = serde_json::from_str(README_ALUMNI)?;
+        let credential = req.credential.as_v2().unwrap();
+
+        let status_lists = StatusLists::new(BASE_URL);
+        status_lists.lock().next_index = STATUS_LIST_LENGTH;
+
+        let problem_details = status_lists
+            .add_credential_status(credential, "urn:uuid:full")
+            .unwrap_err();
+        assert_eq!(
+            problem_details.code().unwrap(),
+            PredefinedProblemType::RangeError.code()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_credential_status_error_duplicate_id() -> anyhow::Result<()> {
+        init_tracing();
+
+        let req: IssueRequest = serde_json::from_str(README_ALUMNI)?;
+        let credential = req.credential.as_v2().unwrap();
+        let id = req.credential_id().unwrap();
+
+        let status_lists = StatusLists::new(BASE_URL);
+        status_lists.add_credential_status(credential, id)?;
+        let problem_details = status_lists
+            .add_credential_status(credential, id)
+            .unwrap_err();
+        assert_eq!(
+            problem_details.code().unwrap(),
+            PredefinedProblemType::MalformedValueError.code()
+        );
+        // the first mapping is kept, and no index is wasted
+        let inner = status_lists.lock();
+        assert_eq!(inner.indices[id], 0);
+        assert_eq!(inner.next_index, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_status_success() -> anyhow::Result<()> {
+        init_tracing();
+
+        let req: IssueRequest = serde_json::from_str(README_ALUMNI)?;
+        let status_lists = StatusLists::new(BASE_URL);
+        status_lists.add_credential_status(
+            req.credential.as_v2().unwrap(),
+            req.credential_id().unwrap(),
+        )?;
+
+        let id = "http://university.example/credentials/1872";
+        status_lists.update_status(id, StatusPurpose::Suspension, true)?;
+        {
+            let inner = status_lists.lock();
+            assert!(inner.lists[&StatusPurpose::Suspension].bitstring.get(0));
+            assert_eq!(inner.lists[&StatusPurpose::Suspension].version, 1);
+            assert!(!inner.lists[&StatusPurpose::Revocation].bitstring.get(0));
+        }
+
+        status_lists.update_status(id, StatusPurpose::Suspension, false)?;
+        assert!(!status_lists.lock().lists[&StatusPurpose::Suspension]
+            .bitstring
+            .get(0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_status_error_revert_revocation() -> anyhow::Result<()> {
+        init_tracing();
+
+        let req: IssueRequest = serde_json::from_str(README_ALUMNI)?;
+        let status_lists = StatusLists::new(BASE_URL);
+        status_lists.add_credential_status(
+            req.credential.as_v2().unwrap(),
+            req.credential_id().unwrap(),
+        )?;
+
+        let id = "http://university.example/credentials/1872";
+        status_lists.update_status(id, StatusPurpose::Revocation, true)?;
+        let problem_details = status_lists
+            .update_status(id, StatusPurpose::Revocation, false)
+            .unwrap_err();
+        assert_eq!(
+            problem_details.code().unwrap(),
+            PredefinedProblemType::MalformedValueError.code()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_statuses_error_nothing_applied() -> anyhow::Result<()> {
+        init_tracing();
+
+        let req: IssueRequest = serde_json::from_str(README_ALUMNI)?;
+        let status_lists = StatusLists::new(BASE_URL);
+        status_lists.add_credential_status(
+            req.credential.as_v2().unwrap(),
+            req.credential_id().unwrap(),
+        )?;
+
+        let id = "http://university.example/credentials/1872";
+        status_lists.update_status(id, StatusPurpose::Revocation, true)?;
+        for updates in [
+            // a valid suspension followed by an un-revocation
+            vec![
+                (StatusPurpose::Suspension, true),
+                (StatusPurpose::Revocation, false),
+            ],
+            // revocation is not reversible within a batch either
+            vec![
+                (StatusPurpose::Suspension, true),
+                (StatusPurpose::Revocation, true),
+                (StatusPurpose::Revocation, false),
+            ],
+        ] {
+            let problem_details = status_lists.update_statuses(id, &updates).unwrap_err();
+            assert_eq!(
+                problem_details.code().unwrap(),
+                PredefinedProblemType::MalformedValueError.code()
+            );
+
+            let inner = status_lists.lock();
+            assert!(!inner.lists[&StatusPurpose::Suspension].bitstring.get(0));
+            assert_eq!(inner.lists[&StatusPurpose::Suspension].version, 0);
+            assert!(inner.lists[&StatusPurpose::Revocation].bitstring.get(0));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_status_error_unknown_credential() {
+        init_tracing();
+
+        let status_lists = StatusLists::new(BASE_URL);
+        let problem_details = status_lists
+            .update_status("urn:uuid:unknown", StatusPurpose::Revocation, true)
+            .unwrap_err();
+        assert_eq!(
+            problem_details.code().unwrap(),
+            CustomProblemType::CredentialNotFoundError.code()
+        );
+    }
+
+    /// [`StatusListStore`] keeping the saved state in memory.
+    #[derive(Debug, Default)]
+    struct TestStatusListStore(Mutex<Option<StatusListsState>>);
+
+    impl StatusListStore for TestStatusListStore {
+        fn load(&self) -> anyhow::Result<Option<StatusListsState>> {
+            Ok(self.0.lock().unwrap().clone())
+        }
+
+        fn save_allocation(
+            &self,
+            credential_id: &str,
+            index: usize,
+            next_index: usize,
+        ) -> anyhow::Result<()> {
+            let mut state = self.0.lock().unwrap();
+            let state = state.get_or_insert_with(StatusListsState::default);
+            state.indices.insert(credential_id.to_string(), index);
+            state.next_index = next_index;
+            Ok(())
+        }
+
+        fn save_bitstring(&self, purpose: StatusPurpose, bitstring: &[u8]) -> anyhow::Result<()> {
+            let mut state = self.0.lock().unwrap();
+            let state = state.get_or_insert_with(StatusListsState::default);
+            state.bitstrings.insert(purpose, bitstring.to_vec());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_with_store_success_restore() -> anyhow::Result<()> {
+        init_tracing();
+
+        let store = Arc::new(TestStatusListStore::default());
+        let req: IssueRequest = serde_json::from_str(README_ALUMNI)?;
+        let id = "http://university.example/credentials/1872";
+
+        let status_lists = StatusLists::with_store(BASE_URL, store.clone())?;
+        status_lists.add_credential_status(
+            req.credential.as_v2().unwrap(),
+            req.credential_id().unwrap(),
+        )?;
+        status_lists.update_status(id, StatusPurpose::Revocation, true)?;
+
+        // Restart
+        let status_lists = StatusLists::with_store(BASE_URL, store)?;
+        let inner = status_lists.lock();
+        assert_eq!(inner.next_index, 1);
+        assert_eq!(inner.indices[id], 0);
+        assert!(inner.lists[&StatusPurpose::Revocation].bitstring.get(0));
+        assert!(!inner.lists[&StatusPurpose::Suspension].bitstring.get(0));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sign_success() -> anyhow::Result<()> {
+        init_tracing();
+
+        let issuer_keys = IssuerKeys::new(vec![ISSMOCK_PRIV_EC_P384, ISSMOCK_PRIV_OKP_ED25519]);
+        let status_lists = StatusLists::new(BASE_URL);
+        assert!(status_lists
+            .signed_credential(StatusPurpose::Revocation)
+            .is_none());
+
+        status_lists
+            .sign(&issuer_keys, None, StatusPurpose::Revocation)
+            .await?;
+        let vc = status_lists
+            .signed_credential(StatusPurpose::Revocation)
+            .expect("should be signed");
+        assert_eq!(vc.proofs.iter().count(), 1);
+
+        let json = serde_json::to_value(&vc)?;
+        assert_eq!(json["id"], format!("{}/revocation", BASE_URL));
+        assert_eq!(
+            json["issuer"],
+            VerificationKey::try_from(&serde_json::from_str::<JWK>(ISSMOCK_PUB_OKP_ED25519)?)?
+                .to_did_key()
+        );
+        assert_eq!(json["credentialSubject"]["statusPurpose"], "revocation");
+        let decoded = decode(json["credentialSubject"]["encodedList"].as_str().unwrap());
+        assert!(decoded.iter().all(|b| *b == 0));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sign_success_with_issuer() -> anyhow::Result<()> {
+        init_tracing();
+
+        let issuer_keys = IssuerKeys::new(vec![ISSMOCK_PRIV_EC_P384]);
+        let did_web = DidWeb::from_base_url("http://localhost:40080")?;
+        let status_lists = StatusLists::new(BASE_URL).with_issuer(Some(did_web.did().to_string()));
+
+        let vc = status_lists
+            .sign(&issuer_keys, Some(&did_web), StatusPurpose::Suspension)
+            .await?;
+        let json = serde_json::to_value(&vc)?;
+        assert_eq!(json["issuer"], "did:web:localhost%3A40080");
+        assert!(json["proof"]["verificationMethod"]
+            .as_str()
+            .unwrap()
+            .starts_with("did:web:localhost%3A40080#"));
+
+        Ok(())
+    }
+}
@@ -57,10 +57,24 @@ pub const JWK_OKP_ED25519_PUB: &str = r#"{
 
 /// Same one used in `docker/vc-issuer-mock-core/env`.
 pub const ISSMOCK_PRIV_OKP_ED25519: &str = r#"{"kty":"OKP","use":"sig","crv":"Ed25519","d":"uACpBLNoNFWd70B2I-Dq41LS6YfBsaMN609VQcynLbc","x":"RP2fVkXQcK7ZARnqeLMyJAU5Nje03RT0pd7Eqwn6_f8"}"#;
+pub const ISSMOCK_PUB_OKP_ED25519: &str = r#"{"kty":"OKP","use":"sig","crv":"Ed25519","x":"RP2fVkXQcK7ZARnqeLMyJAU5Nje03RT0pd7Eqwn6_f8"}"#;
 pub const ISSMOCK_PRIV_OKP_ED25519_DIDKEY: &str =
     "did:key:z6Mkj6a5Em4zUEqJMdmSjyUk3dBz5SEt2xtjtUmfmunTxS62";
 
 /// Same one used in `docker/vc-issuer-mock-core/env`.
 pub const ISSMOCK_PRIV_EC_P384: &str = r#"{"kty":"EC","crv":"P-384","d":"G4DfV3HrerhDlTrVWJgbJ3njPCMXFrkuqYn-_0LmbYovhtUWHpicFjzMR27wMdFL","x":"8NVw26mAY930CF9L0Y2mBvtuLD89TAKjp22eWwHGGuos0UTUZxVoolYy-o168U6G","y":"QUCWiWwQAD2chrwhT2Z-fiMCAuVBzktVpYFjg6eztkQZW8u4pQtug67oZxuSxaOK"}"#;
+pub const ISSMOCK_PUB_EC_P384: &str = r#"{"kty":"EC","crv":"P-384","x":"8NVw26mAY930CF9L0Y2mBvtuLD89TAKjp22eWwHGGuos0UTUZxVoolYy-o168U6G","y":"QUCWiWwQAD2chrwhT2Z-fiMCAuVBzktVpYFjg6eztkQZW8u4pQtug67oZxuSxaOK"}"#;
 pub const ISSMOCK_PRIV_EC_P384_DIDKEY: &str =
     "did:key:z82LkuMX2ytXFQNhevUBGhCwC2jgmRnnkcvRei7ugsF2R1DkTMf3dULMsPcV4yzhCmGsBU1";
+
+/// Same one used in `docker/vc-issuer-mock-core/env`.
+pub const ISSMOCK_PRIV_EC_P256: &str = r#"{"kty":"EC","crv":"P-256","d":"RjSTha8aKfKEUqd01-D0u8-hKkefvRP95v-Wuh-MM1U","x":"-Vcc_8CiiKIv5uhJaJbpegOLsMi2CTTgWckKEPHAGn8","y":"d-b4MMqK_-Axx2-8G-GZmFtI4HDKkyh_CZ41o4HxUiI"}"#;
+pub const ISSMOCK_PUB_EC_P256: &str = r#"{"kty":"EC","crv":"P-256","x":"-Vcc_8CiiKIv5uhJaJbpegOLsMi2CTTgWckKEPHAGn8","y":"d-b4MMqK_-Axx2-8G-GZmFtI4HDKkyh_CZ41o4HxUiI"}"#;
+pub const ISSMOCK_PRIV_EC_P256_DIDKEY: &str =
+    "did:key:zDnaehDHc81akNnf22Vdwyyiu9YZtMH8akj6hEcqTivxuH69U";
+
+/// Same one used in `docker/vc-issuer-mock-core/env`.
+pub const ISSMOCK_PRIV_EC_SECP256K1: &str = r#"{"kty":"EC","crv":"secp256k1","d":"OUGsE7T9R3BQCG_4HRFiQbz1MhLXCUssfgCPoK8_7MU","x":"YAbVknXFIKQmB5aZa0QwtsXkJZxXWODWpQ7T0iadUO4","y":"0Avwsv3SC2IVox3T1VhJZecECbYaTJT965mu8SL_75E"}"#;
+pub const ISSMOCK_PUB_EC_SECP256K1: &str = r#"{"kty":"EC","crv":"secp256k1","x":"YAbVknXFIKQmB5aZa0QwtsXkJZxXWODWpQ7T0iadUO4","y":"0Avwsv3SC2IVox3T1VhJZecECbYaTJT965mu8SL_75E"}"#;
+pub const ISSMOCK_PRIV_EC_SECP256K1_DIDKEY: &str =
+    "did:key:zQ3shm6xJxwxJrR76G6MwziUEWRF1sXqxWVRtdZfwwggsW7Uq";
@@ -52,6 +52,37 @@ pub mod vc_data_model_2_0_test_suite {
 }}"#;
 }
 
+/// Credentials in [VCDM v1.1](https://www.w3.org/TR/vc-data-model-1.1/).
+pub mod vc_data_model_1_1 {
+    use const_format::concatcp;
+
+    use crate::test_jwks::ISSMOCK_PRIV_OKP_ED25519_DIDKEY;
+
+    /// <https://www.w3.org/TR/vc-data-model-1.1/#example-a-simple-example-of-a-verifiable-credential>
+    pub const CREDENTIAL_OK: &str = concatcp!(
+        r#"
+{"credential": {
+  "@context": [
+    "https://www.w3.org/2018/credentials/v1",
+    "https://www.w3.org/2018/credentials/examples/v1"
+  ],
+  "id": "http://example.edu/credentials/1872",
+  "type": ["VerifiableCredential", "AlumniCredential"],
+  "issuer": ""#,
+        ISSMOCK_PRIV_OKP_ED25519_DIDKEY,
+        r#"",
+  "issuanceDate": "2010-01-01T19:23:24Z",
+  "credentialSubject": {
+    "id": "did:example:ebfeb1f712ebc6f1c276e12ec21",
+    "alumniOf": {
+      "id": "did:example:c276e12ec21ebfeb1f712ebc6f1",
+      "name": "Example University"
+    }
+  }
+}}"#
+    );
+}
+
 pub mod vc_issuer_api_openapi_spec {
     /// From <https://w3c-ccg.github.io/vc-api/issuer.html>.
     /// Modified to use v2.
@@ -91,7 +122,10 @@ pub mod vc_issuer_api_openapi_spec {
 pub mod misc {
     use const_format::concatcp;
 
-    use crate::test_jwks::{ISSMOCK_PRIV_EC_P384_DIDKEY, ISSMOCK_PRIV_OKP_ED25519_DIDKEY};
+    use crate::test_jwks::{
+        ISSMOCK_PRIV_EC_P256_DIDKEY, ISSMOCK_PRIV_EC_P384_DIDKEY, ISSMOCK_PRIV_EC_SECP256K1_DIDKEY,
+        ISSMOCK_PRIV_OKP_ED25519_DIDKEY,
+    };
 
     pub const ISSUER_DIDKEY_OKP_ED25519: &str = concatcp!(
         r#"
@@ -126,6 +160,42 @@ pub mod misc {
   "issuer": ""#,
         ISSMOCK_PRIV_EC_P384_DIDKEY,
         r#""
+}}"#
+    );
+
+    pub const ISSUER_DIDKEY_EC_P256: &str = concatcp!(
+        r#"
+{"credential": {
+  "@context": [
+    "https://www.w3.org/ns/credentials/v2"
+  ],
+  "type": [
+    "VerifiableCredential"
+  ],
+  "credentialSubject": {
+    "id": "did:example:subject"
+  },
+  "issuer": ""#,
+        ISSMOCK_PRIV_EC_P256_DIDKEY,
+        r#""
+}}"#
+    );
+
+    pub const ISSUER_DIDKEY_EC_SECP256K1: &str = concatcp!(
+        r#"
+{"credential": {
+  "@context": [
+    "https://www.w3.org/ns/credentials/v2"
+  ],
+  "type": [
+    "VerifiableCredential"
+  ],
+  "credentialSubject": {
+    "id": "did:example:subject"
+  },
+  "issuer": ""#,
+        ISSMOCK_PRIV_EC_SECP256K1_DIDKEY,
+        r#""
 }}"#
     );
 }
@@ -0,0 +1,177 @@
+//! [Securing with COSE](https://www.w3.org/TR/vc-jose-cose/#securing-with-cose).
+
+use anyhow::bail;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use ciborium::Value;
+
+use crate::{
+    endpoints::vc_api::res::{
+        vc_api_error::custom_problem_types::CustomProblemType, CoseCredential,
+        EnvelopedVerifiableCredential, VerifiableCredentialV2,
+    },
+    issuer_keys::SigningKey,
+    vcdm_v2::problem_details::ProblemDetails,
+};
+
+/// <https://www.w3.org/TR/vc-jose-cose/#vc-json-cose>
+pub(crate) const MEDIA_TYPE_VC_COSE: &str = "application/vc+cose";
+
+/// `COSE_Sign1` tag.
+///
+/// <https://www.rfc-editor.org/rfc/rfc9052#section-2>
+const COSE_SIGN1_TAG: u64 = 18;
+
+/// Secure the credential with COSE (`COSE_Sign1`), and envelope it in a `data:application/vc+cose;base64,...` URL.
+///
+/// The payload is the credential itself, and the `kid` header parameter refers to the verification method.
+pub(crate) fn create_enveloped_vc_with_cose(
+    credential: &VerifiableCredentialV2,
+    signing_key: &SigningKey,
+    kid: &str,
+) -> Result<CoseCredential, ProblemDetails> {
+    let cose_sign1 = sign_cose(credential, signing_key, kid).map_err(|e| {
+        ProblemDetails::new(
+            CustomProblemType::SignatureError,
+            "signature error".to_string(),
+            "failed to secure VC with COSE".to_string(),
+            e,
+        )
+    })?;
+
+    let enveloped = EnvelopedVerifiableCredential::new(format!(
+        "data:{};base64,{}",
+        MEDIA_TYPE_VC_COSE,
+        STANDARD.encode(&cose_sign1)
+    ));
+    Ok(CoseCredential {
+        enveloped,
+        cose_sign1,
+    })
+}
+
+fn sign_cose(
+    credential: &VerifiableCredentialV2,
+    signing_key: &SigningKey,
+    kid: &str,
+) -> anyhow::Result<Vec<u8>> {
+    // JWS signers produce signatures in the same format as COSE requires
+    // (e.g. `r || s` for ECDSA).
+    let signer = signing_key.to_jws_signer()?;
+    let alg = cose_algorithm(signer.algorithm().name())?;
+
+    // <https://www.rfc-editor.org/rfc/rfc9052#section-3.1>
+    let protected = to_cbor(&Value::Map(vec![
+        // alg
+        (Value::from(1), Value::from(alg)),
+        // content type
+        (Value::from(3), Value::from("application/vc")),
+        // kid
+        (Value::from(4), Value::Bytes(kid.as_bytes().to_vec())),
+        // typ (RFC 9596)
+        (Value::from(16), Value::from(MEDIA_TYPE_VC_COSE)),
+    ]))?;
+    let payload = serde_json::to_vec(credential)?;
+
+    // <https://www.rfc-editor.org/rfc/rfc9052#section-4.4>
+    let sig_structure = to_cbor(&Value::Array(vec![
+        Value::from("Signature1"),
+        Value::Bytes(protected.clone()),
+        Value::Bytes(vec![]), // external_aad
+        Value::Bytes(payload.clone()),
+    ]))?;
+    let signature = signer.sign(&sig_structure)?;
+
+    // <https://www.rfc-editor.org/rfc/rfc9052#section-4.2>
+    let cose_sign1 = Value::Tag(
+        COSE_SIGN1_TAG,
+        Box::new(Value::Array(vec![
+            Value::Bytes(protected),
+            Value::Map(vec![]),
+            Value::Bytes(payload),
+            Value::Bytes(signature),
+        ])),
+    );
+    to_cbor(&cose_sign1)
+}
+
+/// <https://www.iana.org/assignments/cose/cose.xhtml#algorithms>
+fn cose_algorithm(jws_algorithm: &str) -> anyhow::Result<i64> {
+    let alg = match jws_algorithm {
+        "ES256" => -7,
+        "ES384" => -35,
+        "ES256K" => -47,
+        "EdDSA" => -8,
+        "RS256" => -257,
+        alg => bail!("unsupported algorithm for COSE: {}", alg),
+    };
+    Ok(alg)
+}
+
+fn to_cbor(value: &Value) -> anyhow::Result<Vec<u8>> {
+    let mut bytes = vec![];
+    ciborium::into_writer(value, &mut bytes)?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use josekit::jws::{EdDSA, JwsVerifier};
+
+    use crate::{
+        endpoints::vc_api::req::IssueRequest,
+        test_jwks::{JWK_OKP_ED25519_PRIV, JWK_OKP_ED25519_PUB},
+        test_tracing::init_tracing,
+        test_vc_json::vc_data_model_2_0_test_suite::README_ALUMNI,
+        IssuerKeys,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_create_enveloped_vc_with_cose_success() {
+        init_tracing();
+
+        let req: IssueRequest = serde_json::from_str(README_ALUMNI).unwrap();
+        let issuer_keys = IssuerKeys::new(vec![JWK_OKP_ED25519_PRIV]);
+        let (signing_key, _) = issuer_keys.key_pairs().remove(0);
+
+        let credential = req.credential.as_v2().unwrap();
+        let cose =
+            create_enveloped_vc_with_cose(credential, &signing_key, "did:example:kid").unwrap();
+
+        let data = cose
+            .enveloped
+            .id
+            .strip_prefix("data:application/vc+cose;base64,")
+            .expect("id should be a data URL of application/vc+cose");
+        assert_eq!(STANDARD.decode(data).unwrap(), cose.cose_sign1);
+
+        let value: Value = ciborium::from_reader(cose.cose_sign1.as_slice()).unwrap();
+        let Value::Tag(COSE_SIGN1_TAG, array) = value else {
+            panic!("should be tagged as COSE_Sign1");
+        };
+        let Value::Array(array) = *array else {
+            panic!("COSE_Sign1 should be an array");
+        };
+        let [Value::Bytes(protected), _, Value::Bytes(payload), Value::Bytes(signature)] =
+            array.as_slice()
+        else {
+            panic!("unexpected COSE_Sign1 structure");
+        };
+
+        let payload: serde_json::Value = serde_json::from_slice(payload).unwrap();
+        assert_eq!(payload, serde_json::to_value(credential).unwrap());
+
+        let sig_structure = to_cbor(&Value::Array(vec![
+            Value::from("Signature1"),
+            Value::Bytes(protected.clone()),
+            Value::Bytes(vec![]),
+            Value::Bytes(serde_json::to_vec(credential).unwrap()),
+        ]))
+        .unwrap();
+        let verifier = EdDSA
+            .verifier_from_jwk(&josekit::jwk::Jwk::from_bytes(JWK_OKP_ED25519_PUB).unwrap())
+            .unwrap();
+        verifier.verify(&sig_structure, signature).unwrap();
+    }
+}
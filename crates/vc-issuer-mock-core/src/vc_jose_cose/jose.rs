@@ -0,0 +1,55 @@
+//! [Securing with JOSE](https://www.w3.org/TR/vc-jose-cose/#securing-with-jose).
+
+use josekit::jws::{self, JwsHeader};
+
+use crate::{
+    endpoints::vc_api::res::{
+        vc_api_error::custom_problem_types::CustomProblemType, EnvelopedVerifiableCredential,
+        VerifiableCredentialV2,
+    },
+    issuer_keys::SigningKey,
+    vcdm_v2::problem_details::ProblemDetails,
+};
+
+/// <https://www.w3.org/TR/vc-jose-cose/#vc-json-jwt>
+pub(crate) const MEDIA_TYPE_VC_JWT: &str = "application/vc+jwt";
+
+/// Secure the credential with JOSE, and envelope it in a `data:application/vc+jwt,...` URL.
+///
+/// The JWS payload is the credential itself, and the `kid` header refers to the verification method.
+pub(crate) fn create_enveloped_vc_with_jose(
+    credential: &VerifiableCredentialV2,
+    signing_key: &SigningKey,
+    kid: &str,
+) -> Result<EnvelopedVerifiableCredential, ProblemDetails> {
+    let jwt = sign_jose(credential, signing_key, kid).map_err(|e| {
+        ProblemDetails::new(
+            CustomProblemType::SignatureError,
+            "signature error".to_string(),
+            "failed to secure VC with JOSE".to_string(),
+            e,
+        )
+    })?;
+    Ok(EnvelopedVerifiableCredential::new(format!(
+        "data:{},{}",
+        MEDIA_TYPE_VC_JWT, jwt
+    )))
+}
+
+fn sign_jose(
+    credential: &VerifiableCredentialV2,
+    signing_key: &SigningKey,
+    kid: &str,
+) -> anyhow::Result<String> {
+    let signer = signing_key.to_jws_signer()?;
+    let payload = serde_json::to_vec(credential)?;
+
+    // <https://www.w3.org/TR/vc-jose-cose/#using-header-parameters>
+    let mut header = JwsHeader::new();
+    header.set_token_type("vc+jwt");
+    header.set_content_type("vc");
+    header.set_key_id(kid);
+
+    let jwt = jws::serialize_compact(&payload, &header, signer.as_ref())?;
+    Ok(jwt)
+}
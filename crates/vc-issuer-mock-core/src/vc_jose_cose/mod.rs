@@ -0,0 +1,9 @@
+//! Implements [Securing Verifiable Credentials using JOSE and COSE](https://www.w3.org/TR/vc-jose-cose/).
+//!
+//! Credentials secured in this module are returned as [`EnvelopedVerifiableCredential`](crate::endpoints::vc_api::res::EnvelopedVerifiableCredential)s.
+
+pub(crate) mod cose;
+pub(crate) mod jose;
+
+pub(crate) use cose::create_enveloped_vc_with_cose;
+pub(crate) use jose::create_enveloped_vc_with_jose;
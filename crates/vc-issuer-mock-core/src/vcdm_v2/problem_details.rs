@@ -3,7 +3,7 @@ use std::{error::Error as StdError, fmt};
 use serde::Serialize;
 use serde_with::{serde_as, DisplayFromStr};
 use ssi::{
-    claims::{data_integrity::InvalidCryptosuiteString, SignatureError},
+    claims::{data_integrity::InvalidCryptosuiteString, ProofValidationError, SignatureError},
     verification_methods::VerificationMethodResolutionError,
 };
 
@@ -111,6 +111,17 @@ impl From<SignatureError> for ProblemDetails {
     }
 }
 
+impl From<ProofValidationError> for ProblemDetails {
+    fn from(e: ProofValidationError) -> Self {
+        ProblemDetails::new(
+            PredefinedProblemType::CryptographicSecurityError,
+            "cryptographic security error".to_string(),
+            format!("failed to validate proof: {:?}", e),
+            e.into(),
+        )
+    }
+}
+
 pub trait ProblemType: fmt::Display + fmt::Debug + Send + Sync + 'static {
     fn url(&self) -> &'static str;
     fn code(&self) -> i32;
@@ -122,10 +133,8 @@ pub trait ProblemType: fmt::Display + fmt::Debug + Send + Sync + 'static {
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum PredefinedProblemType {
     ParsingError,
-    #[allow(dead_code)]
     CryptographicSecurityError,
     MalformedValueError,
-    #[allow(dead_code)]
     RangeError,
 }
 
@@ -6,22 +6,30 @@
 use std::borrow::Cow;
 
 use anyhow::anyhow;
+use serde_json::Value;
 use ssi::{
     claims::vc::syntax::{IdOr, IdentifiedObject},
     dids::{AnyDidMethod, DIDResolver, VerificationMethodDIDResolver, DID},
-    json_ld::{iref, IriBuf},
+    json_ld::{
+        iref::{self, UriBuf},
+        IriBuf,
+    },
     prelude::AnySuite,
     verification_methods::{
         AnyMethod, GenericVerificationMethod, InvalidVerificationMethod, JsonWebKey2020,
         MaybeJwkVerificationMethod, ReferenceOrOwned, ReferenceOrOwnedRef, ResolutionOptions,
-        VerificationMethodResolutionError, VerificationMethodResolver,
+        VerificationMethod as _, VerificationMethodResolutionError, VerificationMethodResolver,
     },
     JWK,
 };
 
 use crate::{
+    cryptosuite::{invalid_cryptosuite_error, RequestedCryptosuite},
+    did_web::DidWeb,
     endpoints::vc_api::res::vc_api_error::custom_problem_types::CustomProblemType,
-    vcdm_v2::problem_details::ProblemDetails, IssuerKeys,
+    issuer_keys::{SigningKey, VerificationKey},
+    vcdm_v2::problem_details::ProblemDetails,
+    IssuerKeys,
 };
 
 /// A verification method.
@@ -46,10 +54,56 @@ impl VerificationMethod {
             })
     }
 
-    pub(crate) fn try_to_suite(&self) -> Result<AnySuite, ProblemDetails> {
+    /// Find the issuer's signing key corresponding to this verification method.
+    pub(crate) fn find_signing_key(
+        &self,
+        issuer_keys: &IssuerKeys,
+    ) -> Result<SigningKey, ProblemDetails> {
+        let jwk = self.try_to_jwk()?;
+        VerificationKey::try_from(&jwk)
+            .ok()
+            .and_then(|vk| issuer_keys.find_signing_key_from(&vk))
+            .ok_or_else(|| {
+                ProblemDetails::new(
+                    CustomProblemType::VerificationMethodResolutionError,
+                    "verification method resolution error".to_string(),
+                    "no issuer key found for the resolved verification method".to_string(),
+                    anyhow!(
+                        "No issuer key found for the verification method: {:?}",
+                        self.0
+                    ),
+                )
+            })
+    }
+
+    /// Select a cryptographic suite to sign with this verification method.
+    ///
+    /// If `requested` is `None`, a suite is picked from the key type.
+    /// Otherwise, both the key and the verification method type must be able to serve the requested cryptosuite.
+    pub(crate) fn try_to_suite(
+        &self,
+        requested: Option<RequestedCryptosuite>,
+    ) -> Result<AnySuite, ProblemDetails> {
         let any_method = ReferenceOrOwned::Owned(self.0.clone());
         let jwk = self.try_to_jwk()?;
 
+        if let Some(requested) = requested {
+            if !requested.supports_key(&jwk) {
+                return Err(invalid_cryptosuite_error(format!(
+                    "The resolved issuer key cannot be used with the cryptosuite `{}`",
+                    requested
+                )));
+            }
+            // DataIntegrityProof suites express their public keys as Multikey.
+            if !self.is_multikey() {
+                return Err(invalid_cryptosuite_error(format!(
+                    "The resolved verification method is not a Multikey, and cannot be used with the cryptosuite `{}`",
+                    requested
+                )));
+            }
+            return Ok(requested.to_any_suite());
+        }
+
         AnySuite::pick(&jwk, Some(&any_method)).ok_or_else(|| {
             ProblemDetails::new(
                 CustomProblemType::InvalidCryptosuiteError,
@@ -63,22 +117,28 @@ impl VerificationMethod {
         })
     }
 
-    /// # Panics
-    ///
-    /// If the verification method is not supported.
+    /// Whether Data Integrity cryptosuites (`DataIntegrityProof`) can be used with this method.
+    pub(crate) fn is_multikey(&self) -> bool {
+        matches!(self.0, AnyMethod::Multikey(_))
+    }
+
+    /// `id` of the verification method, whatever its type is
+    /// (e.g. `EcdsaSecp256k1VerificationKey2019` for secp256k1 `did:key`s).
     pub(crate) fn to_id_iri(&self) -> IriBuf {
-        match &self.0 {
-            AnyMethod::Multikey(multikey) => multikey.id.clone(),
-            AnyMethod::JsonWebKey2020(jwk) => jwk.id.clone(),
-            _ => unimplemented!("Unsupported verification method type: {:?}", self.0),
-        }
+        self.0.id().to_owned()
     }
 }
 
-/// Verification method resolver. Currently supports `did:key` or JWK methods.
+/// DID of a DID URL, without its fragment.
+fn did_of(did_url: &str) -> &str {
+    did_url.split_once('#').map_or(did_url, |(did, _)| did)
+}
+
+/// Verification method resolver. Currently supports `did:key`, `did:jwk`, the mock's own `did:web` or JWK methods.
 pub(crate) struct CustomVerificationMethodResolver {
     did_resolver: VerificationMethodDIDResolver<AnyDidMethod, AnyMethod>,
     issuer_keys: IssuerKeys,
+    did_web: Option<DidWeb>,
 }
 
 impl CustomVerificationMethodResolver {
@@ -89,9 +149,16 @@ impl CustomVerificationMethodResolver {
         Self {
             did_resolver,
             issuer_keys,
+            did_web: None,
         }
     }
 
+    /// Resolve the `did:web`s hosted by the mock locally, instead of fetching their DID documents.
+    pub(crate) fn with_did_web(mut self, did_web: Option<DidWeb>) -> Self {
+        self.did_web = did_web;
+        self
+    }
+
     pub(crate) async fn resolve(
         &self,
         issuer: &IdOr<IdentifiedObject>,
@@ -102,6 +169,73 @@ impl CustomVerificationMethodResolver {
         Ok(VerificationMethod(vm_method.into_owned()))
     }
 
+    /// Resolve the verification method explicitly chosen to sign with (e.g. `verificationMethod` option).
+    ///
+    /// Unlike [`Self::resolve`], it never falls back to an arbitrary issuer key.
+    /// If the issuer is a DID, the method must belong to it. URL issuers accept any method.
+    ///
+    /// # Errors
+    ///
+    /// `VerificationMethodResolutionError` if the method is not of the issuer DID, cannot be resolved,
+    /// or the issuer keys hold no private key for it.
+    pub(crate) async fn resolve_method(
+        &self,
+        issuer: &IdOr<IdentifiedObject>,
+        method: &iref::Iri,
+    ) -> Result<VerificationMethod, ProblemDetails> {
+        let issuer = issuer.id().as_str();
+        if issuer.starts_with("did:") && did_of(method.as_str()) != did_of(issuer) {
+            return Err(ProblemDetails::new(
+                CustomProblemType::VerificationMethodResolutionError,
+                "verification method resolution error".to_string(),
+                "verification method does not belong to the issuer".to_string(),
+                anyhow!(
+                    "The verification method `{}` does not belong to the issuer `{}`",
+                    method,
+                    issuer
+                ),
+            ));
+        }
+
+        let vm_method = match self.resolve_issuer_did_jwk(method) {
+            Some(vm_method) => Cow::Owned(vm_method),
+            None => match self.resolve_hosted_did_web(method) {
+                Some(vm_method) => vm_method?,
+                None => {
+                    self.did_resolver
+                        .resolve_verification_method_with(
+                            None,
+                            Some(ReferenceOrOwnedRef::Reference(method)),
+                            ResolutionOptions::default(),
+                        )
+                        .await?
+                }
+            },
+        };
+
+        let vm = VerificationMethod(vm_method.into_owned());
+        vm.find_signing_key(&self.issuer_keys)?;
+        Ok(vm)
+    }
+
+    /// Resolve a verification method of someone other than the issuer (e.g. a holder) from its DID URL.
+    ///
+    /// Unlike [`Self::resolve`], it never falls back to the issuer keys.
+    pub(crate) async fn resolve_did_url(
+        &self,
+        did_url: &iref::Iri,
+    ) -> Result<VerificationMethod, ProblemDetails> {
+        let vm_method = self
+            .did_resolver
+            .resolve_verification_method_with(
+                None,
+                Some(ReferenceOrOwnedRef::Reference(did_url)),
+                ResolutionOptions::default(),
+            )
+            .await?;
+        Ok(VerificationMethod(vm_method.into_owned()))
+    }
+
     async fn resolve_by_method(
         &self,
         issuer: Option<&iref::Iri>,
@@ -109,6 +243,12 @@ impl CustomVerificationMethodResolver {
         options: ResolutionOptions,
     ) -> Result<Cow<AnyMethod>, VerificationMethodResolutionError> {
         if method.id().scheme().as_str() == "did" {
+            if let Some(method) = self.resolve_issuer_did_jwk(method.id()) {
+                return Ok(Cow::Owned(method));
+            }
+            if let Some(method) = self.resolve_hosted_did_web(method.id()) {
+                return method;
+            }
             if let Ok(method) = self
                 .did_resolver
                 .resolve_verification_method_with(issuer, Some(method), options)
@@ -125,6 +265,12 @@ impl CustomVerificationMethodResolver {
         &self,
         issuer: &iref::Iri,
     ) -> Result<Cow<AnyMethod>, VerificationMethodResolutionError> {
+        if let Some(method) = self.resolve_issuer_did_jwk(issuer) {
+            return Ok(Cow::Owned(method));
+        }
+        if let Some(method) = self.resolve_hosted_did_web(issuer) {
+            return method;
+        }
         if let Ok(did) = DID::new(issuer) {
             let output = self.did_resolver.resolve(did).await.map_err(|e| {
                 VerificationMethodResolutionError::InternalError(format!(
@@ -150,6 +296,56 @@ impl CustomVerificationMethodResolver {
         }
     }
 
+    /// Resolve a `did:jwk` of an issuer key, with or without the `#0` fragment, to its only verification method.
+    ///
+    /// Returns `None` if `did_url` is not a `did:jwk` of any issuer key.
+    /// `did:jwk`s of others are resolved by the DID resolver.
+    fn resolve_issuer_did_jwk(&self, did_url: &iref::Iri) -> Option<AnyMethod> {
+        let vk = VerificationKey::from_did_jwk(did_url.as_str())?;
+        self.issuer_keys.find_signing_key_from(&vk)?;
+
+        let did = did_url
+            .as_str()
+            .strip_suffix("#0")
+            .unwrap_or(did_url.as_str());
+        Some(AnyMethod::JsonWebKey2020(JsonWebKey2020 {
+            id: IriBuf::new(format!("{}#0", did)).ok()?,
+            controller: UriBuf::new(did.as_bytes().to_vec()).ok()?,
+            public_key: Box::new(JWK::from(&vk)),
+        }))
+    }
+
+    /// Returns `None` if `did_url` is not a `did:web` hosted by the mock.
+    fn resolve_hosted_did_web(
+        &self,
+        did_url: &iref::Iri,
+    ) -> Option<Result<Cow<AnyMethod>, VerificationMethodResolutionError>> {
+        let did_web = self.did_web.as_ref()?;
+        let did = did_url.as_str().split('#').next().unwrap_or_default();
+        if !did_web.is_hosted(did) {
+            return None;
+        }
+
+        let vm = did_web
+            .verification_method(did_url.as_str(), &self.issuer_keys)
+            .ok_or_else(|| {
+                VerificationMethodResolutionError::InternalError(format!(
+                    "No verification method `{}` in the hosted DID document",
+                    did_url
+                ))
+            })
+            .and_then(|vm: Value| {
+                let vm: GenericVerificationMethod = serde_json::from_value(vm).map_err(|e| {
+                    VerificationMethodResolutionError::InternalError(format!(
+                        "Invalid verification method in the hosted DID document: {}",
+                        e
+                    ))
+                })?;
+                Ok(Cow::Owned(AnyMethod::try_from(vm)?))
+            });
+        Some(vm)
+    }
+
     fn resolve_to_jwk2020(
         &self,
         method_or_issuer_id: &iref::Iri,
@@ -1,8 +1,10 @@
 // Before running the tests, you can specify a BASE_URL, such as
 // BASE_URL=http://localhost:40443/zDdfsdfs npm test
 const baseUrl = process.env.BASE_URL || "http://localhost:8000/vc-issuer-mock";
-const didkitHttpBaseUrl =
-  process.env.DIDKIT_HTTP_BASE_URL || "http://localhost:8000/didkit-http";
+
+// did:key of ISSMOCK_PRIV_OKP_ED25519 in docker/vc-issuer-mock-core/env,
+// the key the mock signs and verifies with.
+const mockDidKey = "did:key:z6Mkj6a5Em4zUEqJMdmSjyUk3dBz5SEt2xtjtUmfmunTxS62";
 
 module.exports = {
   settings: {},
@@ -12,37 +14,18 @@ module.exports = {
       implementation: "vc-issuer-mock-core (local test)",
       issuers: [
         {
-          id: "did:key:z6Mkj6a5Em4zUEqJMdmSjyUk3dBz5SEt2xtjtUmfmunTxS62",
+          id: mockDidKey,
           endpoint: `${baseUrl}/credentials/issue`,
           tags: ["vc2.0"],
         },
       ],
 
-      // Settings from: <https://github.com/spruceid/didkit-http/blob/a10928734de046074b3dbde05bb4c3db02ce5d10/tests/localConfig.cjs#L90-L113>
+      // Only credentials with an embedded Data Integrity proof are verified (no "JWT" tag).
+      // Presentations and DID resolution are not tested (no vpVerifiers and didResolvers).
       verifiers: [
         {
-          id: "https://spruceid.com",
-          endpoint: `${didkitHttpBaseUrl}/credentials/verify`,
-          supports: {
-            vc: ["1.1", "2.0"],
-          },
-          supportedEcdsaKeyTypes: ["P-256", "P-384"],
-          tags: [
-            "vc-api",
-            "Ed25519Signature2020",
-            "JWT",
-            "ecdsa-rdfc-2019",
-            "ecdsa-sd-2023",
-            "eddsa-rdfc-2022",
-            "bbs-2023",
-            "vc2.0",
-          ],
-        },
-      ],
-      vpVerifiers: [
-        {
-          id: "https://spruceid.com",
-          endpoint: `${didkitHttpBaseUrl}/presentations/verify`,
+          id: mockDidKey,
+          endpoint: `${baseUrl}/credentials/verify`,
           supports: {
             vc: ["1.1", "2.0"],
           },
@@ -50,7 +33,6 @@ module.exports = {
           tags: [
             "vc-api",
             "Ed25519Signature2020",
-            "JWT",
             "ecdsa-rdfc-2019",
             "ecdsa-sd-2023",
             "eddsa-rdfc-2022",
@@ -59,13 +41,6 @@ module.exports = {
           ],
         },
       ],
-      didResolvers: [
-        {
-          id: "https://spruceid.com",
-          endpoint: `${didkitHttpBaseUrl}/identifiers`,
-          tags: ["did-key"],
-        },
-      ],
     },
   ],
 };
@@ -2,9 +2,6 @@
 
 nginx &
 
-# runs on port 3000
-/usr/local/bin/didkit-http &
-
 # Run the vc-issuer-mock-core server
 ## Read signing keys from files the Render platform places.
 for secret in ISSMOCK_PRIV_EC_P384 ISSMOCK_PRIV_OKP_ED25519; do